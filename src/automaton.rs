@@ -0,0 +1,161 @@
+use super::Node;
+
+/// A finite automaton over bytes that a tree can be intersected
+/// with, see [`Art::search`](struct.Art.html#method.search).
+///
+/// The tree feeds every key byte by byte, starting from
+/// `start`, and yields the keys whose final state `is_match`.
+/// Subtrees are skipped as soon as `can_match` says no key
+/// below them can match anymore.
+pub trait Automaton {
+    type State: Clone;
+
+    /// The state before any byte was seen.
+    fn start(&self) -> Self::State;
+
+    /// The state after seeing `byte` in `state`.
+    fn step(&self, state: &Self::State, byte: u8) -> Self::State;
+
+    /// Whether a key ending in `state` is accepted.
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    /// Whether any continuation of a key in `state` could still
+    /// be accepted.
+    fn can_match(&self, _state: &Self::State) -> bool {
+        true
+    }
+}
+
+// accepts the keys that are at most `max_edits` byte edits away
+// from `key`. a state holds the edit distances between the
+// bytes seen so far and every prefix of `key`.
+pub(crate) struct Levenshtein<'k> {
+    pub(crate) key: &'k [u8],
+    pub(crate) max_edits: usize,
+}
+
+impl<'k> Automaton for Levenshtein<'k> {
+    type State = Vec<usize>;
+
+    fn start(&self) -> Vec<usize> {
+        (0..=self.key.len()).collect()
+    }
+
+    fn step(&self, row: &Vec<usize>, byte: u8) -> Vec<usize> {
+        let mut next = Vec::with_capacity(row.len());
+        next.push(row[0] + 1);
+        for j in 1..row.len() {
+            let substitution =
+                row[j - 1] + (self.key[j - 1] != byte) as usize;
+            let edit = row[j].min(next[j - 1]) + 1;
+            next.push(substitution.min(edit));
+        }
+        next
+    }
+
+    fn is_match(&self, row: &Vec<usize>) -> bool {
+        row[self.key.len()] <= self.max_edits
+    }
+
+    // longer keys can only need more edits
+    fn can_match(&self, row: &Vec<usize>) -> bool {
+        row.iter().any(|edits| *edits <= self.max_edits)
+    }
+}
+
+// accepts the keys matching a pattern in which `?` stands for
+// any byte and `*` for any run of bytes. a state holds every
+// position in the pattern that the bytes seen so far can lead
+// to, which is empty once none can.
+pub(crate) struct Glob<'p> {
+    pub(crate) pattern: &'p [u8],
+}
+
+impl<'p> Glob<'p> {
+    // adds the positions past every `*` that can match nothing
+    fn close(&self, mut positions: Vec<usize>) -> Vec<usize> {
+        let mut i = 0;
+        while i < positions.len() {
+            let pos = positions[i];
+            if self.pattern.get(pos) == Some(&b'*')
+                && !positions.contains(&(pos + 1))
+            {
+                positions.push(pos + 1);
+            }
+            i += 1;
+        }
+        positions.sort_unstable();
+        positions
+    }
+}
+
+impl<'p> Automaton for Glob<'p> {
+    type State = Vec<usize>;
+
+    fn start(&self) -> Vec<usize> {
+        self.close(vec![0])
+    }
+
+    fn step(&self, positions: &Vec<usize>, byte: u8) -> Vec<usize> {
+        let mut next = vec![];
+        for &pos in positions {
+            let advanced = match self.pattern.get(pos) {
+                Some(b'*') => pos,
+                Some(b'?') => pos + 1,
+                Some(p) if *p == byte => pos + 1,
+                _ => continue,
+            };
+            if !next.contains(&advanced) {
+                next.push(advanced);
+            }
+        }
+        self.close(next)
+    }
+
+    fn is_match(&self, positions: &Vec<usize>) -> bool {
+        positions.contains(&self.pattern.len())
+    }
+
+    fn can_match(&self, positions: &Vec<usize>) -> bool {
+        !positions.is_empty()
+    }
+}
+
+/// Walks the tree below `root` in key order along with
+/// `automaton`, returning the accepted entries with their final
+/// states.
+pub(crate) fn intersect<'a, T, M, A>(
+    root: &'a Node<T, M>,
+    automaton: &A,
+) -> Vec<(Vec<u8>, &'a T, A::State)>
+where
+    A: Automaton,
+{
+    let mut found = vec![];
+    let mut stack = vec![(vec![], None, root, automaton.start())];
+
+    while let Some((mut key, byte, node, mut state)) = stack.pop() {
+        for &b in byte.iter().chain(node.prefix().iter()) {
+            if !automaton.can_match(&state) {
+                break;
+            }
+            state = automaton.step(&state, b);
+            key.push(b);
+        }
+        if !automaton.can_match(&state) {
+            continue;
+        }
+
+        if let Some(value) = node.value() {
+            if automaton.is_match(&state) {
+                found.push((key.clone(), value, state.clone()));
+            }
+        }
+        for (byte, child) in node.children().into_iter().rev() {
+            let child = unsafe { &*child };
+            stack.push((key.clone(), Some(byte), child, state.clone()));
+        }
+    }
+
+    found
+}
@@ -0,0 +1,171 @@
+use super::{Art, CartError, NormalizedArt, Normalizer};
+
+/// The number of children each node variant holds before it
+/// grows into the next larger one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Thresholds {
+    pub(crate) node4: usize,
+    pub(crate) node16: usize,
+    pub(crate) node48: usize,
+    pub(crate) use_node48: bool,
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds {
+            node4: 4,
+            node16: 16,
+            node48: 48,
+            use_node48: true,
+        }
+    }
+}
+
+/// Tunes when the nodes of an `Art` grow before constructing it.
+///
+/// The defaults give the classic 4/16/48/256 layout.
+///
+/// ```
+/// let art: cart::Art<u8> = cart::ArtBuilder::new()
+///     .node16_capacity(8)
+///     .node48(false)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ArtBuilder {
+    thresholds: Thresholds,
+    intern_prefixes: bool,
+    root_capacity: usize,
+}
+
+impl ArtBuilder {
+    pub fn new() -> ArtBuilder {
+        ArtBuilder::default()
+    }
+
+    /// Number of children a Node4 holds before growing into
+    /// a Node16. Must be between 1 and 4.
+    pub fn node4_capacity(self, capacity: usize) -> ArtBuilder {
+        self.try_node4_capacity(capacity)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `node4_capacity`, but returns an error for a
+    /// capacity out of range instead of panicking.
+    pub fn try_node4_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Result<ArtBuilder, CartError> {
+        check_capacity("Node4", capacity, 4)?;
+        self.thresholds.node4 = capacity;
+        Ok(self)
+    }
+
+    /// Number of children a Node16 holds before growing into
+    /// a Node48 (or a Node256). Must be between 1 and 16.
+    pub fn node16_capacity(self, capacity: usize) -> ArtBuilder {
+        self.try_node16_capacity(capacity)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `node16_capacity`, but returns an error for a
+    /// capacity out of range instead of panicking.
+    pub fn try_node16_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Result<ArtBuilder, CartError> {
+        check_capacity("Node16", capacity, 16)?;
+        self.thresholds.node16 = capacity;
+        Ok(self)
+    }
+
+    /// Number of children a Node48 holds before growing into
+    /// a Node256. Must be between 1 and 48.
+    pub fn node48_capacity(self, capacity: usize) -> ArtBuilder {
+        self.try_node48_capacity(capacity)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `node48_capacity`, but returns an error for a
+    /// capacity out of range instead of panicking.
+    pub fn try_node48_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Result<ArtBuilder, CartError> {
+        check_capacity("Node48", capacity, 48)?;
+        self.thresholds.node48 = capacity;
+        Ok(self)
+    }
+
+    /// Whether Node16s grow into Node48s. When disabled they
+    /// grow straight into Node256s.
+    pub fn node48(mut self, enabled: bool) -> ArtBuilder {
+        self.thresholds.use_node48 = enabled;
+        self
+    }
+
+    /// Whether node prefixes are copied into chunks shared by
+    /// the whole tree instead of each node allocating its own.
+    /// This saves many small allocations when there are lots of
+    /// short prefixes. See `Art::compact` for reclaiming the
+    /// space of prefixes that were replaced.
+    pub fn intern_prefixes(mut self, enabled: bool) -> ArtBuilder {
+        self.intern_prefixes = enabled;
+        self
+    }
+
+    /// Number of children the root is expected to have. The
+    /// root starts out as the smallest variant that holds them,
+    /// which saves growing it one step at a time when the keys
+    /// are known to fan out widely at their first byte.
+    pub fn root_capacity(mut self, children: usize) -> ArtBuilder {
+        self.root_capacity = children;
+        self
+    }
+
+    pub fn build<T>(self) -> Art<T> {
+        self.build_with_metadata()
+    }
+
+    /// Builds a tree that stores and looks up every key in the
+    /// form `normalize` turns it into.
+    pub fn build_normalized<T>(
+        self,
+        normalize: Normalizer,
+    ) -> NormalizedArt<T> {
+        NormalizedArt::with_art(self.build(), normalize)
+    }
+
+    /// Builds a tree that keeps metadata of type `M` in every
+    /// node. See `Art::update_metadata`.
+    pub fn build_with_metadata<T, M>(self) -> Art<T, M>
+    where
+        M: Default,
+    {
+        let art = Art::with_thresholds(self.thresholds)
+            .with_root_capacity(self.root_capacity);
+
+        if self.intern_prefixes {
+            art.with_interner()
+        } else {
+            art
+        }
+    }
+}
+
+// a node variant holds between one and `max` children
+fn check_capacity(
+    variant: &'static str,
+    capacity: usize,
+    max: usize,
+) -> Result<(), CartError> {
+    if (1..=max).contains(&capacity) {
+        Ok(())
+    } else {
+        Err(CartError::InvalidCapacity {
+            variant,
+            capacity,
+            max,
+        })
+    }
+}
@@ -0,0 +1,189 @@
+use std::iter::FromIterator;
+
+use super::{common_prefix_len, Art, CartError, Node};
+
+impl<T> Art<T> {
+    /// Builds a tree from entries that are sorted by key, without
+    /// copying the keys up front. Only the bytes that end up in
+    /// node prefixes are copied. If a key occurs more than once
+    /// the last value wins, like with repeated calls to `set`.
+    ///
+    /// The tree is built bottom-up, so every node is created with
+    /// its final size instead of growing along the way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not sorted.
+    pub fn from_sorted_refs<'a, I>(pairs: I) -> Art<T>
+    where
+        I: IntoIterator<Item = (&'a [u8], T)>,
+    {
+        Art::try_from_sorted_refs(pairs)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `from_sorted_refs`, but returns an error for keys
+    /// that are not sorted instead of panicking.
+    pub fn try_from_sorted_refs<'a, I>(
+        pairs: I,
+    ) -> Result<Art<T>, CartError>
+    where
+        I: IntoIterator<Item = (&'a [u8], T)>,
+    {
+        let mut entries: Vec<(&[u8], Option<T>)> =
+            pairs.into_iter().map(|(k, v)| (k, Some(v))).collect();
+
+        let unsorted = (1..entries.len())
+            .find(|&i| entries[i - 1].0 > entries[i].0);
+        if let Some(index) = unsorted {
+            return Err(CartError::UnsortedKeys { index });
+        }
+
+        let mut art = Art::default();
+        if entries.is_empty() {
+            return Ok(art);
+        }
+
+        // how many distinct keys the entries before each index
+        // have, which gives every node its count
+        let mut distinct = vec![0];
+        for i in 0..entries.len() {
+            let last = i + 1 == entries.len()
+                || entries[i].0 != entries[i + 1].0;
+            distinct.push(distinct[i] + last as usize);
+        }
+
+        // ranges of entries that still need a node, along with
+        // the depth their keys are consumed to and the slot the
+        // node goes into. the root is the only node without a
+        // parent.
+        let root_parent: Option<(*mut Node<T>, u8)> = None;
+        let mut stack = vec![(0, entries.len(), 0, root_parent)];
+
+        while let Some((start, end, depth, parent)) = stack.pop() {
+            let first = entries[start].0;
+            let last = entries[end - 1].0;
+            let split = depth
+                + common_prefix_len(&first[depth..], &last[depth..]);
+
+            // keys ending right here sort first, the last one of
+            // them wins.
+            let mut children_start = start;
+            let mut value = None;
+            while children_start < end
+                && entries[children_start].0.len() == split
+            {
+                value = entries[children_start].1.take();
+                children_start += 1;
+            }
+
+            let mut groups = vec![];
+            let mut group_start = children_start;
+            for i in children_start..end {
+                if i + 1 == end
+                    || entries[i + 1].0[split] != entries[i].0[split]
+                {
+                    groups.push((
+                        entries[i].0[split],
+                        group_start,
+                        i + 1,
+                    ));
+                    group_start = i + 1;
+                }
+            }
+
+            let mut node =
+                Node::with_capacity(groups.len(), &art.thresholds);
+            node.set_prefix(first[depth..split].into());
+            *node.count_mut() = distinct[end] - distinct[start];
+            if let Some(value) = value {
+                node.set_value(value);
+                art.len += 1;
+            }
+
+            let node_ptr = match parent {
+                None => {
+                    unsafe { *art.root = node };
+                    art.root
+                }
+                Some((parent, byte)) => {
+                    let node_ptr = Box::into_raw(Box::new(node));
+                    unsafe { (*parent).set_child(byte, node_ptr) };
+                    node_ptr
+                }
+            };
+
+            for (byte, group_start, group_end) in groups {
+                stack.push((
+                    group_start,
+                    group_end,
+                    split + 1,
+                    Some((node_ptr, byte)),
+                ));
+            }
+        }
+
+        Ok(art)
+    }
+
+    /// Builds a tree from entries that are sorted by key. See
+    /// `from_sorted_refs` for building from borrowed keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not sorted.
+    pub fn bulk_load<I>(pairs: I) -> Art<T>
+    where
+        I: IntoIterator<Item = (Vec<u8>, T)>,
+    {
+        let (keys, values): (Vec<Vec<u8>>, Vec<T>) =
+            pairs.into_iter().unzip();
+
+        Art::from_sorted_refs(keys.iter().map(|k| &**k).zip(values))
+    }
+}
+
+impl<T> FromIterator<(Vec<u8>, T)> for Art<T> {
+    fn from_iter<I>(pairs: I) -> Art<T>
+    where
+        I: IntoIterator<Item = (Vec<u8>, T)>,
+    {
+        let mut art = Art::default();
+        art.extend(pairs);
+        art
+    }
+}
+
+/// Sets every key to its value, later pairs winning over
+/// earlier ones. While the keys come in sorted order each
+/// insertion starts where the previous one left the cursor,
+/// instead of at the root.
+impl<T, M> Extend<(Vec<u8>, T)> for Art<T, M>
+where
+    M: Default,
+{
+    fn extend<I>(&mut self, pairs: I)
+    where
+        I: IntoIterator<Item = (Vec<u8>, T)>,
+    {
+        let mut cursor = self.cursor_mut();
+        let mut last = vec![];
+        let mut sorted = true;
+
+        for (key, value) in pairs {
+            sorted = sorted && key >= last;
+            if !sorted {
+                cursor.insert(key, value);
+                continue;
+            }
+
+            // the descent starts on the path to the previous key,
+            // and moving the cursor onto this one only climbs out
+            // of that path as far as the keys differ.
+            last.clear();
+            last.extend_from_slice(&key);
+            cursor.insert(key, value);
+            cursor.seek(&last);
+        }
+    }
+}
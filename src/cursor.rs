@@ -0,0 +1,408 @@
+use super::{Art, Node};
+
+/// A position among the entries of an `Art`, which can be
+/// moved to nearby keys without descending from the root again.
+///
+/// The cursor keeps the nodes along the path to its entry, so
+/// `next`, `prev` and `seek` only climb as far as they need to.
+/// Once it moved past either end of the tree the cursor points
+/// nowhere, and only `seek` brings it back.
+///
+/// Created by [`Art::cursor`](struct.Art.html#method.cursor).
+pub struct Cursor<'a, T: 'a, M: 'a = ()> {
+    root: &'a Node<T, M>,
+    path: Path<T, M>,
+}
+
+impl<'a, T, M> Cursor<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> Cursor<'a, T, M> {
+        let mut cursor = Cursor {
+            root,
+            path: Path::default(),
+        };
+        cursor.seek(&[]);
+        cursor
+    }
+
+    /// The key of the entry the cursor points at.
+    pub fn key(&self) -> Option<&[u8]> {
+        self.path.key()
+    }
+
+    /// The value of the entry the cursor points at.
+    pub fn value(&self) -> Option<&'a T> {
+        self.path.node().and_then(|node| unsafe { (*node).value() })
+    }
+
+    /// Moves to the first entry whose key is not below `key`,
+    /// and returns whether there is one. Only the part of the
+    /// path that `key` does not share with the current entry
+    /// is walked again.
+    pub fn seek(&mut self, key: &[u8]) -> bool {
+        let root = self.root as *const Node<T, M> as *mut Node<T, M>;
+        self.path.seek(root, key)
+    }
+
+    /// Moves to the next entry in key order, and returns
+    /// whether there is one.
+    // a cursor moves rather than yields, so it isn't an iterator
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> bool {
+        self.path.next()
+    }
+
+    /// Moves to the previous entry in key order, and returns
+    /// whether there is one.
+    pub fn prev(&mut self) -> bool {
+        self.path.prev()
+    }
+}
+
+/// A cursor that can also change the tree around it.
+///
+/// Removing the current entry moves the cursor on to the next
+/// one. Inserting leaves it on the entry it pointed at, or
+/// nowhere if it pointed nowhere. Nodes that grow, shrink,
+/// split or merge stay where they are and only nodes that
+/// became empty are freed, so either change only repairs the
+/// part of the path it could have touched.
+///
+/// Created by
+/// [`Art::cursor_mut`](struct.Art.html#method.cursor_mut).
+pub struct CursorMut<'a, T: 'a, M: 'a = ()> {
+    art: &'a mut Art<T, M>,
+    path: Path<T, M>,
+    // holds the key to return to after a change, reused
+    // across changes
+    scratch: Vec<u8>,
+}
+
+impl<'a, T, M> CursorMut<'a, T, M>
+where
+    M: Default,
+{
+    pub(crate) fn new(art: &'a mut Art<T, M>) -> CursorMut<'a, T, M> {
+        let mut cursor = CursorMut {
+            art,
+            path: Path::default(),
+            scratch: vec![],
+        };
+        cursor.seek(&[]);
+        cursor
+    }
+
+    /// The key of the entry the cursor points at.
+    pub fn key(&self) -> Option<&[u8]> {
+        self.path.key()
+    }
+
+    /// The value of the entry the cursor points at.
+    pub fn value(&self) -> Option<&T> {
+        self.path.node().and_then(|node| unsafe { (*node).value() })
+    }
+
+    /// Mutable access to the value the cursor points at.
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        self.path
+            .node()
+            .and_then(|node| unsafe { (*node).value_mut() })
+    }
+
+    /// Moves to the first entry whose key is not below `key`,
+    /// and returns whether there is one.
+    pub fn seek(&mut self, key: &[u8]) -> bool {
+        self.path.seek(self.art.root, key)
+    }
+
+    /// Moves to the next entry in key order, and returns
+    /// whether there is one.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> bool {
+        self.path.next()
+    }
+
+    /// Moves to the previous entry in key order, and returns
+    /// whether there is one.
+    pub fn prev(&mut self) -> bool {
+        self.path.prev()
+    }
+
+    /// Removes the entry the cursor points at and returns its
+    /// value. The cursor moves on to the next entry.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.path.node()?;
+        let value = unsafe { (*node).take_value() };
+
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&self.path.key);
+        let key = &self.scratch;
+        self.art.path.clear();
+        self.art.path.extend(self.path.nodes.iter().map(|&(ptr, len)| {
+            let start = len - unsafe { (*ptr).prefix().len() };
+            let byte = if start > 0 { key[start - 1] } else { 0 };
+            (ptr, byte)
+        }));
+        self.art.counted(-1);
+        self.art.prune();
+
+        // pruning changes at most the node and its parent, the
+        // rest of the path still leads towards the next entry.
+        let kept = self.path.nodes.len().saturating_sub(2);
+        self.path.truncate(kept);
+        self.path.seek(self.art.root, &self.scratch);
+
+        value
+    }
+
+    /// Sets `key` to `value`, returning the value it replaced.
+    /// The descent starts at the deepest node on the cursor's
+    /// path that `key` leads through.
+    pub fn insert(&mut self, key: Vec<u8>, value: T) -> Option<T> {
+        let positioned = !self.path.nodes.is_empty();
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&self.path.key);
+
+        let path_key = &self.path.key;
+        let deepest = self
+            .path
+            .nodes
+            .iter()
+            .rposition(|&(_, len)| key.starts_with(&path_key[..len]));
+        let (node, depth) = match deepest {
+            Some(i) => {
+                let (ptr, len) = self.path.nodes[i];
+                (ptr, len - unsafe { (*ptr).prefix().len() })
+            }
+            None => (self.art.root, 0),
+        };
+
+        self.art.path.clear();
+        self.art.path.push((node, 0));
+        let old = unsafe {
+            (*node).insert(
+                &key,
+                depth,
+                value,
+                &self.art.thresholds,
+                self.art.interner.as_mut(),
+                &mut self.art.path,
+            )
+        };
+        if old.is_none() {
+            let above = &self.path.nodes[..deepest.unwrap_or(0)];
+            for &(ptr, _) in above {
+                unsafe { *(*ptr).count_mut() += 1 };
+            }
+            self.art.counted(1);
+        }
+
+        // nodes below the one the insert started at may have
+        // been split
+        self.path.truncate(deepest.map_or(0, |i| i + 1));
+        if positioned {
+            self.path.seek(self.art.root, &self.scratch);
+        } else {
+            self.path.truncate(0);
+        }
+
+        old
+    }
+}
+
+// the nodes from the root down to a cursor's entry, shared by
+// both kinds of cursor.
+struct Path<T, M> {
+    // each node along with the length of its full key. empty
+    // when the cursor points nowhere.
+    nodes: Vec<(*mut Node<T, M>, usize)>,
+    // the full key of the last node
+    key: Vec<u8>,
+}
+
+impl<T, M> Default for Path<T, M> {
+    fn default() -> Path<T, M> {
+        Path {
+            nodes: vec![],
+            key: vec![],
+        }
+    }
+}
+
+impl<T, M> Path<T, M> {
+    fn key(&self) -> Option<&[u8]> {
+        if self.nodes.is_empty() {
+            None
+        } else {
+            Some(&self.key)
+        }
+    }
+
+    fn node(&self) -> Option<*mut Node<T, M>> {
+        self.nodes.last().map(|&(node, _)| node)
+    }
+
+    // the last node on the path. it belongs to the tree rather
+    // than to the path, so it is not borrowed from `self`.
+    fn last<'n>(&self) -> &'n Node<T, M> {
+        unsafe { &*self.nodes[self.nodes.len() - 1].0 }
+    }
+
+    fn seek(&mut self, root: *mut Node<T, M>, key: &[u8]) -> bool {
+        // climb until `key` agrees with the path leading to the
+        // last node, which always holds for the root
+        while let Some(&(node, len)) = self.nodes.last() {
+            let start = len - unsafe { (*node).prefix().len() };
+            if key.starts_with(&self.key[..start]) {
+                break;
+            }
+            self.pop();
+        }
+        if self.nodes.is_empty() {
+            self.key.clear();
+            self.key.extend_from_slice(unsafe { (*root).prefix() });
+            self.nodes.push((root, self.key.len()));
+        }
+
+        loop {
+            let node = self.last();
+            let len = self.key.len();
+            let start = len - node.prefix().len();
+            let end = key.len().min(len);
+
+            let prefix = &self.key[start..];
+            let searched = &key[start..end];
+            if prefix[..searched.len()] != *searched {
+                // the subtree lies entirely before or after `key`
+                return if prefix > searched {
+                    self.first_below()
+                } else {
+                    self.skip_subtree()
+                };
+            }
+            if key.len() <= len {
+                // every key in the subtree starts with `key`
+                return self.first_below();
+            }
+
+            let byte = key[len];
+            if let Some(idx) = node.find_child(byte) {
+                self.push(byte, unsafe { node.child_at(idx) });
+                continue;
+            }
+            return match node.adjacent_child(Some(byte), false) {
+                Some((byte, child)) => {
+                    self.push(byte, child);
+                    self.first_below()
+                }
+                None => self.skip_subtree(),
+            };
+        }
+    }
+
+    fn next(&mut self) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        // children come after their parent
+        match self.last().adjacent_child(None, false) {
+            Some((byte, child)) => {
+                self.push(byte, child);
+                self.first_below()
+            }
+            None => self.skip_subtree(),
+        }
+    }
+
+    fn prev(&mut self) -> bool {
+        while self.nodes.len() > 1 {
+            let byte = self.pop();
+            let parent = self.last();
+
+            if let Some((byte, child)) =
+                parent.adjacent_child(Some(byte), true)
+            {
+                self.push(byte, child);
+                return self.last_below();
+            }
+            if parent.value().is_some() {
+                return true;
+            }
+        }
+
+        self.nodes.clear();
+        false
+    }
+
+    fn push(&mut self, byte: u8, child: *mut Node<T, M>) {
+        self.key.push(byte);
+        self.key.extend_from_slice(unsafe { (*child).prefix() });
+        self.nodes.push((child, self.key.len()));
+    }
+
+    // drops the last node, which is not the root, from the
+    // path and returns the byte that led to it
+    fn pop(&mut self) -> u8 {
+        let (node, len) = self.nodes.pop().unwrap();
+        let start = len - unsafe { (*node).prefix().len() };
+        let byte = self.key[start - 1];
+        self.key.truncate(start - 1);
+        byte
+    }
+
+    // keeps only the first `len` nodes, without looking at the
+    // ones that are dropped
+    fn truncate(&mut self, len: usize) {
+        self.nodes.truncate(len);
+        let key_len = self.nodes.last().map_or(0, |&(_, len)| len);
+        self.key.truncate(key_len);
+    }
+
+    // moves to the first entry in the subtree of the last node
+    // on the path, which is the node itself if it has a value
+    fn first_below(&mut self) -> bool {
+        loop {
+            let node = self.last();
+            if node.value().is_some() {
+                return true;
+            }
+            match node.adjacent_child(None, false) {
+                Some((byte, child)) => self.push(byte, child),
+                // only an empty root has neither
+                None => {
+                    self.nodes.clear();
+                    return false;
+                }
+            }
+        }
+    }
+
+    // moves to the last entry in the subtree of the last node
+    // on the path, which is always a leaf
+    fn last_below(&mut self) -> bool {
+        while let Some((byte, child)) =
+            self.last().adjacent_child(None, true)
+        {
+            self.push(byte, child);
+        }
+        true
+    }
+
+    // moves to the first entry after the whole subtree of the
+    // last node on the path
+    fn skip_subtree(&mut self) -> bool {
+        while self.nodes.len() > 1 {
+            let byte = self.pop();
+
+            if let Some((byte, child)) =
+                self.last().adjacent_child(Some(byte), false)
+            {
+                self.push(byte, child);
+                return self.first_below();
+            }
+        }
+
+        self.nodes.clear();
+        false
+    }
+}
@@ -0,0 +1,164 @@
+use std::error::Error;
+use std::fmt;
+
+use super::{Art, Node, Slot};
+
+/// A view into a single key of an `Art`, which is either
+/// occupied or vacant.
+///
+/// Created by [`Art::entry`](struct.Art.html#method.entry).
+pub enum Entry<'a, T: 'a, M: 'a = ()> {
+    Occupied(OccupiedEntry<'a, T, M>),
+    Vacant(VacantEntry<'a, T, M>),
+}
+
+/// An entry whose key holds a value.
+pub struct OccupiedEntry<'a, T: 'a, M: 'a = ()> {
+    art: &'a mut Art<T, M>,
+    key: Vec<u8>,
+    // the node holding the value
+    node: *mut Node<T, M>,
+}
+
+/// An entry whose key holds no value.
+pub struct VacantEntry<'a, T: 'a, M: 'a = ()> {
+    art: &'a mut Art<T, M>,
+    key: Vec<u8>,
+    // where the descent for the key ended, so inserting does
+    // not need to start over at the root.
+    slot: Slot<T, M>,
+}
+
+/// The error returned by
+/// [`Art::try_insert`](struct.Art.html#method.try_insert) when
+/// the key already holds a value. It hands back the rejected
+/// key and value, the stored value is left untouched.
+#[derive(Debug)]
+pub struct OccupiedError<T> {
+    pub key: Vec<u8>,
+    pub value: T,
+}
+
+impl<T> fmt::Display for OccupiedError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "key {:?} already holds a value", self.key)
+    }
+}
+
+impl<T> Error for OccupiedError<T> where T: fmt::Debug {}
+
+impl<'a, T, M> Entry<'a, T, M>
+where
+    M: Default,
+{
+    pub(crate) fn new(
+        art: &'a mut Art<T, M>,
+        key: Vec<u8>,
+    ) -> Entry<'a, T, M> {
+        let slot = unsafe { Node::slot(art.root, &key, &mut art.path) };
+
+        match slot {
+            Slot::Found(node) if unsafe { &*node }.value().is_some() => {
+                Entry::Occupied(OccupiedEntry { art, key, node })
+            }
+            slot => Entry::Vacant(VacantEntry { art, key, slot }),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a
+    /// mutable reference to the value either way.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts what `default` returns if the entry is vacant,
+    /// and returns a mutable reference to the value either way.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut T
+    where
+        F: FnOnce() -> T,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` with the value if the entry is occupied.
+    pub fn and_modify<F>(mut self, f: F) -> Entry<'a, T, M>
+    where
+        F: FnOnce(&mut T),
+    {
+        if let Entry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T, M> OccupiedEntry<'a, T, M>
+where
+    M: Default,
+{
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub(crate) fn into_key(self) -> Vec<u8> {
+        self.key
+    }
+
+    pub fn get(&self) -> &T {
+        unsafe { (*self.node).value().expect("entry is occupied") }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { (*self.node).value_mut().expect("entry is occupied") }
+    }
+
+    /// Turns the entry into a mutable reference to its value,
+    /// which lives as long as the borrow of the tree.
+    pub fn into_mut(self) -> &'a mut T {
+        unsafe { (*self.node).value_mut().expect("entry is occupied") }
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: T) -> T {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the tree, returning its value.
+    pub fn remove(self) -> T {
+        let value = unsafe { (*self.node).take_value() };
+        self.art.counted(-1);
+        self.art.prune();
+
+        value.expect("entry is occupied")
+    }
+}
+
+impl<'a, T, M> VacantEntry<'a, T, M>
+where
+    M: Default,
+{
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn into_key(self) -> Vec<u8> {
+        self.key
+    }
+
+    /// Inserts `value` under the entry's key, returning a
+    /// mutable reference to it.
+    pub fn insert(self, value: T) -> &'a mut T {
+        let VacantEntry { art, key, slot } = self;
+        art.insert_at(slot, &key, value)
+    }
+}
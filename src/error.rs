@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt;
+
+/// The error returned by the fallible variants of the calls
+/// that otherwise panic on input they can't work with, like
+/// [`Art::try_from_sorted_refs`](struct.Art.html#method.try_from_sorted_refs).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CartError {
+    /// A node capacity outside of `1..=max`, which is what the
+    /// node variant named by `variant` can hold.
+    InvalidCapacity {
+        variant: &'static str,
+        capacity: usize,
+        max: usize,
+    },
+    /// Keys that have to be sorted are not. The key at `index`
+    /// sorts before the one preceding it.
+    UnsortedKeys { index: usize },
+    /// A page limit of zero, which could never make progress.
+    ZeroLimit,
+}
+
+impl fmt::Display for CartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CartError::InvalidCapacity {
+                variant,
+                capacity,
+                max,
+            } => write!(
+                f,
+                "{} capacity must be between 1 and {}, not {}",
+                variant, max, capacity
+            ),
+            CartError::UnsortedKeys { index } => write!(
+                f,
+                "keys must be sorted, but key {} sorts before \
+                 the one preceding it",
+                index
+            ),
+            CartError::ZeroLimit => {
+                write!(f, "pages must hold at least one entry")
+            }
+        }
+    }
+}
+
+impl Error for CartError {}
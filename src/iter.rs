@@ -0,0 +1,973 @@
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Bound, ControlFlow};
+
+use super::{free, Art, Cursor, Node};
+
+// the full key of the child reached by `byte` from the node
+// with the full key `parent`.
+pub(crate) fn child_key<T, M>(
+    parent: &[u8],
+    byte: u8,
+    child: &Node<T, M>,
+) -> Vec<u8> {
+    let mut key =
+        Vec::with_capacity(parent.len() + 1 + child.prefix().len());
+    key.extend_from_slice(parent);
+    key.push(byte);
+    key.extend_from_slice(child.prefix());
+    key
+}
+
+// whether every key starting with `prefix` lies below `start`
+pub(crate) fn below_start(
+    prefix: &[u8],
+    start: Bound<&[u8]>,
+) -> bool {
+    match start {
+        Bound::Included(s) | Bound::Excluded(s) => {
+            prefix < s && !s.starts_with(prefix)
+        }
+        Bound::Unbounded => false,
+    }
+}
+
+// whether every key starting with `prefix` lies beyond `end`
+pub(crate) fn past_end(prefix: &[u8], end: Bound<&[u8]>) -> bool {
+    match end {
+        Bound::Included(e) => prefix > e,
+        Bound::Excluded(e) => prefix >= e,
+        Bound::Unbounded => false,
+    }
+}
+
+pub(crate) fn in_range(
+    key: &[u8],
+    start: Bound<&[u8]>,
+    end: Bound<&[u8]>,
+) -> bool {
+    let after_start = match start {
+        Bound::Included(s) => key >= s,
+        Bound::Excluded(s) => key > s,
+        Bound::Unbounded => true,
+    };
+
+    after_start && !past_end(key, end)
+}
+
+/// Calls `f` on every entry between `start` and `end` in key
+/// order, skipping subtrees that lie entirely outside of them.
+pub(crate) fn range_for_each<T, M, F>(
+    root: &Node<T, M>,
+    start: Bound<&[u8]>,
+    end: Bound<&[u8]>,
+    mut f: F,
+) where
+    F: FnMut(&[u8], &T),
+{
+    let mut stack = vec![(root.prefix().to_vec(), root)];
+
+    while let Some((key, node)) = stack.pop() {
+        if below_start(&key, start) {
+            continue;
+        }
+        if past_end(&key, end) {
+            // everything left on the stack is even larger
+            return;
+        }
+
+        if let Some(value) = node.value() {
+            if in_range(&key, start, end) {
+                f(&key, value);
+            }
+        }
+
+        for (byte, child) in node.children().into_iter().rev() {
+            let child = unsafe { &*child };
+            stack.push((child_key(&key, byte, child), child));
+        }
+    }
+}
+
+/// Calls `f` on every entry below `node`, whose full key is
+/// `key`, in key order until it breaks. One key buffer is
+/// shared by the whole walk.
+pub(crate) fn walk<'a, T, M, B, F>(
+    mut key: Vec<u8>,
+    mut node: &'a Node<T, M>,
+    mut f: F,
+) -> Option<B>
+where
+    F: FnMut(&[u8], &'a T) -> ControlFlow<B>,
+{
+    // the nodes still to visit, each with the length of its
+    // parent's key and the byte leading to it
+    let mut stack = vec![];
+
+    loop {
+        if let Some(value) = node.value() {
+            if let ControlFlow::Break(b) = f(&key, value) {
+                return Some(b);
+            }
+        }
+
+        let len = key.len();
+        for (byte, child) in node.children().into_iter().rev() {
+            stack.push((unsafe { &*child }, len, byte));
+        }
+
+        let (child, len, byte) = stack.pop()?;
+        key.truncate(len);
+        key.push(byte);
+        key.extend_from_slice(child.prefix());
+        node = child;
+    }
+}
+
+/// Walks the entries of an `Art` in key order like `Iter`, but
+/// lends out each key from a buffer that is reused for the
+/// next one instead of allocating it. Since a key only lives
+/// until the next call, this is not an `Iterator`.
+///
+/// Created by
+/// [`Art::lending_iter`](struct.Art.html#method.lending_iter).
+pub struct LendingIter<'a, T: 'a, M: 'a = ()> {
+    cursor: Cursor<'a, T, M>,
+    started: bool,
+}
+
+impl<'a, T, M> LendingIter<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> LendingIter<'a, T, M> {
+        LendingIter {
+            cursor: Cursor::new(root),
+            started: false,
+        }
+    }
+
+    /// The next entry, whose key borrows from the iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&[u8], &'a T)> {
+        // the cursor starts out on the first entry
+        if self.started {
+            self.cursor.next();
+        }
+        self.started = true;
+
+        let value = self.cursor.value()?;
+        self.cursor.key().map(|key| (key, value))
+    }
+}
+
+/// An iterator over the entries of an `Art`, in key order.
+///
+/// Like every iterator and cursor over a tree, it borrows the
+/// tree for as long as it lives. Changing the tree underneath
+/// it, which could free the nodes it points to, is therefore
+/// rejected at compile time instead of being detected while
+/// iterating:
+///
+/// ```compile_fail
+/// let mut art: cart::Art<u32> = cart::Art::default();
+/// art.set(b"a".to_vec(), 1);
+///
+/// for (key, _) in art.iter() {
+///     art.remove(&key);
+/// }
+/// ```
+///
+/// Changes made while walking the tree go through a
+/// [`CursorMut`](struct.CursorMut.html), which repairs its own
+/// path, or through `retain` and `extract_if`.
+pub struct Iter<'a, T: 'a, M: 'a = ()> {
+    stack: Vec<(Vec<u8>, &'a Node<T, M>)>,
+    // walks the same subtree from the other end for
+    // `next_back`, the two walks stop where they meet.
+    back: IterRev<'a, T, M>,
+    back_last: Option<Vec<u8>>,
+}
+
+impl<'a, T, M> Iter<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> Iter<'a, T, M> {
+        Iter::subtree(root.prefix().to_vec(), root)
+    }
+
+    pub(crate) fn empty() -> Iter<'a, T, M> {
+        Iter {
+            stack: vec![],
+            back: IterRev { stack: vec![] },
+            back_last: None,
+        }
+    }
+
+    // iterates over the subtree below `node`, whose full key
+    // is `key`.
+    pub(crate) fn subtree(
+        key: Vec<u8>,
+        node: &'a Node<T, M>,
+    ) -> Iter<'a, T, M> {
+        Iter {
+            back: IterRev::subtree(key.clone(), node),
+            stack: vec![(key, node)],
+            back_last: None,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for Iter<'a, T, M> {
+    type Item = (Vec<u8>, &'a T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        while let Some((key, node)) = self.stack.pop() {
+            if yielded_from_back(&self.back_last, &key) {
+                self.stack.clear();
+                return None;
+            }
+
+            for (byte, child) in node.children().into_iter().rev() {
+                let child = unsafe { &*child };
+                self.stack
+                    .push((child_key(&key, byte, child), child));
+            }
+
+            if let Some(value) = node.value() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T, M> DoubleEndedIterator for Iter<'a, T, M> {
+    fn next_back(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        let (key, value) = self.back.next()?;
+
+        if yielded_from_front(&self.stack, &key) {
+            self.back.stack.clear();
+            return None;
+        }
+
+        self.back_last = Some(key.clone());
+        Some((key, value))
+    }
+}
+
+// whether the subtree with the full key `key` was consumed from
+// the back already, given the last key yielded from there.
+fn yielded_from_back(back_last: &Option<Vec<u8>>, key: &[u8]) -> bool {
+    match back_last {
+        Some(last) => key >= &last[..],
+        None => false,
+    }
+}
+
+// whether `key` was yielded from the front already. the front
+// stack's top is the smallest subtree left to visit, everything
+// below its key was visited.
+fn yielded_from_front<N>(front: &[(Vec<u8>, N)], key: &[u8]) -> bool {
+    match front.last() {
+        Some((next, _)) => key < &next[..],
+        None => true,
+    }
+}
+
+// an exhausted iterator has nothing left on its stack
+impl<'a, T, M> FusedIterator for Iter<'a, T, M> {}
+
+/// An iterator over the keys of an `Art`, in key order.
+///
+/// Created by [`Art::keys`](struct.Art.html#method.keys).
+pub struct Keys<'a, T: 'a, M: 'a = ()> {
+    inner: Iter<'a, T, M>,
+}
+
+impl<'a, T, M> Keys<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> Keys<'a, T, M> {
+        Keys {
+            inner: Iter::new(root),
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for Keys<'a, T, M> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+impl<'a, T, M> FusedIterator for Keys<'a, T, M> {}
+
+/// An iterator over the values of an `Art`, in key order.
+///
+/// Created by [`Art::values`](struct.Art.html#method.values).
+pub struct Values<'a, T: 'a, M: 'a = ()> {
+    // unlike `Iter`, no keys are put together along the way
+    stack: Vec<&'a Node<T, M>>,
+}
+
+impl<'a, T, M> Values<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> Values<'a, T, M> {
+        Values { stack: vec![root] }
+    }
+}
+
+impl<'a, T, M> Iterator for Values<'a, T, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while let Some(node) = self.stack.pop() {
+            for (_, child) in node.children().into_iter().rev() {
+                self.stack.push(unsafe { &*child });
+            }
+
+            if let Some(value) = node.value() {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T, M> FusedIterator for Values<'a, T, M> {}
+
+/// A mutable iterator over the values of an `Art`, in key
+/// order.
+///
+/// Created by [`Art::values_mut`](struct.Art.html#method.values_mut).
+pub struct ValuesMut<'a, T: 'a, M: 'a = ()> {
+    // every node is pushed and popped exactly once, so no two
+    // yielded references can point at the same value.
+    stack: Vec<*mut Node<T, M>>,
+    _art: PhantomData<&'a mut Art<T, M>>,
+}
+
+impl<'a, T, M> ValuesMut<'a, T, M> {
+    pub(crate) fn new(root: *mut Node<T, M>) -> ValuesMut<'a, T, M> {
+        ValuesMut {
+            stack: vec![root],
+            _art: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for ValuesMut<'a, T, M> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        while let Some(ptr) = self.stack.pop() {
+            let node = unsafe { &mut *ptr };
+
+            for (_, child) in node.children().into_iter().rev() {
+                self.stack.push(child);
+            }
+
+            if let Some(value) = node.value_mut() {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T, M> FusedIterator for ValuesMut<'a, T, M> {}
+
+/// An iterator over the entries of an `Art` whose keys lie
+/// between two bounds, in key order. Subtrees entirely outside
+/// of the bounds are skipped.
+///
+/// Created by [`Art::range`](struct.Art.html#method.range).
+pub struct Range<'a, T: 'a, M: 'a = ()> {
+    start: Bound<Vec<u8>>,
+    end: Bound<Vec<u8>>,
+    stack: Vec<(Vec<u8>, &'a Node<T, M>)>,
+    // like the stack of `IterRev`, for `next_back`
+    back: Vec<(Vec<u8>, &'a Node<T, M>, bool)>,
+    back_last: Option<Vec<u8>>,
+}
+
+impl<'a, T, M> Range<'a, T, M> {
+    pub(crate) fn new<K>(
+        root: &'a Node<T, M>,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Range<'a, T, M>
+    where
+        K: AsRef<[u8]> + ?Sized,
+    {
+        Range {
+            start: owned_bound(start),
+            end: owned_bound(end),
+            stack: vec![(root.prefix().to_vec(), root)],
+            back: vec![(root.prefix().to_vec(), root, false)],
+            back_last: None,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for Range<'a, T, M> {
+    type Item = (Vec<u8>, &'a T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        let start = borrowed_bound(&self.start);
+        let end = borrowed_bound(&self.end);
+
+        while let Some((key, node)) = self.stack.pop() {
+            if below_start(&key, start) {
+                continue;
+            }
+            if past_end(&key, end)
+                || yielded_from_back(&self.back_last, &key)
+            {
+                // everything left on the stack is even larger
+                self.stack.clear();
+                return None;
+            }
+
+            for (byte, child) in node.children().into_iter().rev() {
+                let child = unsafe { &*child };
+                self.stack
+                    .push((child_key(&key, byte, child), child));
+            }
+
+            if let Some(value) = node.value() {
+                if in_range(&key, start, end) {
+                    return Some((key, value));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T, M> DoubleEndedIterator for Range<'a, T, M> {
+    fn next_back(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        let start = borrowed_bound(&self.start);
+        let end = borrowed_bound(&self.end);
+
+        while let Some((key, node, expanded)) = self.back.pop() {
+            if expanded {
+                let value = match node.value() {
+                    Some(value) if in_range(&key, start, end) => value,
+                    _ => continue,
+                };
+                if yielded_from_front(&self.stack, &key) {
+                    break;
+                }
+
+                self.back_last = Some(key.clone());
+                return Some((key, value));
+            }
+
+            if past_end(&key, end) {
+                continue;
+            }
+            if below_start(&key, start) {
+                // everything left on the stack is even smaller
+                break;
+            }
+
+            let children: Vec<_> = node
+                .children()
+                .into_iter()
+                .map(|(byte, child)| {
+                    let child = unsafe { &*child };
+                    (child_key(&key, byte, child), child, false)
+                })
+                .collect();
+
+            self.back.push((key, node, true));
+            self.back.extend(children);
+        }
+
+        self.back.clear();
+        None
+    }
+}
+
+/// A mutable iterator over the entries of an `Art` whose keys
+/// lie between two bounds, in key order.
+///
+/// Created by [`Art::range_mut`](struct.Art.html#method.range_mut).
+pub struct RangeMut<'a, T: 'a, M: 'a = ()> {
+    start: Bound<Vec<u8>>,
+    end: Bound<Vec<u8>>,
+    // every node is pushed and popped at most once, so no two
+    // yielded references can point at the same value.
+    stack: Vec<(Vec<u8>, *mut Node<T, M>)>,
+    _art: PhantomData<&'a mut Art<T, M>>,
+}
+
+impl<'a, T, M> RangeMut<'a, T, M> {
+    pub(crate) fn new<K>(
+        root: *mut Node<T, M>,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> RangeMut<'a, T, M>
+    where
+        K: AsRef<[u8]> + ?Sized,
+    {
+        let key = unsafe { (*root).prefix().to_vec() };
+
+        RangeMut {
+            start: owned_bound(start),
+            end: owned_bound(end),
+            stack: vec![(key, root)],
+            _art: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for RangeMut<'a, T, M> {
+    type Item = (Vec<u8>, &'a mut T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a mut T)> {
+        let start = borrowed_bound(&self.start);
+        let end = borrowed_bound(&self.end);
+
+        while let Some((key, ptr)) = self.stack.pop() {
+            if below_start(&key, start) {
+                continue;
+            }
+            if past_end(&key, end) {
+                // everything left on the stack is even larger
+                self.stack.clear();
+                return None;
+            }
+
+            let node = unsafe { &mut *ptr };
+            for (byte, child) in node.children().into_iter().rev() {
+                let child_key =
+                    child_key(&key, byte, unsafe { &*child });
+                self.stack.push((child_key, child));
+            }
+
+            if !in_range(&key, start, end) {
+                continue;
+            }
+            if let Some(value) = node.value_mut() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+fn owned_bound<K>(bound: Bound<&K>) -> Bound<Vec<u8>>
+where
+    K: AsRef<[u8]> + ?Sized,
+{
+    match bound {
+        Bound::Included(b) => Bound::Included(b.as_ref().to_vec()),
+        Bound::Excluded(b) => Bound::Excluded(b.as_ref().to_vec()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+fn borrowed_bound(bound: &Bound<Vec<u8>>) -> Bound<&[u8]> {
+    match bound {
+        Bound::Included(b) => Bound::Included(b),
+        Bound::Excluded(b) => Bound::Excluded(b),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// An iterator over the entries whose keys start with a prefix,
+/// yielding the keys without it, in key order.
+///
+/// Created by
+/// [`Art::iter_prefix_stripped`](struct.Art.html#method.iter_prefix_stripped).
+pub struct StripPrefix<'a, T: 'a, M: 'a = ()> {
+    inner: Iter<'a, T, M>,
+    len: usize,
+}
+
+impl<'a, T, M> StripPrefix<'a, T, M> {
+    pub(crate) fn new(
+        inner: Iter<'a, T, M>,
+        len: usize,
+    ) -> StripPrefix<'a, T, M> {
+        StripPrefix { inner, len }
+    }
+}
+
+impl<'a, T, M> Iterator for StripPrefix<'a, T, M> {
+    type Item = (Vec<u8>, &'a T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        let (mut key, value) = self.inner.next()?;
+        key.drain(..self.len);
+
+        Some((key, value))
+    }
+}
+
+/// A mutable iterator over the entries of an `Art`, in key order.
+///
+/// Created by [`Art::iter_mut`](struct.Art.html#method.iter_mut).
+pub struct IterMut<'a, T: 'a, M: 'a = ()> {
+    // every node is pushed and popped exactly once, so no two
+    // yielded references can point at the same value.
+    stack: Vec<(Vec<u8>, *mut Node<T, M>)>,
+    _art: PhantomData<&'a mut Art<T, M>>,
+}
+
+impl<'a, T, M> IterMut<'a, T, M> {
+    pub(crate) fn new(root: *mut Node<T, M>) -> IterMut<'a, T, M> {
+        let key = unsafe { (*root).prefix().to_vec() };
+
+        IterMut {
+            stack: vec![(key, root)],
+            _art: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for IterMut<'a, T, M> {
+    type Item = (Vec<u8>, &'a mut T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a mut T)> {
+        while let Some((key, ptr)) = self.stack.pop() {
+            let node = unsafe { &mut *ptr };
+
+            for (byte, child) in node.children().into_iter().rev() {
+                let child_key =
+                    child_key(&key, byte, unsafe { &*child });
+                self.stack.push((child_key, child));
+            }
+
+            if let Some(value) = node.value_mut() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T, M> FusedIterator for IterMut<'a, T, M> {}
+
+/// An iterator over the entries of an `Art`, in descending key
+/// order.
+pub(crate) struct IterRev<'a, T: 'a, M: 'a = ()> {
+    // nodes are pushed once to expand their children and once
+    // more to yield their own value after all of them, as a
+    // node's key sorts before the keys below it.
+    stack: Vec<(Vec<u8>, &'a Node<T, M>, bool)>,
+}
+
+impl<'a, T, M> IterRev<'a, T, M> {
+    pub(crate) fn new(root: &'a Node<T, M>) -> IterRev<'a, T, M> {
+        IterRev::subtree(root.prefix().to_vec(), root)
+    }
+
+    fn subtree(
+        key: Vec<u8>,
+        node: &'a Node<T, M>,
+    ) -> IterRev<'a, T, M> {
+        IterRev {
+            stack: vec![(key, node, false)],
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for IterRev<'a, T, M> {
+    type Item = (Vec<u8>, &'a T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a T)> {
+        while let Some((key, node, expanded)) = self.stack.pop() {
+            if expanded {
+                if let Some(value) = node.value() {
+                    return Some((key, value));
+                }
+                continue;
+            }
+
+            let children: Vec<_> = node
+                .children()
+                .into_iter()
+                .map(|(byte, child)| {
+                    let child = unsafe { &*child };
+                    (child_key(&key, byte, child), child, false)
+                })
+                .collect();
+
+            self.stack.push((key, node, true));
+            self.stack.extend(children);
+        }
+
+        None
+    }
+}
+
+/// An iterator over groups of entries that share their first
+/// bytes, in key order.
+///
+/// Created by
+/// [`Art::group_by_prefix`](struct.Art.html#method.group_by_prefix).
+pub struct GroupByPrefix<'a, T: 'a, M: 'a = ()> {
+    len: usize,
+    stack: Vec<(Vec<u8>, &'a Node<T, M>)>,
+}
+
+impl<'a, T, M> GroupByPrefix<'a, T, M> {
+    pub(crate) fn new(
+        root: &'a Node<T, M>,
+        len: usize,
+    ) -> GroupByPrefix<'a, T, M> {
+        GroupByPrefix {
+            len,
+            stack: vec![(root.prefix().to_vec(), root)],
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for GroupByPrefix<'a, T, M> {
+    type Item = (Vec<u8>, Vec<(Vec<u8>, &'a T)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key, node)) = self.stack.pop() {
+            if key.len() >= self.len {
+                // every key below this node starts with the same
+                // `len` bytes, so the subtree is one group.
+                let group_key = key[..self.len].to_vec();
+                let entries = Iter::subtree(key, node).collect();
+                return Some((group_key, entries));
+            }
+
+            for (byte, child) in node.children().into_iter().rev() {
+                let child = unsafe { &*child };
+                self.stack
+                    .push((child_key(&key, byte, child), child));
+            }
+
+            // a key shorter than `len` is a group of its own
+            if let Some(value) = node.value() {
+                return Some((key.clone(), vec![(key, value)]));
+            }
+        }
+
+        None
+    }
+}
+
+/// An iterator over the stored keys that are prefixes of a
+/// query, from the shortest to the query itself. The keys are
+/// slices of the query.
+///
+/// Created by
+/// [`Art::prefixes_of`](struct.Art.html#method.prefixes_of).
+pub struct PrefixesOf<'a, 'q, T: 'a, M: 'a = ()> {
+    // the next node on the path of the query, and where its
+    // prefix starts in the query
+    node: Option<&'a Node<T, M>>,
+    depth: usize,
+    query: &'q [u8],
+}
+
+impl<'a, 'q, T, M> PrefixesOf<'a, 'q, T, M> {
+    pub(crate) fn new(
+        root: &'a Node<T, M>,
+        query: &'q [u8],
+    ) -> PrefixesOf<'a, 'q, T, M> {
+        PrefixesOf {
+            node: Some(root),
+            depth: 0,
+            query,
+        }
+    }
+}
+
+impl<'a, 'q, T, M> Iterator for PrefixesOf<'a, 'q, T, M> {
+    type Item = (&'q [u8], &'a T);
+
+    fn next(&mut self) -> Option<(&'q [u8], &'a T)> {
+        loop {
+            let node = self.node.take()?;
+            if !self.query[self.depth..].starts_with(node.prefix()) {
+                return None;
+            }
+
+            let end = self.depth + node.prefix().len();
+            if end < self.query.len() {
+                if let Some(idx) = node.find_child(self.query[end]) {
+                    self.node = Some(unsafe { &*node[idx] });
+                    self.depth = end + 1;
+                }
+            }
+
+            if let Some(value) = node.value() {
+                return Some((&self.query[..end], value));
+            }
+        }
+    }
+}
+
+impl<'a, 'q, T, M> FusedIterator for PrefixesOf<'a, 'q, T, M> {}
+
+/// An iterator over the entries of an `Art`, in key order,
+/// taking ownership of the values.
+///
+/// Created by the `IntoIterator` implementation of `Art`.
+pub struct IntoIter<T, M = ()> {
+    // subtrees that still have to be visited, along with the
+    // full key of their root. the top of the stack is the
+    // next subtree in key order.
+    stack: Vec<(Vec<u8>, *mut Node<T, M>)>,
+    _nodes: PhantomData<Box<Node<T, M>>>,
+}
+
+impl<T, M> IntoIter<T, M> {
+    pub(crate) fn new(root: *mut Node<T, M>) -> IntoIter<T, M> {
+        let key = unsafe { (*root).prefix().to_vec() };
+
+        IntoIter {
+            stack: vec![(key, root)],
+            _nodes: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Iterator for IntoIter<T, M> {
+    type Item = (Vec<u8>, T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, T)> {
+        while let Some((key, ptr)) = self.stack.pop() {
+            // we take ownership of the node here, its children
+            // are owned by the stack from now on.
+            let mut node = unsafe { Box::from_raw(ptr) };
+
+            for (byte, child) in node.children().into_iter().rev() {
+                let child_key =
+                    child_key(&key, byte, unsafe { &*child });
+                self.stack.push((child_key, child));
+            }
+
+            if let Some(value) = node.take_value() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T, M> FusedIterator for IntoIter<T, M> {}
+
+impl<T, M> Drop for IntoIter<T, M> {
+    fn drop(&mut self) {
+        for (_, ptr) in self.stack.drain(..) {
+            unsafe { free(ptr) }
+        }
+    }
+}
+
+// the private stack owns the subtrees on it, like the tree did
+unsafe impl<T, M> Send for IntoIter<T, M>
+where
+    T: Send,
+    M: Send,
+{
+}
+
+unsafe impl<T, M> Sync for IntoIter<T, M>
+where
+    T: Sync,
+    M: Sync,
+{
+}
+
+/// A draining iterator over the entries of an `Art`, in key order.
+///
+/// Created by [`Art::drain`](struct.Art.html#method.drain).
+pub struct Drain<'a, T: 'a, M: 'a = ()> {
+    // the nodes were detached from the tree already, they are
+    // consumed just like those of an owned tree.
+    inner: IntoIter<T, M>,
+    _art: PhantomData<&'a mut Art<T, M>>,
+}
+
+impl<'a, T, M> Drain<'a, T, M> {
+    pub(crate) fn new(root: *mut Node<T, M>) -> Drain<'a, T, M> {
+        Drain {
+            inner: IntoIter::new(root),
+            _art: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for Drain<'a, T, M> {
+    type Item = (Vec<u8>, T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, T)> {
+        self.inner.next()
+    }
+}
+
+/// An iterator that removes and yields the entries of an `Art`
+/// matching a predicate, in key order.
+///
+/// Created by [`Art::extract_if`](struct.Art.html#method.extract_if).
+pub struct ExtractIf<'a, T: 'a, F, M: 'a = ()> {
+    art: &'a mut Art<T, M>,
+    pred: F,
+    // the last key the predicate was called for. removals may
+    // restructure the tree between two calls to `next`, so
+    // every step seeks past this key from the root again
+    // instead of keeping a stack of nodes.
+    last: Option<Vec<u8>>,
+}
+
+impl<'a, T, F, M> ExtractIf<'a, T, F, M> {
+    pub(crate) fn new(
+        art: &'a mut Art<T, M>,
+        pred: F,
+    ) -> ExtractIf<'a, T, F, M> {
+        ExtractIf {
+            art,
+            pred,
+            last: None,
+        }
+    }
+}
+
+impl<'a, T, F, M> Iterator for ExtractIf<'a, T, F, M>
+where
+    F: FnMut(&[u8], &mut T) -> bool,
+    M: Default,
+{
+    type Item = (Vec<u8>, T);
+
+    fn next(&mut self) -> Option<(Vec<u8>, T)> {
+        loop {
+            let key = {
+                let start = match self.last {
+                    Some(ref last) => Bound::Excluded(&last[..]),
+                    None => Bound::Unbounded,
+                };
+                let root = unsafe { &*self.art.root };
+                Range::new(root, start, Bound::Unbounded).next()?.0
+            };
+
+            let matched = {
+                let value = self
+                    .art
+                    .get_mut(&key)
+                    .expect("the key was just found");
+                (self.pred)(&key, value)
+            };
+
+            if matched {
+                let value = self.art.remove(&key);
+                self.last = Some(key.clone());
+                return value.map(|value| (key, value));
+            }
+            self.last = Some(key);
+        }
+    }
+}
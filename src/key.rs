@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+
+use key_encoding::*;
+
+/// A type that can be used as a key, by encoding it into bytes.
+///
+/// The encoding has to preserve order: if `a < b` then
+/// `a.to_bytes() < b.to_bytes()`, so that iterating over the
+/// tree yields keys in their own order. Unsigned integers are
+/// encoded big-endian, which sorts them numerically, and signed
+/// integers and floats as described in
+/// [`key_encoding`](key_encoding/index.html). Byte strings and
+/// strings are used as they are.
+///
+/// Only `Art::set` and `Art::get` take a `Key`. The rest of the
+/// keyed methods, like `remove`, `get_mut` or `entry`, take the
+/// encoded bytes, which `k.to_bytes()` gives. A
+/// [`TypedArt`](struct.TypedArt.html) encodes its keys in all
+/// of its methods.
+pub trait Key {
+    fn to_bytes(&self) -> Cow<'_, [u8]>;
+}
+
+/// A key type that can be read back from its encoding, for
+/// iterating over a [`TypedArt`](struct.TypedArt.html).
+pub trait DecodeKey: Key + Sized {
+    /// The key encoded as `bytes`, or `None` if they are not
+    /// the encoding of any key of this type.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<K: Key + ?Sized> Key for &K {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        (**self).to_bytes()
+    }
+}
+
+impl Key for [u8] {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<const N: usize> Key for [u8; N] {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl Key for Vec<u8> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl Key for str {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl Key for String {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+macro_rules! impl_key_for_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Key for $t {
+                fn to_bytes(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned(self.to_be_bytes().to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_key_for_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_key_with_encoding {
+    ($($t:ty => $encode:ident),*) => {
+        $(
+            impl Key for $t {
+                fn to_bytes(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned($encode(*self).to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_key_with_encoding!(
+    i8 => encode_i8,
+    i16 => encode_i16,
+    i32 => encode_i32,
+    i64 => encode_i64,
+    i128 => encode_i128,
+    f32 => encode_f32,
+    f64 => encode_f64
+);
+
+impl DecodeKey for Vec<u8> {
+    fn from_bytes(bytes: &[u8]) -> Option<Vec<u8>> {
+        Some(bytes.to_vec())
+    }
+}
+
+impl DecodeKey for String {
+    fn from_bytes(bytes: &[u8]) -> Option<String> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+// copies `bytes` into the array that `decode` takes
+fn fixed<A: Default + AsMut<[u8]>>(bytes: &[u8]) -> Option<A> {
+    let mut array = A::default();
+    if array.as_mut().len() != bytes.len() {
+        return None;
+    }
+    array.as_mut().copy_from_slice(bytes);
+    Some(array)
+}
+
+macro_rules! impl_decode_key {
+    ($($t:ty => $decode:expr),*) => {
+        $(
+            impl DecodeKey for $t {
+                fn from_bytes(bytes: &[u8]) -> Option<$t> {
+                    fixed(bytes).map($decode)
+                }
+            }
+        )*
+    };
+}
+
+impl_decode_key!(
+    u8 => u8::from_be_bytes,
+    u16 => u16::from_be_bytes,
+    u32 => u32::from_be_bytes,
+    u64 => u64::from_be_bytes,
+    u128 => u128::from_be_bytes,
+    usize => usize::from_be_bytes,
+    i8 => decode_i8,
+    i16 => decode_i16,
+    i32 => decode_i32,
+    i64 => decode_i64,
+    i128 => decode_i128,
+    f32 => decode_f32,
+    f64 => decode_f64
+);
@@ -0,0 +1,238 @@
+//! Order-preserving byte encodings for numbers.
+//!
+//! Keys are compared byte by byte, so numbers have to be
+//! encoded such that their bytes sort like the numbers do.
+//! Unsigned integers only need to be big-endian. Signed
+//! integers additionally have their sign bit flipped, so that
+//! negative numbers sort before positive ones. Floats are
+//! encoded in the order of `total_cmp`: negative NaNs, negative
+//! infinity, negative numbers, `-0.0`, `0.0`, positive numbers,
+//! positive infinity and positive NaNs.
+//!
+//! ```
+//! use cart::key_encoding::{decode_i32, encode_i32};
+//!
+//! assert!(encode_i32(-1) < encode_i32(0));
+//! assert_eq!(decode_i32(encode_i32(-7)), -7);
+//! ```
+//!
+//! Keys made of several components are built with
+//! `CompositeKey`.
+
+use std::borrow::Cow;
+
+use key::{DecodeKey, Key};
+
+// ends a variable length component. it sorts below both the
+// escaped zero byte and every other byte, so that a component
+// sorts before all components it is a prefix of.
+const TERMINATOR: [u8; 2] = [0, 1];
+// stands for a zero byte inside a variable length component
+const ESCAPED_ZERO: [u8; 2] = [0, 0xff];
+
+macro_rules! signed {
+    ($t:ty, $u:ty, $n:expr, $encode:ident, $decode:ident) => {
+        /// Encodes the integer big-endian with its sign bit
+        /// flipped.
+        pub fn $encode(value: $t) -> [u8; $n] {
+            ((value as $u) ^ (1 << ($n * 8 - 1))).to_be_bytes()
+        }
+
+        /// The inverse of the matching `encode` function.
+        pub fn $decode(bytes: [u8; $n]) -> $t {
+            (<$u>::from_be_bytes(bytes) ^ (1 << ($n * 8 - 1))) as $t
+        }
+    };
+}
+
+signed!(i8, u8, 1, encode_i8, decode_i8);
+signed!(i16, u16, 2, encode_i16, decode_i16);
+signed!(i32, u32, 4, encode_i32, decode_i32);
+signed!(i64, u64, 8, encode_i64, decode_i64);
+signed!(i128, u128, 16, encode_i128, decode_i128);
+
+macro_rules! float {
+    ($t:ty, $u:ty, $n:expr, $encode:ident, $decode:ident) => {
+        /// Encodes the float in the order of `total_cmp`. The
+        /// sign bit of positive numbers is set, and all bits of
+        /// negative numbers are flipped so that larger
+        /// magnitudes sort first.
+        pub fn $encode(value: $t) -> [u8; $n] {
+            let bits = value.to_bits();
+            let sign = 1 << ($n * 8 - 1);
+            let bits = if bits & sign == 0 { bits | sign } else { !bits };
+            bits.to_be_bytes()
+        }
+
+        /// The inverse of the matching `encode` function.
+        pub fn $decode(bytes: [u8; $n]) -> $t {
+            let bits = <$u>::from_be_bytes(bytes);
+            let sign = 1 << ($n * 8 - 1);
+            let bits = if bits & sign == 0 { !bits } else { bits ^ sign };
+            <$t>::from_bits(bits)
+        }
+    };
+}
+
+float!(f32, u32, 4, encode_f32, decode_f32);
+float!(f64, u64, 8, encode_f64, decode_f64);
+
+/// A key made of several components, like `(tenant, table,
+/// primary key)`, that sorts component by component.
+///
+/// Integers are encoded with a fixed width as described above.
+/// Byte strings and strings end with a terminator, and zero
+/// bytes inside them are escaped, so that no component can run
+/// into the next one and shorter components sort first. The
+/// components are read back with `CompositeKey::decode`, in the
+/// order and with the types they were pushed with.
+///
+/// ```
+/// use cart::key_encoding::CompositeKey;
+///
+/// let key = CompositeKey::new()
+///     .push_u64(7)
+///     .push_str("orders")
+///     .push_i64(-3);
+///
+/// let mut components = CompositeKey::decode(key.as_bytes());
+/// assert_eq!(components.next_u64(), Some(7));
+/// assert_eq!(components.next_str(), Some("orders".to_string()));
+/// assert_eq!(components.next_i64(), Some(-3));
+/// assert!(components.is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompositeKey {
+    bytes: Vec<u8>,
+}
+
+impl CompositeKey {
+    pub fn new() -> CompositeKey {
+        CompositeKey::default()
+    }
+
+    pub fn push_bytes(mut self, component: &[u8]) -> CompositeKey {
+        for &byte in component {
+            if byte == 0 {
+                self.bytes.extend_from_slice(&ESCAPED_ZERO);
+            } else {
+                self.bytes.push(byte);
+            }
+        }
+        self.bytes.extend_from_slice(&TERMINATOR);
+        self
+    }
+
+    pub fn push_str(self, component: &str) -> CompositeKey {
+        self.push_bytes(component.as_bytes())
+    }
+
+    pub fn push_u64(mut self, component: u64) -> CompositeKey {
+        self.bytes.extend_from_slice(&component.to_be_bytes());
+        self
+    }
+
+    pub fn push_i64(mut self, component: i64) -> CompositeKey {
+        self.bytes.extend_from_slice(&encode_i64(component));
+        self
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Reads the components of a key built by `CompositeKey`,
+    /// like one yielded while iterating over a tree.
+    pub fn decode(key: &[u8]) -> Components<'_> {
+        Components { rest: key }
+    }
+}
+
+impl Key for CompositeKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.bytes)
+    }
+}
+
+impl DecodeKey for CompositeKey {
+    fn from_bytes(bytes: &[u8]) -> Option<CompositeKey> {
+        Some(CompositeKey {
+            bytes: bytes.to_vec(),
+        })
+    }
+}
+
+/// The components of a `CompositeKey`, read from the front.
+///
+/// Components don't record their types, so they have to be read
+/// with the types they were pushed with. Every `next_` method
+/// returns `None`, leaving the components as they were, if the
+/// rest of the key is too short for its type. Byte strings
+/// also have to be terminated, and strings valid UTF-8.
+#[derive(Clone, Debug)]
+pub struct Components<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Components<'a> {
+    /// Whether all components were read.
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn next_bytes(&mut self) -> Option<Vec<u8>> {
+        let mut component = vec![];
+        let mut i = 0;
+
+        loop {
+            match *self.rest.get(i)? {
+                0 => {
+                    match *self.rest.get(i + 1)? {
+                        1 => break,
+                        0xff => component.push(0),
+                        _ => return None,
+                    }
+                    i += 2;
+                }
+                byte => {
+                    component.push(byte);
+                    i += 1;
+                }
+            }
+        }
+
+        self.rest = &self.rest[i + TERMINATOR.len()..];
+        Some(component)
+    }
+
+    pub fn next_str(&mut self) -> Option<String> {
+        let rest = self.rest;
+        let component = self.next_bytes()?;
+        String::from_utf8(component).ok().or_else(|| {
+            self.rest = rest;
+            None
+        })
+    }
+
+    pub fn next_u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.next_fixed()?))
+    }
+
+    pub fn next_i64(&mut self) -> Option<i64> {
+        Some(decode_i64(self.next_fixed()?))
+    }
+
+    fn next_fixed(&mut self) -> Option<[u8; 8]> {
+        if self.rest.len() < 8 {
+            return None;
+        }
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&self.rest[..8]);
+        self.rest = &self.rest[8..];
+        Some(bytes)
+    }
+}
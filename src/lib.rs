@@ -2,522 +2,3498 @@
 ///
 /// Important notes: nodes 48 must have
 /// pointers initialized to u8::MAX.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
 use std::fmt;
-use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::marker::PhantomData;
+use std::ops::{
+    Bound, ControlFlow, Index, IndexMut, RangeBounds,
+};
 use std::ptr::null_mut;
 
-#[derive(Clone, Debug)]
-pub struct Art<T> {
-    root: *mut Node<T>,
-}
+#[cfg(any(test, feature = "rand"))]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
-impl<T> Default for Art<T>
-where
-    T: fmt::Debug,
-{
-    fn default() -> Art<T> {
-        let root = Node::default();
-        let root_ptr = Box::into_raw(Box::new(root));
+mod automaton;
+mod builder;
+mod bulk;
+mod cursor;
+mod entry;
+mod error;
+mod iter;
+mod key;
+pub mod key_encoding;
+mod normalize;
+#[cfg(feature = "rayon")]
+mod par;
+mod prefix;
+#[cfg(feature = "rand")]
+mod sample;
+mod subtree;
+mod typed;
+mod version;
 
-        Art { root: root_ptr }
-    }
-}
+pub use automaton::Automaton;
+pub use builder::ArtBuilder;
+pub use cursor::{Cursor, CursorMut};
+pub use entry::{Entry, OccupiedEntry, OccupiedError, VacantEntry};
+pub use error::CartError;
+pub use iter::{
+    Drain, ExtractIf, GroupByPrefix, IntoIter, Iter, IterMut, Keys,
+    LendingIter, PrefixesOf, Range, RangeMut, StripPrefix, Values,
+    ValuesMut,
+};
+pub use key::{DecodeKey, Key};
+pub use normalize::{ascii_lowercase, NormalizedArt, Normalizer};
+pub use subtree::Subtree;
+pub use typed::{StrArt, TypedArt, TypedIter};
 
-impl<T> Art<T>
-where
-    T: fmt::Debug,
-{
-    pub fn set(&mut self, k: Vec<u8>, v: T) {
-        unsafe { (*self.root).set(k, v) }
-    }
+use automaton::{Glob, Levenshtein};
+use builder::Thresholds;
+use iter::IterRev;
+use prefix::{make_prefix, Interner, Prefix};
+use version::Version;
 
-    pub fn get<'a>(&self, k: &'a [u8]) -> Option<&'a T> {
-        unsafe { (*self.root).get(k) }
-    }
+#[derive(Debug)]
+pub struct Art<T, M = ()> {
+    root: *mut Node<T, M>,
+    thresholds: Thresholds,
+    interner: Option<Interner>,
+    // the number of keys, kept up to date by every operation
+    // that adds or removes one.
+    len: usize,
+    // the nodes the last descent for a key passed, each along
+    // with the byte leading to it from its parent, for updating
+    // counts and pruning on the way back without descending
+    // again. only valid until the tree changes otherwise.
+    path: Vec<(*mut Node<T, M>, u8)>,
+    // the tree owns its nodes, and through them values of `T`,
+    // which a raw pointer alone does not tell the drop checker.
+    _nodes: PhantomData<Box<Node<T, M>>>,
 }
 
-impl<T> Deref for Art<T> {
-    type Target = Node<T>;
-
-    fn deref(&self) -> &Node<T> {
-        unsafe { &*self.root }
+impl<T> Default for Art<T> {
+    fn default() -> Art<T> {
+        Art::with_thresholds(Thresholds::default())
     }
 }
 
-impl<T> DerefMut for Art<T> {
-    fn deref_mut(&mut self) -> &mut Node<T> {
-        unsafe { &mut *self.root }
-    }
+/// Counts of each node variant in a tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub node4: usize,
+    pub node16: usize,
+    pub node48: usize,
+    pub node256: usize,
 }
 
-#[derive(Clone)]
-pub enum Node<T> {
-    Node4 {
-        value: Option<T>,
-        prefix: Vec<u8>,
-        index: [u8; 4],
-        pointers: [*mut Node<T>; 4],
-    },
-    Node16 {
-        value: Option<T>,
-        prefix: Vec<u8>,
-        index: [u8; 16],
-        pointers: [*mut Node<T>; 16],
-    },
-    Node48 {
-        value: Option<T>,
-        prefix: Vec<u8>,
-        index: [u8; 256],
-        pointers: [*mut Node<T>; 48],
-    },
-    Node256 {
-        value: Option<T>,
-        prefix: Vec<u8>,
-        pointers: [*mut Node<T>; 256],
-    },
+/// A page of entries returned by
+/// [`Art::scan_prefix_paged`](struct.Art.html#method.scan_prefix_paged).
+#[derive(Debug, PartialEq)]
+pub struct Page<'a, T: 'a> {
+    pub entries: Vec<(Vec<u8>, &'a T)>,
+    /// Where the next page starts, to be passed back as
+    /// `resume_after`. `None` once the last page was returned.
+    pub resume_after: Option<Vec<u8>>,
 }
 
-use Node::*;
-
-impl<T> Default for Node<T>
-where
-    T: fmt::Debug,
-{
-    fn default() -> Node<T> {
-        Node4 {
-            value: None,
-            prefix: vec![],
-            index: [255; 4],
-            pointers: [null_mut(); 4],
-        }
-    }
+/// The error returned by
+/// [`Art::compare_and_swap`](struct.Art.html#method.compare_and_swap)
+/// when the stored value is not the expected one. It holds the
+/// value that is stored instead, and hands back the proposed
+/// one.
+#[derive(Debug, PartialEq)]
+pub struct CompareAndSwapError<'a, T: 'a> {
+    pub current: Option<&'a T>,
+    pub proposed: Option<T>,
 }
 
-impl<T> fmt::Debug for Node<T>
-where
-    T: fmt::Debug,
-{
+impl<'a, T> fmt::Display for CompareAndSwapError<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Node4 {
-                value,
-                prefix,
-                index,
-                pointers,
-            } => write!(
-                f,
-                "Node4 {{ value: {:?}, prefix: {:?}, index: {:?}, pointers: {:?} }}",
-                value, prefix, index, pointers
-            ),
-            Node16 {
-                value,
-                prefix,
-                index,
-                pointers,
-            } => write!(
-                f,
-                "Node16 {{ value: {:?}, prefix: {:?}, index: {:?}, pointers: {:?} }}",
-                value, prefix, index, pointers
-            ),
-            Node48 { value, prefix, .. } => write!(
-                f,
-                "Node48 {{ value: {:?}, prefix: {:?}, index: OMITTED, pointers: OMITTED }}",
-                value, prefix,
-            ),
-            Node256 { value, prefix, .. } => {
-                write!(f, "Node256 {{ value: {:?}, prefix: {:?}, OMITTED }}", value, prefix,)
-            }
-        }
+        write!(f, "the stored value is not the expected one")
     }
 }
 
-impl<T> Index<usize> for Node<T>
+impl<'a, T> std::error::Error for CompareAndSwapError<'a, T>
 where
     T: fmt::Debug,
 {
-    type Output = *mut Node<T>;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        match self {
-            Node4 { ref pointers, .. } => &pointers[index],
-            Node16 { ref pointers, .. } => &pointers[index],
-            Node48 { ref pointers, .. } => &pointers[index],
-            Node256 { ref pointers, .. } => &pointers[index],
-        }
-    }
 }
 
-impl<T> IndexMut<usize> for Node<T>
+impl<T, M> Art<T, M>
 where
-    T: fmt::Debug,
+    M: Default,
 {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        match self {
-            Node4 {
-                ref mut pointers, ..
-            } => &mut pointers[index],
-            Node16 {
-                ref mut pointers, ..
-            } => &mut pointers[index],
-            Node48 {
-                ref mut pointers, ..
-            } => &mut pointers[index],
-            Node256 {
-                ref mut pointers, ..
-            } => &mut pointers[index],
+    pub(crate) fn with_thresholds(
+        thresholds: Thresholds,
+    ) -> Art<T, M> {
+        let root = Node::default();
+        let root_ptr = Box::into_raw(Box::new(root));
+
+        Art {
+            root: root_ptr,
+            thresholds,
+            interner: None,
+            len: 0,
+            path: vec![],
+            _nodes: PhantomData,
         }
     }
-}
 
-impl<T> Node<T>
-where
-    T: fmt::Debug,
-{
-    pub fn set(&mut self, key: Vec<u8>, value: T) {
-        self.insert(&*key, 0, value);
+    pub(crate) fn with_interner(mut self) -> Art<T, M> {
+        self.interner = Some(Interner::default());
+        self
     }
 
-    fn insert(&mut self, key: &[u8], mut depth: usize, value: T) {
-        println!(
-            "key: {:?} depth: {} prefix: {:?}",
-            key,
-            depth,
-            self.prefix()
-        );
-        if self.prefix() == &key[depth..] {
-            self.set_value(value);
-            return;
+    pub(crate) fn with_root_capacity(
+        self,
+        children: usize,
+    ) -> Art<T, M> {
+        // the root is still empty, so nothing is lost by
+        // replacing it
+        unsafe {
+            *self.root =
+                Node::with_capacity(children, &self.thresholds);
         }
+        self
+    }
 
-        let common_prefix_len =
-            common_prefix_len(&*key, self.prefix());
-
-        // prefix mismatch, create a new parent for the current node
-        if common_prefix_len != self.prefix().len() {
-            println!("splitting prefix: ");
-            let common_prefix =
-                self.prefix()[..common_prefix_len].to_vec();
+    /// Sets the value of `k`, returning the value it replaced if
+    /// the key was already present. Besides bytes, `k` can be
+    /// anything implementing `Key`, like a string or an integer.
+    pub fn set<K: Key>(&mut self, k: K, v: T) -> Option<T> {
+        let k = k.to_bytes();
+        self.path.clear();
+        self.path.push((self.root, 0));
+        let old = unsafe {
+            (*self.root).insert(
+                &k,
+                0,
+                v,
+                &self.thresholds,
+                self.interner.as_mut(),
+                &mut self.path,
+            )
+        };
 
-            let old_byte = self.prefix()[common_prefix_len];
-            let old_prefix =
-                self.prefix()[common_prefix_len + 1..].to_vec();
-            let new_byte = key[common_prefix_len];
-            let new_prefix = key[common_prefix_len + 1..].to_vec();
+        if old.is_none() {
+            self.counted(1);
+        }
 
-            println!("old_byte: {}, old: {:?}", old_byte, old_prefix);
-            println!("common: {:?}", common_prefix);
-            println!("before: {:?}", self);
+        old
+    }
 
-            let mut node = Node::default();
-            node.set_prefix(common_prefix);
+    // updates `len` and the counts of the nodes on `path` after
+    // the value of its last node was added or, before pruning,
+    // removed
+    fn counted(&mut self, delta: isize) {
+        self.len = (self.len as isize + delta) as usize;
+        for &(node, _) in &self.path {
+            let count = unsafe { (*node).count_mut() };
+            *count = (*count as isize + delta) as usize;
+        }
+    }
 
-            std::mem::swap(self, &mut node);
+    /// The number of keys in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-            node.set_prefix(old_prefix);
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 
-            println!("after old: {:?}", node);
+    /// The value stored under `k`. It borrows from the tree,
+    /// so the key may be a temporary.
+    pub fn get<K>(&self, k: &K) -> Option<&T>
+    where
+        K: Key + ?Sized,
+    {
+        unsafe { (*self.root).get(&k.to_bytes()) }
+    }
 
-            self.add_child(old_byte, node);
+    /// Looks up `k` like `get`, also returning the key the value
+    /// is stored under. Keys are compared byte for byte, so that
+    /// is always `k` itself.
+    pub fn get_key_value<'k>(
+        &self,
+        k: &'k [u8],
+    ) -> Option<(&'k [u8], &T)> {
+        let value = unsafe { (*self.root).get(k)? };
+        Some((k, value))
+    }
 
-            let mut new_node = Node::default();
-            new_node.set_prefix(new_prefix);
-            new_node.set_value(value);
-            self.add_child(new_byte, new_node);
-            println!("after self: {:?}", self);
-            return;
-        }
+    /// Looks up every key in `keys`, returning the values in the
+    /// same order. The keys are looked up in sorted order, and
+    /// each descent starts from the deepest node it shares with
+    /// the previous key's instead of from the root.
+    pub fn multi_get<'k, I>(&self, keys: I) -> Vec<Option<&T>>
+    where
+        I: IntoIterator<Item = &'k [u8]>,
+    {
+        let keys: Vec<&[u8]> = keys.into_iter().collect();
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by_key(|i| keys[*i]);
 
-        depth += self.prefix().len();
+        let mut values = vec![None; keys.len()];
+        // the nodes on the path of the previous key, along with
+        // how many of its bytes lead to them. they are on the
+        // path of every key sharing those bytes.
+        let mut path = vec![(unsafe { &*self.root }, 0)];
+        let mut previous: &[u8] = &[];
 
-        println!(".");
-        if let Some(next_idx) = self.find_child(key[depth]) {
-            let ptr = self[next_idx];
-            unsafe { (*ptr).insert(key, depth + 1, value) }
-        } else {
-            if self.is_full() {
-                self.grow();
+        for i in order {
+            let key = keys[i];
+            let shared = common_prefix_len(previous, key);
+            while path[path.len() - 1].1 > shared {
+                path.pop();
             }
 
-            let new_node = Node4 {
-                value: Some(value),
-                prefix: key[depth + 1..].to_vec(),
-                index: [0u8; 4],
-                pointers: [null_mut(); 4],
-            };
+            values[i] = loop {
+                let (node, depth) = path[path.len() - 1];
+                if !key[depth..].starts_with(node.prefix()) {
+                    break None;
+                }
+                let skip = depth + node.prefix().len();
+                if skip == key.len() {
+                    break node.value();
+                }
 
-            println!("added child at byte {}", key[depth]);
-            self.add_child(key[depth], new_node);
+                match node.find_child(key[skip]) {
+                    Some(child_idx) => path.push((
+                        unsafe { &*node.child_at(child_idx) },
+                        skip + 1,
+                    )),
+                    None => break None,
+                }
+            };
+            previous = key;
         }
-    }
 
-    fn set_prefix(&mut self, p: Vec<u8>) {
-        match self {
-            Node4 { ref mut prefix, .. }
-            | Node16 { ref mut prefix, .. }
-            | Node48 { ref mut prefix, .. }
-            | Node256 { ref mut prefix, .. } => *prefix = p,
-        }
+        values
     }
 
-    fn set_value(&mut self, v: T) {
-        match self {
-            Node4 { ref mut value, .. }
-            | Node16 { ref mut value, .. }
-            | Node48 { ref mut value, .. }
-            | Node256 { ref mut value, .. } => *value = Some(v),
+    /// Returns a mutable reference to the value of `k`, for
+    /// updating it in place.
+    pub fn get_mut(&mut self, k: &[u8]) -> Option<&mut T> {
+        match unsafe { Node::slot(self.root, k, &mut self.path) } {
+            Slot::Found(node) => unsafe { (*node).value_mut() },
+            Slot::Vacant(..) => None,
         }
     }
 
-    pub fn get(&self, key: &[u8]) -> Option<&T> {
-        if !key.starts_with(self.prefix()) {
-            return None;
+    /// Mutable references to the values of all `keys` at once,
+    /// in the order of `keys`, for swapping or moving parts of
+    /// values between entries. Returns `None` if any key is
+    /// absent or listed twice, as the references would overlap.
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        keys: [&[u8]; N],
+    ) -> Option<[&mut T; N]> {
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].contains(key) {
+                return None;
+            }
         }
-        let skip = self.prefix().len();
 
-        if skip == key.len() {
-            return self.value();
+        // each descent only reads the nodes on its path, so the
+        // values found before stay valid even if they lie on it.
+        let mut values = [null_mut::<T>(); N];
+        for (value, key) in values.iter_mut().zip(keys.iter()) {
+            let slot =
+                unsafe { Node::slot(self.root, key, &mut self.path) };
+            *value = match slot {
+                Slot::Found(node) => unsafe { (*node).value_mut()? },
+                Slot::Vacant(..) => return None,
+            };
         }
+        // distinct keys are stored in distinct nodes
+        Some(values.map(|value| unsafe { &mut *value }))
+    }
 
-        let child_idx = match self.find_child(key[skip]) {
-            Some(c) => c,
-            None => return None,
-        };
+    /// Looks up `k` like `get`, but also returns how many bytes
+    /// of `k` were matched by node prefixes and child bytes
+    /// before the descent stopped. The value is only returned
+    /// if all of `k` matched.
+    pub fn get_with_consumed(&self, k: &[u8]) -> (usize, Option<&T>) {
+        let mut node = unsafe { &*self.root };
+        let mut consumed = 0;
 
-        let child = self[child_idx];
+        loop {
+            let matched =
+                common_prefix_len(&k[consumed..], node.prefix());
+            consumed += matched;
 
-        unsafe {
-            match *child {
-                ref next_node => next_node.get(&key[skip + 1..]),
+            if matched < node.prefix().len() {
+                return (consumed, None);
+            }
+            if consumed == k.len() {
+                return (consumed, node.value());
+            }
+
+            match node.find_child(k[consumed]) {
+                Some(child_idx) => {
+                    node = unsafe { &*node[child_idx] };
+                    consumed += 1;
+                }
+                None => return (consumed, None),
             }
         }
     }
 
-    fn value(&self) -> Option<&T> {
-        match self {
-            Node4 {
-                value: Some(ref v), ..
+    /// Returns the length of the key of the deepest node that
+    /// lies on the paths to both `a` and `b`, which is where
+    /// their paths through the tree branch. Both keys are
+    /// descended together until they part.
+    pub fn common_branch_depth(&self, a: &[u8], b: &[u8]) -> usize {
+        let mut node = unsafe { &*self.root };
+        let mut depth = 0;
+        let mut branch_depth = 0;
+
+        loop {
+            let end = depth + node.prefix().len();
+            if !a[depth..].starts_with(node.prefix())
+                || !b[depth..].starts_with(node.prefix())
+            {
+                return branch_depth;
             }
-            | Node16 {
-                value: Some(ref v), ..
+            branch_depth = end;
+
+            if a.len() == end || b.len() == end || a[end] != b[end] {
+                return branch_depth;
             }
-            | Node48 {
-                value: Some(ref v), ..
+
+            match node.find_child(a[end]) {
+                Some(child_idx) => {
+                    node = unsafe { &*node.child_at(child_idx) };
+                    depth = end + 1;
+                }
+                None => return branch_depth,
             }
-            | Node256 {
-                value: Some(ref v), ..
-            } => Some(v),
-            _ => None,
         }
     }
 
-    fn is_full(&self) -> bool {
-        match self {
-            Node4 { ref pointers, .. } => {
-                pointers.iter().all(|p| !p.is_null())
+    /// Replaces the value of `k` with what `f` returns for the
+    /// current one. `f` receives `None` if the key is absent,
+    /// and returning `None` removes the key. This covers insert,
+    /// update and conditional removal with a single descent.
+    /// A removal cleans up on the way back along its path.
+    pub fn update<F>(&mut self, k: Vec<u8>, f: F)
+    where
+        F: FnOnce(Option<T>) -> Option<T>,
+    {
+        self.fetch_and_update(&k, f);
+    }
+
+    /// Like `update`, but borrows the key and returns a mutable
+    /// reference to the new value, or `None` if the key ended up
+    /// absent. The key is only copied into the tree when it is
+    /// inserted. Should `f` panic, the key is left absent.
+    pub fn fetch_and_update<F>(
+        &mut self,
+        k: &[u8],
+        f: F,
+    ) -> Option<&mut T>
+    where
+        F: FnOnce(Option<T>) -> Option<T>,
+    {
+        let slot = unsafe { Node::slot(self.root, k, &mut self.path) };
+        let node = match slot {
+            Slot::Found(node) => node,
+            Slot::Vacant(..) => {
+                return match f(None) {
+                    Some(v) => Some(self.insert_at(slot, k, v)),
+                    None => None,
+                };
             }
-            Node16 { ref pointers, .. } => {
-                pointers.iter().all(|p| !p.is_null())
+        };
+
+        let old = unsafe { (*node).take_value() };
+        let removed = old.is_some();
+
+        // should `f` panic, the value it was handed is gone
+        let taken = if removed {
+            Some(Taken { art: self })
+        } else {
+            None
+        };
+        let new = f(old);
+        std::mem::forget(taken);
+
+        match new {
+            Some(v) => {
+                if !removed {
+                    self.counted(1);
+                }
+                unsafe {
+                    (*node).set_value(v);
+                    (*node).value_mut()
+                }
             }
-            Node48 { ref pointers, .. } => {
-                pointers.iter().all(|p| !p.is_null())
+            None => {
+                if removed {
+                    self.counted(-1);
+                    self.prune();
+                }
+                None
             }
-            Node256 { ref pointers, .. } => {
-                pointers.iter().all(|p| !p.is_null())
+        }
+    }
+
+    /// The entry for `k`, for inspecting and changing its value
+    /// in place with a single descent.
+    pub fn entry(&mut self, k: Vec<u8>) -> Entry<'_, T, M> {
+        Entry::new(self, k)
+    }
+
+    /// Inserts `v` under `k` unless the key already holds a
+    /// value, in which case nothing is changed and the rejected
+    /// key and value are returned in the error.
+    pub fn try_insert(
+        &mut self,
+        k: Vec<u8>,
+        v: T,
+    ) -> Result<&mut T, OccupiedError<T>> {
+        match self.entry(k) {
+            Entry::Occupied(entry) => Err(OccupiedError {
+                key: entry.into_key(),
+                value: v,
+            }),
+            Entry::Vacant(entry) => Ok(entry.insert(v)),
+        }
+    }
+
+    /// Returns the value of `k`, inserting what `default`
+    /// returns first if there is none. The key is only copied
+    /// into the tree when it is inserted, and both cases take a
+    /// single descent.
+    pub fn get_or_insert_with<F>(
+        &mut self,
+        k: &[u8],
+        default: F,
+    ) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        let slot = unsafe { Node::slot(self.root, k, &mut self.path) };
+
+        if let Slot::Found(node) = slot {
+            if let Some(value) = unsafe { (*node).value_mut() } {
+                return value;
             }
         }
+
+        self.insert_at(slot, k, default())
     }
 
-    fn add_child(&mut self, byte: u8, child: Node<T>) {
-        let ptr = Box::into_raw(Box::new(child));
+    // inserts `v` for `k` where the descent for `k` ended, which
+    // must not hold a value yet.
+    fn insert_at(
+        &mut self,
+        slot: Slot<T, M>,
+        k: &[u8],
+        v: T,
+    ) -> &mut T {
+        let (node, depth) = match slot {
+            Slot::Found(node) => {
+                (node, k.len() - unsafe { (*node).prefix().len() })
+            }
+            Slot::Vacant(node, depth) => (node, depth),
+        };
 
-        match self {
-            Node4 {
-                index, pointers, ..
-            } => {
-                let idx = pointers
-                    .iter()
-                    .position(|p| p.is_null())
-                    .expect("node must not be empty");
-                index[idx] = byte;
-                pointers[idx] = ptr;
+        // the descent recorded the path down to `node`, which
+        // the insert carries on to the node holding the value.
+        unsafe {
+            (*node).insert(
+                k,
+                depth,
+                v,
+                &self.thresholds,
+                self.interner.as_mut(),
+                &mut self.path,
+            );
+        }
+        self.counted(1);
+        let (node, _) = self.path[self.path.len() - 1];
+        unsafe { (*node).value_mut().expect("value was just inserted") }
+    }
+
+    /// Moves all entries of `other` into this tree, leaving
+    /// `other` empty. Values of keys present in both trees are
+    /// replaced by the ones from `other`. Subtrees of `other`
+    /// whose keys don't occur in this tree are linked in as
+    /// they are instead of being inserted key by key, so
+    /// appending trees over disjoint key ranges is cheap.
+    pub fn append(&mut self, other: &mut Art<T, M>) {
+        let new_root = Box::into_raw(Box::new(Node::default()));
+        let other_root = std::mem::replace(&mut other.root, new_root);
+        let mut len = self.len + std::mem::replace(&mut other.len, 0);
+
+        let root_key = unsafe { (*other_root).prefix().to_vec() };
+        let mut stack = vec![(root_key, other_root)];
+
+        while let Some((key, ptr)) = stack.pop() {
+            let node = unsafe { &mut *ptr };
+            let children = node.children();
+
+            if node.value().is_none() && children.is_empty() {
+                // only an empty root has neither
+                unsafe { free(ptr) };
+                continue;
             }
-            Node16 {
-                index, pointers, ..
-            } => {
-                let idx = pointers
-                    .iter()
-                    .position(|p| p.is_null())
-                    .expect("node must not be empty");
-                index[idx] = byte;
-                pointers[idx] = ptr;
+
+            if self.root_is_empty()
+                || unsafe { (*self.root).find_prefix(&key) }.is_none()
+            {
+                self.link(&key, ptr);
+                continue;
             }
-            Node48 {
-                index, pointers, ..
-            } => {
-                let idx = pointers
-                    .iter()
-                    .position(|p| p.is_null())
-                    .expect("node must not be empty");
-                index[byte as usize] = idx as u8;
-                pointers[idx] = ptr;
+
+            // both trees have keys below this node, so it is
+            // taken apart and merged one level further down.
+            for (byte, child) in children {
+                let child_key =
+                    iter::child_key(&key, byte, unsafe { &*child });
+                stack.push((child_key, child));
             }
-            Node256 { pointers, .. } => {
-                if !pointers[byte as usize].is_null() {
-                    panic!("replacing existing node");
+            // `len` already counts the value, and is what
+            // `self.len` ends up as
+            if let Some(value) = node.take_value() {
+                if self.set(&key[..], value).is_some() {
+                    len -= 1;
                 }
-
-                pointers[byte as usize] = ptr;
             }
+            // its children were moved onto the stack already
+            drop(unsafe { Box::from_raw(ptr) });
         }
+
+        self.len = len;
     }
 
-    fn grow(&mut self) {
-        let new = match self {
-            Node4 {
-                value,
-                index,
-                pointers,
-                prefix,
-            } => {
-                let old = index
-                    .iter()
-                    .cloned()
-                    .zip(pointers.iter().cloned());
+    /// Moves all entries with keys of at least `key` into a new
+    /// tree, which is returned. Only the nodes along the path
+    /// to `key` are split, the subtrees on either side of it
+    /// are moved over as they are.
+    pub fn split_off(&mut self, key: &[u8]) -> Art<T, M> {
+        let mut other = Art::with_thresholds(self.thresholds);
+        if self.interner.is_some() {
+            other = other.with_interner();
+        }
 
-                let mut index = [0u8; 16];
-                let mut pointers = [null_mut(); 16];
+        // subtrees that move along with their full keys, and the
+        // nodes on the path to `key` that lose some children
+        // along with the byte leading to them.
+        let mut moved = vec![];
+        let mut path: Vec<(*mut Node<T, M>, u8)> = vec![];
 
-                for (i, (byte, ptr)) in old.enumerate() {
-                    index[i] = byte;
-                    pointers[i] = ptr;
-                }
+        let mut ptr = self.root;
+        let mut byte = 0;
+        let mut full_key = unsafe { (*ptr).prefix().to_vec() };
 
-                Node16 {
-                    value: value.take(),
-                    prefix: prefix.clone(),
-                    index: index,
-                    pointers: pointers,
+        loop {
+            if &full_key[..] >= key {
+                // every key below this node is at least `key`
+                match path.last() {
+                    Some(&(parent, _)) => unsafe {
+                        (*parent).remove_child(byte);
+                        moved.push((full_key, ptr));
+                    },
+                    None => {
+                        std::mem::swap(&mut self.root, &mut other.root);
+                        std::mem::swap(&mut self.len, &mut other.len);
+                        return other;
+                    }
                 }
+                break;
+            }
+            if !key.starts_with(&full_key) {
+                // every key below this node is smaller
+                break;
             }
-            Node16 {
-                value,
-                prefix,
-                index,
-                pointers,
-            } => {
-                let old = index
-                    .iter()
-                    .cloned()
-                    .zip(pointers.iter().cloned());
-
-                let mut index = [0u8; 256];
-                let mut pointers = [null_mut(); 48];
 
-                for (i, (byte, ptr)) in old.enumerate() {
-                    index[byte as usize] = i as u8;
-                    pointers[i] = ptr;
-                }
+            let node = unsafe { &mut *ptr };
+            path.push((ptr, byte));
 
-                Node48 {
-                    value: value.take(),
-                    prefix: prefix.clone(),
-                    index: index,
-                    pointers: pointers,
+            let next = key[full_key.len()];
+            for (child_byte, child) in node.children() {
+                if child_byte > next {
+                    node.remove_child(child_byte);
+                    let child_key = iter::child_key(
+                        &full_key,
+                        child_byte,
+                        unsafe { &*child },
+                    );
+                    moved.push((child_key, child));
                 }
             }
-            Node48 {
-                value,
+
+            let child_idx = match node.find_child(next) {
+                Some(child_idx) => child_idx,
+                None => break,
+            };
+            ptr = node[child_idx];
+            byte = next;
+            full_key =
+                iter::child_key(&full_key, next, unsafe { &*ptr });
+        }
+
+        for (key, ptr) in moved {
+            other.len += unsafe { (*ptr).count() };
+            other.link(&key, ptr);
+        }
+        self.len -= other.len;
+
+        // restores the node invariants bottom-up, all nodes on
+        // the path may have lost children.
+        while let Some((ptr, byte)) = path.pop() {
+            let node = unsafe { &mut *ptr };
+            node.recount();
+            let children = node.child_count();
+
+            if node.value().is_some() || children > 1 {
+                node.shrink(&self.thresholds);
+            } else if children == 1 {
+                node.merge_with_child(self.interner.as_mut());
+            } else {
+                match path.last() {
+                    Some(&(parent, _)) => unsafe {
+                        (*parent).remove_child(byte);
+                        free(ptr);
+                    },
+                    // the tree is empty now
+                    None => node.set_prefix(Prefix::default()),
+                }
+            }
+        }
+
+        other
+    }
+
+    fn root_is_empty(&self) -> bool {
+        let root = unsafe { &*self.root };
+        root.value().is_none() && root.child_count() == 0
+    }
+
+    // makes `subtree` part of the tree with `key` as the full
+    // key of its top node. no key in the tree may start with
+    // `key`.
+    fn link(&mut self, key: &[u8], subtree: *mut Node<T, M>) {
+        let count = unsafe { (*subtree).count() };
+        if self.root_is_empty() {
+            unsafe {
+                (*subtree).set_prefix(make_prefix(
+                    &mut self.interner.as_mut(),
+                    key,
+                ));
+                free(std::mem::replace(&mut self.root, subtree));
+            }
+        } else {
+            unsafe {
+                (*self.root).attach(
+                    key,
+                    0,
+                    subtree,
+                    &self.thresholds,
+                    self.interner.as_mut(),
+                );
+                // the nodes above the subtree, whose full keys
+                // are the shorter prefixes of `key`
+                (*self.root).count_path(
+                    &key[..key.len() - 1],
+                    0,
+                    count as isize,
+                );
+            }
+        }
+    }
+
+    /// Sets the value of `k` to `new`, or removes it for `None`,
+    /// but only if its current value equals `expected`, where
+    /// `None` expects the key to be absent. Returns the value
+    /// that was replaced or removed.
+    pub fn compare_and_swap(
+        &mut self,
+        k: &[u8],
+        expected: Option<&T>,
+        new: Option<T>,
+    ) -> Result<Option<T>, CompareAndSwapError<'_, T>>
+    where
+        T: PartialEq,
+    {
+        let slot = unsafe { Node::slot(self.root, k, &mut self.path) };
+        let current = match slot {
+            Slot::Found(node) => unsafe { (*node).value() },
+            Slot::Vacant(..) => None,
+        };
+
+        if current != expected {
+            return Err(CompareAndSwapError {
+                current,
+                proposed: new,
+            });
+        }
+
+        match (slot, new) {
+            (Slot::Found(node), Some(v)) if current.is_some() => {
+                Ok(unsafe { (*node).set_value(v) })
+            }
+            (slot, Some(v)) => {
+                self.insert_at(slot, k, v);
+                Ok(None)
+            }
+            (Slot::Found(node), None) if current.is_some() => {
+                let old = unsafe { (*node).take_value() };
+                self.counted(-1);
+                self.prune();
+                Ok(old)
+            }
+            (_, None) => Ok(None),
+        }
+    }
+
+    /// Removes `k` from the tree, returning its value if it was
+    /// present. Nodes left without a value or children are
+    /// freed, a node left with a single child is merged into it,
+    /// and nodes shrink back into smaller variants as their
+    /// children go away.
+    pub fn remove(&mut self, k: &[u8]) -> Option<T> {
+        self.remove_if(k, |_| true)
+    }
+
+    /// Removes the value of `k` and returns it if `pred` holds
+    /// for it. Otherwise the entry is left untouched and `None`
+    /// is returned, so this can evict an entry only if it is,
+    /// say, expired.
+    pub fn remove_if<F>(&mut self, k: &[u8], pred: F) -> Option<T>
+    where
+        F: FnOnce(&T) -> bool,
+    {
+        let slot = unsafe { Node::slot(self.root, k, &mut self.path) };
+        let node = match slot {
+            Slot::Found(node) => unsafe { &mut *node },
+            Slot::Vacant(..) => return None,
+        };
+
+        if !pred(node.value()?) {
+            return None;
+        }
+
+        let value = node.take_value();
+        self.counted(-1);
+        self.prune();
+        value
+    }
+
+    // restores the node invariants along `path`, whose last
+    // node just lost its value or a child. works upwards from
+    // that node for as long as nodes become empty and are
+    // unlinked.
+    fn prune(&mut self) {
+        while let Some((ptr, byte)) = self.path.pop() {
+            let node = unsafe { &mut *ptr };
+            let children = node.child_count();
+
+            if node.value().is_some() || children > 1 {
+                node.shrink(&self.thresholds);
+                return;
+            }
+
+            if children == 1 {
+                node.merge_with_child(self.interner.as_mut());
+                return;
+            }
+
+            match self.path.last() {
+                Some(&(parent, _)) => unsafe {
+                    (*parent).remove_child(byte);
+                    free(ptr);
+                },
+                // the tree is empty now
+                None => node.set_prefix(Prefix::default()),
+            }
+        }
+    }
+
+    /// Removes every key starting with `prefix`, returning how
+    /// many there were. The subtree below `prefix` is unlinked
+    /// and freed as a whole instead of removing its keys one by
+    /// one.
+    pub fn remove_prefix(&mut self, prefix: &[u8]) -> usize {
+        self.path.clear();
+        self.path.push((self.root, 0));
+        let mut ptr = self.root;
+        let mut depth = 0;
+
+        loop {
+            let node = unsafe { &*ptr };
+            let rest = &prefix[depth..];
+            if rest.len() <= node.prefix().len() {
+                if !node.prefix().starts_with(rest) {
+                    return 0;
+                }
+                break;
+            }
+            if !rest.starts_with(node.prefix()) {
+                return 0;
+            }
+
+            let next = depth + node.prefix().len();
+            let child_idx = match node.find_child(prefix[next]) {
+                Some(child_idx) => child_idx,
+                None => return 0,
+            };
+            ptr = unsafe { node.child_at(child_idx) };
+            self.path.push((ptr, prefix[next]));
+            depth = next + 1;
+        }
+
+        let removed = unsafe { (*ptr).count() };
+        let (_, byte) = self.path.pop().expect("path ends at ptr");
+        match self.path.last() {
+            Some(&(parent, _)) => {
+                self.counted(-(removed as isize));
+                unsafe {
+                    (*parent).remove_child(byte);
+                    free(ptr);
+                }
+                self.prune();
+            }
+            // every key starts with `prefix`
+            None => self.clear(),
+        }
+
+        removed
+    }
+
+    /// Removes the values of all `keys`, returning what was
+    /// stored under each of them, or `None` if nothing was, in
+    /// the order of `keys`. The keys are removed in key order
+    /// by a cursor, so each seek only walks the part of the
+    /// path that a key does not share with the one before it.
+    /// Nodes are still collapsed after every single removal.
+    /// A key listed twice is only removed once.
+    pub fn remove_many(&mut self, keys: &[&[u8]]) -> Vec<Option<T>> {
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by_key(|i| keys[*i]);
+
+        let mut removed: Vec<Option<T>> =
+            keys.iter().map(|_| None).collect();
+        let mut cursor = self.cursor_mut();
+        for i in order {
+            if cursor.seek(keys[i]) && cursor.key() == Some(keys[i]) {
+                removed[i] = cursor.remove_current();
+            }
+        }
+
+        removed
+    }
+
+    /// Keeps only the entries for which `f` returns `true`,
+    /// calling it in key order. Unlike removing the other keys
+    /// one by one, this walks the tree once and restores the
+    /// node invariants bottom-up on the way back. Should `f`
+    /// panic, the entries it rejected until then are removed
+    /// and the rest are kept.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[u8], &mut T) -> bool,
+    {
+        let root_key = unsafe { (*self.root).prefix().to_vec() };
+        // nodes are pushed once to filter their own value and
+        // expand their children, and once more to be pruned
+        // after all of them were. the parent is null for the
+        // root.
+        let stack = vec![(root_key, self.root, null_mut(), 0, false)];
+        let mut walk = Retain { art: self, stack };
+
+        while let Some((key, ptr, parent, byte, expanded)) =
+            walk.stack.pop()
+        {
+            if expanded {
+                walk.prune(ptr, parent, byte);
+                continue;
+            }
+
+            let node = unsafe { &mut *ptr };
+            let keep = match node.value_mut() {
+                Some(value) => f(&key, value),
+                None => true,
+            };
+            if !keep {
+                node.take_value();
+                walk.art.len -= 1;
+            }
+
+            walk.stack.push((key.clone(), ptr, parent, byte, true));
+            // reversed, so that children are popped in ascending
+            // byte order
+            let children = node.children();
+            for (child_byte, child) in children.into_iter().rev() {
+                let child_key =
+                    iter::child_key(&key, child_byte, unsafe {
+                        &*child
+                    });
+                walk.stack
+                    .push((child_key, child, ptr, child_byte, false));
+            }
+        }
+    }
+
+    /// Returns an iterator that removes and yields the entries
+    /// for which `pred` returns `true`, in key order. Entries
+    /// are only looked at as the iterator advances, and the
+    /// ones it is dropped before are left in place.
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F, M>
+    where
+        F: FnMut(&[u8], &mut T) -> bool,
+    {
+        ExtractIf::new(self, pred)
+    }
+
+    /// Consumes the tree and turns every value into `f(key,
+    /// value)`, calling `f` in key order. The new tree has the
+    /// same node layout as this one instead of being built by
+    /// inserting every key again.
+    pub fn map<U, F>(self, mut f: F) -> Art<U, M>
+    where
+        F: FnMut(&[u8], T) -> U,
+    {
+        // the nodes are taken apart below, the old tree must not
+        // free them again.
+        let mut old = std::mem::ManuallyDrop::new(self);
+        let root = old.root;
+        let thresholds = old.thresholds;
+        let interner = old.interner.take();
+        let len = old.len;
+        drop(std::mem::take(&mut old.path));
+
+        let root_key = unsafe { (*root).prefix().to_vec() };
+        let mut mapping = Mapping {
+            new_root: null_mut(),
+            stack: vec![(root_key, root, None)],
+        };
+
+        while let Some((key, ptr, parent)) = mapping.stack.pop() {
+            // the old node's children are owned by the stack
+            // until they are mapped in turn.
+            let mut old = unsafe { Box::from_raw(ptr) };
+
+            let mut new = old.empty_like();
+            new.set_prefix(std::mem::take(old.prefix_mut()));
+            std::mem::swap(new.meta_mut(), old.meta_mut());
+            *new.count_mut() = old.count();
+
+            let new_ptr = Box::into_raw(Box::new(new));
+            match parent {
+                None => mapping.new_root = new_ptr,
+                Some((parent, byte)) => unsafe {
+                    (*parent).set_child(byte, new_ptr)
+                },
+            }
+
+            for (byte, child) in old.children().into_iter().rev() {
+                let child_key =
+                    iter::child_key(&key, byte, unsafe { &*child });
+                mapping.stack.push((
+                    child_key,
+                    child,
+                    Some((new_ptr, byte)),
+                ));
+            }
+
+            // only now that all nodes are owned by `mapping`
+            if let Some(value) = old.take_value() {
+                unsafe { (*new_ptr).set_value(f(&key, value)) };
+            }
+        }
+
+        Art {
+            root: std::mem::replace(&mut mapping.new_root, null_mut()),
+            thresholds,
+            interner,
+            len,
+            path: vec![],
+            _nodes: PhantomData,
+        }
+    }
+
+    /// Replaces the value of an existing key, returning the old
+    /// one. Unlike `set`, nothing is inserted if the key is
+    /// absent, in which case `None` is returned.
+    pub fn replace(&mut self, k: &[u8], v: T) -> Option<T> {
+        let slot = self.get_mut(k)?;
+
+        Some(std::mem::replace(slot, v))
+    }
+
+    /// Removes every entry from the tree, dropping all values and
+    /// nodes. The tree starts over with an empty Node4 root.
+    pub fn clear(&mut self) {
+        let new_root = Box::into_raw(Box::new(Node::default()));
+        let old_root = std::mem::replace(&mut self.root, new_root);
+        self.len = 0;
+
+        // releases the chunks of interned prefixes along with
+        // the nodes referring to them
+        if self.interner.is_some() {
+            self.interner = Some(Interner::default());
+        }
+
+        unsafe { free(old_root) }
+    }
+
+    /// Removes every entry from the tree, yielding them in key
+    /// order. The tree is reset to a fresh root immediately, so
+    /// it is empty and reusable once the `Drain` is gone, even
+    /// if it was not fully consumed. Entries that were not
+    /// yielded are dropped along with the `Drain`.
+    pub fn drain(&mut self) -> Drain<'_, T, M> {
+        let new_root = Box::into_raw(Box::new(Node::default()));
+        let old_root = std::mem::replace(&mut self.root, new_root);
+        self.len = 0;
+
+        // the drained nodes keep their chunks alive on their own
+        if self.interner.is_some() {
+            self.interner = Some(Interner::default());
+        }
+
+        Drain::new(old_root)
+    }
+
+    /// Iterates over the entries in ascending key order. Keys
+    /// are put back together from the prefixes and child bytes
+    /// along their path, and a key comes before all keys it is
+    /// a prefix of.
+    pub fn iter(&self) -> Iter<'_, T, M> {
+        Iter::new(unsafe { &*self.root })
+    }
+
+    /// Iterates over the keys in ascending order.
+    pub fn keys(&self) -> Keys<'_, T, M> {
+        Keys::new(unsafe { &*self.root })
+    }
+
+    /// Iterates over the values in key order. The keys are not
+    /// put together, so this is cheaper than `iter`.
+    pub fn values(&self) -> Values<'_, T, M> {
+        Values::new(unsafe { &*self.root })
+    }
+
+    /// Iterates over the values in key order, with mutable
+    /// access to them. Like `values`, this does not put the
+    /// keys together.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T, M> {
+        ValuesMut::new(self.root)
+    }
+
+    /// Like `iter`, but without allocating a key per entry. Each
+    /// key is lent out of a buffer that the next call reuses:
+    ///
+    /// ```
+    /// let mut art = cart::Art::default();
+    /// art.set(b"a".to_vec(), 1);
+    /// art.set(b"b".to_vec(), 2);
+    ///
+    /// let mut entries = art.lending_iter();
+    /// while let Some((key, value)) = entries.next() {
+    ///     println!("{:?} = {}", key, value);
+    /// }
+    /// ```
+    pub fn lending_iter(&self) -> LendingIter<'_, T, M> {
+        LendingIter::new(unsafe { &*self.root })
+    }
+
+    /// Iterates over the entries with keys in `range`, in key
+    /// order. Subtrees whose keys all lie below or above the
+    /// range are skipped by looking at their prefix alone.
+    ///
+    /// The bounds can be anything that derefs to bytes, like
+    /// `&b"a"[..]..&b"b"[..]` or `vec![1]..`. A pair of
+    /// `Bound<&[u8]>` and `..` need the key type spelled out, as
+    /// in `range::<[u8], _>(..)`. An empty range, including one
+    /// whose start lies past its end, yields nothing.
+    pub fn range<K, R>(&self, range: R) -> Range<'_, T, M>
+    where
+        K: AsRef<[u8]> + ?Sized,
+        R: RangeBounds<K>,
+    {
+        Range::new(
+            unsafe { &*self.root },
+            range.start_bound(),
+            range.end_bound(),
+        )
+    }
+
+    /// Like `range`, but with mutable access to the values.
+    pub fn range_mut<K, R>(&mut self, range: R) -> RangeMut<'_, T, M>
+    where
+        K: AsRef<[u8]> + ?Sized,
+        R: RangeBounds<K>,
+    {
+        RangeMut::new(self.root, range.start_bound(), range.end_bound())
+    }
+
+    /// The number of keys in `range`, without visiting them.
+    /// Only the paths to both ends of the range are descended,
+    /// the subtrees beside them are counted as a whole by the
+    /// counts their nodes keep.
+    pub fn range_count<K, R>(&self, range: R) -> usize
+    where
+        K: AsRef<[u8]> + ?Sized,
+        R: RangeBounds<K>,
+    {
+        let root = unsafe { &*self.root };
+        let start = match range.start_bound() {
+            Bound::Included(k) => root.count_below(k.as_ref(), false),
+            Bound::Excluded(k) => root.count_below(k.as_ref(), true),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(k) => root.count_below(k.as_ref(), true),
+            Bound::Excluded(k) => root.count_below(k.as_ref(), false),
+            Bound::Unbounded => self.len,
+        };
+
+        // nothing lies in an inverted range
+        end.saturating_sub(start)
+    }
+
+    /// A cursor pointing at the entry with the smallest key. It
+    /// can be moved with `seek`, `next` and `prev`, which start
+    /// from where the cursor is instead of from the root.
+    pub fn cursor(&self) -> Cursor<'_, T, M> {
+        Cursor::new(unsafe { &*self.root })
+    }
+
+    /// Like `cursor`, but the cursor can also change values and
+    /// remove or insert entries near where it points.
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, M> {
+        CursorMut::new(self)
+    }
+
+    /// Iterates over the entries whose keys start with `prefix`,
+    /// in key order. Only the subtree below `prefix` is visited.
+    pub fn iter_prefix(&self, prefix: &[u8]) -> Iter<'_, T, M> {
+        match unsafe { (*self.root).find_prefix(prefix) } {
+            Some((key, node)) => Iter::subtree(key, node),
+            None => Iter::empty(),
+        }
+    }
+
+    /// Another name for `iter_prefix`, matching
+    /// `scan_prefix_paged` and what other ordered maps call it.
+    pub fn scan_prefix(&self, prefix: &[u8]) -> Iter<'_, T, M> {
+        self.iter_prefix(prefix)
+    }
+
+    /// Returns up to `limit` entries whose keys start with
+    /// `prefix`, beginning after the key `resume_after` if one
+    /// is given. Nothing is held between pages, so entries that
+    /// change in the meantime show up on later pages as they
+    /// are then.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    pub fn scan_prefix_paged(
+        &self,
+        prefix: &[u8],
+        limit: usize,
+        resume_after: Option<&[u8]>,
+    ) -> Page<'_, T> {
+        self.try_scan_prefix_paged(prefix, limit, resume_after)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `scan_prefix_paged`, but returns an error for a
+    /// `limit` of zero instead of panicking.
+    pub fn try_scan_prefix_paged(
+        &self,
+        prefix: &[u8],
+        limit: usize,
+        resume_after: Option<&[u8]>,
+    ) -> Result<Page<'_, T>, CartError> {
+        if limit == 0 {
+            return Err(CartError::ZeroLimit);
+        }
+
+        let start = match resume_after {
+            Some(after) if after >= prefix => Bound::Excluded(after),
+            _ => Bound::Included(prefix),
+        };
+
+        // one entry more than asked for tells whether there is
+        // another page
+        let mut entries: Vec<(Vec<u8>, &T)> = self
+            .range::<[u8], _>((start, Bound::Unbounded))
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .take(limit + 1)
+            .collect();
+
+        let resume_after = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|(key, _)| key.clone())
+        } else {
+            None
+        };
+
+        Ok(Page {
+            entries,
+            resume_after,
+        })
+    }
+
+    /// Iterates over the stored keys that are prefixes of
+    /// `query`, shortest first, like the dictionary words a
+    /// text starts with. They all lie on the path of `query`,
+    /// so this is a single descent.
+    pub fn prefixes_of<'q>(
+        &self,
+        query: &'q [u8],
+    ) -> PrefixesOf<'_, 'q, T, M> {
+        PrefixesOf::new(unsafe { &*self.root }, query)
+    }
+
+    /// Finds the entries whose keys are at most `max_edits`
+    /// insertions, deletions or substitutions of single bytes
+    /// away from `key`, in key order and along with their edit
+    /// distance. The distances of every key prefix are computed
+    /// row by row on the way down, and subtrees whose prefix
+    /// already needs more edits are skipped.
+    pub fn fuzzy_search(
+        &self,
+        key: &[u8],
+        max_edits: usize,
+    ) -> Vec<(Vec<u8>, &T, usize)> {
+        let levenshtein = Levenshtein { key, max_edits };
+
+        automaton::intersect(unsafe { &*self.root }, &levenshtein)
+            .into_iter()
+            .map(|(found, value, row)| (found, value, row[key.len()]))
+            .collect()
+    }
+
+    /// Finds the entries whose keys match `pattern`, in key
+    /// order. In the pattern `?` stands for any single byte and
+    /// `*` for any run of bytes, including none, every other
+    /// byte stands for itself. Only branches that can still
+    /// match are descended into.
+    pub fn match_pattern(&self, pattern: &[u8]) -> Vec<(Vec<u8>, &T)> {
+        self.search(&Glob { pattern })
+    }
+
+    /// Finds the entries whose keys `automaton` accepts, in key
+    /// order. Keys are fed to the automaton as the tree is
+    /// descended, so a subtree is skipped as soon as its prefix
+    /// leads into a state that can't match.
+    pub fn search<A>(&self, automaton: &A) -> Vec<(Vec<u8>, &T)>
+    where
+        A: Automaton,
+    {
+        automaton::intersect(unsafe { &*self.root }, automaton)
+            .into_iter()
+            .map(|(key, value, _)| (key, value))
+            .collect()
+    }
+
+    /// The `k` entries under `prefix` with the highest scores,
+    /// best first, like the completions to offer for a typed
+    /// prefix. Equal scores are ranked in key order. Only `k`
+    /// entries are kept while the subtree is walked, and keys
+    /// are only copied for entries that make it into them.
+    /// Ranking by value is `|_, value| value.clone()`.
+    pub fn top_k_in_prefix<S, F>(
+        &self,
+        prefix: &[u8],
+        k: usize,
+        mut score: F,
+    ) -> Vec<(Vec<u8>, &T)>
+    where
+        S: Ord,
+        F: FnMut(&[u8], &T) -> S,
+    {
+        if k == 0 {
+            return vec![];
+        }
+
+        // the worst of the entries kept so far is on top
+        let mut heap: BinaryHeap<Ranked<S, &T>> =
+            BinaryHeap::with_capacity(k + 1);
+        let mut seq = 0;
+
+        self.walk(prefix, |key, value| {
+            let score = score(key, value);
+            seq += 1;
+
+            let kept = match heap.peek() {
+                Some(worst) if heap.len() == k => score > worst.score,
+                _ => true,
+            };
+            if kept {
+                heap.push(Ranked {
+                    score,
+                    seq,
+                    key: key.to_vec(),
+                    value,
+                });
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+
+        let mut ranked = heap.into_vec();
+        ranked.sort();
+        ranked.into_iter().map(|r| (r.key, r.value)).collect()
+    }
+
+    /// The number of keys starting with `prefix`. Every node
+    /// keeps the number of values below it up to date, so this
+    /// only descends to `prefix` instead of visiting the keys.
+    pub fn count_prefix(&self, prefix: &[u8]) -> usize {
+        unsafe { (*self.root).find_prefix(prefix) }
+            .map_or(0, |(_, node)| node.count())
+    }
+
+    /// A view of the entries whose keys start with `prefix`,
+    /// which looks up and yields keys relative to it. Nothing is
+    /// copied, the view borrows the tree.
+    pub fn subtree(&self, prefix: &[u8]) -> Subtree<'_, T, M> {
+        Subtree::new(unsafe { &*self.root }, prefix)
+    }
+
+    /// Like `iter_prefix`, but yields the keys with `prefix`
+    /// removed. A key equal to `prefix` is yielded as an empty
+    /// key.
+    pub fn iter_prefix_stripped(
+        &self,
+        prefix: &[u8],
+    ) -> StripPrefix<'_, T, M> {
+        StripPrefix::new(self.iter_prefix(prefix), prefix.len())
+    }
+
+    /// The distinct bytes that follow `prefix` in the stored
+    /// keys, in ascending order. They are read off the node at
+    /// `prefix` instead of visiting the keys below it. If
+    /// `prefix` ends inside a node prefix, its next byte is the
+    /// only one.
+    pub fn children_of_prefix(&self, prefix: &[u8]) -> Vec<u8> {
+        let found = unsafe { (*self.root).find_prefix(prefix) };
+        let (key, node) = match found {
+            Some(found) => found,
+            None => return vec![],
+        };
+
+        if key.len() > prefix.len() {
+            vec![key[prefix.len()]]
+        } else {
+            node.children().iter().map(|(byte, _)| *byte).collect()
+        }
+    }
+
+    /// Iterates over the entries in key order, with mutable
+    /// access to their values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, M> {
+        IterMut::new(self.root)
+    }
+
+    /// Groups the entries by their first `len` bytes, yielding
+    /// each group's shared bytes along with its entries, in key
+    /// order. Group boundaries come from the tree structure, so
+    /// keys are not compared against each other. Keys shorter
+    /// than `len` form a group of their own.
+    pub fn group_by_prefix(
+        &self,
+        len: usize,
+    ) -> GroupByPrefix<'_, T, M> {
+        GroupByPrefix::new(unsafe { &*self.root }, len)
+    }
+
+    /// The entry with the smallest key. Nodes come before their
+    /// children in key order, so this stops at the first value
+    /// along the path of smallest child bytes.
+    pub fn first_key_value(&self) -> Option<(Vec<u8>, &T)> {
+        let mut node = unsafe { &*self.root };
+        let mut key = node.prefix().to_vec();
+
+        loop {
+            if let Some(value) = node.value() {
+                return Some((key, value));
+            }
+            let (byte, child) = node.adjacent_child(None, false)?;
+            node = unsafe { &*child };
+            key.push(byte);
+            key.extend_from_slice(node.prefix());
+        }
+    }
+
+    /// The entry with the largest key, found by following the
+    /// largest child bytes down to a leaf.
+    pub fn last_key_value(&self) -> Option<(Vec<u8>, &T)> {
+        let mut node = unsafe { &*self.root };
+        let mut key = node.prefix().to_vec();
+
+        while let Some((byte, child)) =
+            node.adjacent_child(None, true)
+        {
+            node = unsafe { &*child };
+            key.push(byte);
+            key.extend_from_slice(node.prefix());
+        }
+
+        // every leaf holds a value, only an empty root doesn't
+        node.value().map(|value| (key, value))
+    }
+
+    /// The entry with the smallest key at or above `k`.
+    pub fn get_ge(&self, k: &[u8]) -> Option<(Vec<u8>, &T)> {
+        self.neighbour(k, true, true)
+    }
+
+    /// The entry with the smallest key above `k`.
+    pub fn get_gt(&self, k: &[u8]) -> Option<(Vec<u8>, &T)> {
+        self.neighbour(k, true, false)
+    }
+
+    /// The entry with the largest key at or below `k`, like the
+    /// latest sample at or before a timestamp.
+    pub fn get_le(&self, k: &[u8]) -> Option<(Vec<u8>, &T)> {
+        self.neighbour(k, false, true)
+    }
+
+    /// The entry with the largest key below `k`.
+    pub fn get_lt(&self, k: &[u8]) -> Option<(Vec<u8>, &T)> {
+        self.neighbour(k, false, false)
+    }
+
+    // the closest entry above or below `k`, or at `k` itself if
+    // `inclusive`. a single descent finds the smallest key at or
+    // above `k`, and the others are one step away from it.
+    fn neighbour(
+        &self,
+        k: &[u8],
+        above: bool,
+        inclusive: bool,
+    ) -> Option<(Vec<u8>, &T)> {
+        let mut cursor = self.cursor();
+        let found = cursor.seek(k);
+        let exact = found && cursor.key() == Some(k);
+
+        if above {
+            if exact && !inclusive {
+                cursor.next();
+            }
+        } else if !found {
+            // every key lies below `k`
+            return self.last_key_value();
+        } else if !(exact && inclusive) {
+            cursor.prev();
+        }
+
+        let value = cursor.value()?;
+        cursor.key().map(|key| (key.to_vec(), value))
+    }
+
+    /// Returns up to `k` entries with the smallest keys, in
+    /// ascending order. The walk stops once `k` entries are
+    /// found.
+    pub fn take_smallest(&self, k: usize) -> Vec<(Vec<u8>, &T)> {
+        self.iter().take(k).collect()
+    }
+
+    /// Returns up to `k` entries with the largest keys, in
+    /// descending order. The walk stops once `k` entries are
+    /// found.
+    pub fn take_largest(&self, k: usize) -> Vec<(Vec<u8>, &T)> {
+        IterRev::new(unsafe { &*self.root }).take(k).collect()
+    }
+
+    /// Accumulates the values in key order, returning every key
+    /// along with the accumulator after its entry was folded in.
+    pub fn scan<A, F>(&self, init: A, mut f: F) -> Vec<(Vec<u8>, A)>
+    where
+        A: Clone,
+        F: FnMut(&A, &[u8], &T) -> A,
+    {
+        let mut acc = init;
+
+        self.iter()
+            .map(|(key, value)| {
+                acc = f(&acc, &key, value);
+                (key, acc.clone())
+            })
+            .collect()
+    }
+
+    /// Clones all entries into a vector, in key order. The
+    /// vector is sized for the number of entries up front.
+    pub fn to_vec(&self) -> Vec<(Vec<u8>, T)>
+    where
+        T: Clone,
+    {
+        let mut entries = Vec::with_capacity(self.len);
+        entries.extend(self.iter().map(|(k, v)| (k, v.clone())));
+        entries
+    }
+
+    /// Writes all keys into one contiguous buffer, in key order,
+    /// returning it along with every value and the range of its
+    /// key in the buffer. Unlike iterating, this does not
+    /// allocate a `Vec` per key.
+    pub fn to_flat(
+        &self,
+    ) -> (Vec<u8>, Vec<(std::ops::Range<usize>, &T)>) {
+        let mut keys = vec![];
+        let mut entries = vec![];
+
+        // the key of the node being visited, shared by all of
+        // them. every stack entry remembers how long the path
+        // was at its parent and the byte leading to it.
+        let mut path = vec![];
+        let mut stack = vec![(0, None, unsafe { &*self.root })];
+
+        while let Some((depth, byte, node)) = stack.pop() {
+            path.truncate(depth);
+            path.extend(byte);
+            path.extend_from_slice(node.prefix());
+
+            if let Some(value) = node.value() {
+                let start = keys.len();
+                keys.extend_from_slice(&path);
+                entries.push((start..keys.len(), value));
+            }
+
+            for (byte, child) in node.children().into_iter().rev() {
+                stack.push((path.len(), Some(byte), unsafe {
+                    &*child
+                }));
+            }
+        }
+
+        (keys, entries)
+    }
+
+    /// Returns whether every key in `self` is also present in
+    /// `other` with an equal value. Both trees are walked once,
+    /// in key order, side by side.
+    pub fn is_subset(&self, other: &Art<T, M>) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut others = other.iter();
+
+        'entries: for (key, value) in self.iter() {
+            for (other_key, other_value) in &mut others {
+                match other_key.cmp(&key) {
+                    Ordering::Less => continue,
+                    Ordering::Equal if other_value == value => {
+                        continue 'entries
+                    }
+                    _ => return false,
+                }
+            }
+
+            return false;
+        }
+
+        true
+    }
+
+    /// Calls `f` with every entry whose key lies between `start`
+    /// and `end`, in key order.
+    pub fn range_for_each<F>(
+        &self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        f: F,
+    ) where
+        F: FnMut(&[u8], &T),
+    {
+        iter::range_for_each(unsafe { &*self.root }, start, end, f)
+    }
+
+    /// Calls `f` with every entry whose key starts with `prefix`,
+    /// in key order, until it returns `ControlFlow::Break`. The
+    /// value it broke with is returned, or `None` if the walk
+    /// ran through.
+    pub fn walk<'a, B, F>(&'a self, prefix: &[u8], f: F) -> Option<B>
+    where
+        F: FnMut(&[u8], &'a T) -> ControlFlow<B>,
+    {
+        let (key, node) = unsafe { (*self.root).find_prefix(prefix)? };
+        iter::walk(key, node, f)
+    }
+
+    /// Approximates the memory used by the tree in bytes: every
+    /// node, the heap memory owned by node prefixes and the
+    /// chunks holding interned prefixes. Memory owned by the
+    /// values themselves is not included.
+    pub fn memory_bytes(&self) -> usize {
+        let mut bytes = 0;
+        let mut chunks = HashSet::new();
+        let mut stack = vec![self.root as *const Node<T, M>];
+
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { &*ptr };
+            let prefix = match node {
+                Node4 { prefix, .. }
+                | Node16 { prefix, .. }
+                | Node48 { prefix, .. }
+                | Node256 { prefix, .. } => prefix,
+            };
+
+            bytes += std::mem::size_of::<Node<T, M>>();
+            bytes += prefix.owned_bytes();
+            if let Some(chunk) = prefix.chunk() {
+                if chunks.insert(&**chunk as *const prefix::Chunk) {
+                    bytes += chunk.capacity();
+                }
+            }
+
+            for (_, child) in node.children() {
+                stack.push(child);
+            }
+        }
+
+        bytes
+    }
+
+    /// Copies the prefixes of all nodes into fresh interning
+    /// chunks, releasing the bytes of prefixes that were split
+    /// or replaced since. Does nothing unless prefix interning
+    /// was enabled through the `ArtBuilder`.
+    pub fn compact(&mut self) {
+        let mut interner = match self.interner {
+            Some(_) => Interner::default(),
+            None => return,
+        };
+        let mut stack = vec![self.root];
+
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { &mut *ptr };
+            let prefix = interner.intern(node.prefix());
+            node.set_prefix(prefix);
+
+            for (_, child) in node.children() {
+                stack.push(child);
+            }
+        }
+
+        self.interner = Some(interner);
+    }
+
+    /// The metadata of the topmost node below which all keys
+    /// starting with `prefix` are found, or `None` if there are
+    /// no such keys. With metadata that aggregates a subtree
+    /// this answers queries about all keys under `prefix`.
+    pub fn metadata(&self, prefix: &[u8]) -> Option<&M> {
+        let (_, node) = unsafe { (*self.root).find_prefix(prefix)? };
+
+        Some(node.meta())
+    }
+
+    /// Recomputes the metadata of every node, children before
+    /// their parents. `f` receives the node's full key, its
+    /// value and the metadata of its children in key order, and
+    /// returns the node's new metadata. This makes it possible
+    /// to maintain subtree aggregates, like the largest value
+    /// below each node.
+    ///
+    /// Metadata is carried over when a node grows, but nodes
+    /// created by later inserts start out with `M::default()`
+    /// until this is called again.
+    pub fn update_metadata<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[u8], Option<&T>, &[&M]) -> M,
+    {
+        let root_key = unsafe { (*self.root).prefix().to_vec() };
+        // nodes are pushed once to expand their children and
+        // once more to be updated after all of them were.
+        let mut stack = vec![(root_key, self.root, false)];
+
+        while let Some((key, ptr, expanded)) = stack.pop() {
+            let node = unsafe { &mut *ptr };
+            let children = node.children();
+
+            if expanded {
+                let metas: Vec<&M> = children
+                    .iter()
+                    .map(|(_, child)| unsafe { (**child).meta() })
+                    .collect();
+                let meta = f(&key, node.value(), &metas);
+                *node.meta_mut() = meta;
+                continue;
+            }
+
+            stack.push((key.clone(), ptr, true));
+            for (byte, child) in children {
+                let child_key =
+                    iter::child_key(&key, byte, unsafe { &*child });
+                stack.push((child_key, child, false));
+            }
+        }
+    }
+
+    /// The name of the node variant at the root of the tree,
+    /// like `"Node16"`. Only there for tests, with the `testing`
+    /// feature.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn root_variant(&self) -> &'static str {
+        unsafe { (*self.root).variant_name() }
+    }
+
+    /// Checks the structural invariants of every node, and
+    /// that the tree holds as many keys as `len` says.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a description of the first broken invariant.
+    pub fn validate(&self) {
+        let root = unsafe { &*self.root };
+        root.validate();
+        assert_eq!(root.count(), self.len, "len is off");
+    }
+
+    /// Walks the whole tree and counts the nodes of each variant.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        let mut stack = vec![self.root as *const Node<T, M>];
+
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { &*ptr };
+            match node {
+                Node4 { .. } => stats.node4 += 1,
+                Node16 { .. } => stats.node16 += 1,
+                Node48 { .. } => stats.node48 += 1,
+                Node256 { .. } => stats.node256 += 1,
+            }
+            for (_, child) in node.children() {
+                stack.push(child);
+            }
+        }
+
+        stats
+    }
+}
+
+// lookups that never borrow a value, so they don't require
+// anything of `T` either.
+impl<T, M> Art<T, M> {
+    /// Returns `true` if `k` has a value. The descent stops at
+    /// the first prefix or child byte that doesn't match.
+    pub fn contains_key(&self, k: &[u8]) -> bool {
+        let mut node = unsafe { &*self.root };
+        let mut key = k;
+
+        loop {
+            if !key.starts_with(node.prefix()) {
+                return false;
+            }
+            let skip = node.prefix().len();
+
+            if skip == key.len() {
+                return node.has_value();
+            }
+
+            let child_idx = match node.find_child(key[skip]) {
+                Some(child_idx) => child_idx,
+                None => return false,
+            };
+
+            node = unsafe { &*node.child_at(child_idx) };
+            key = &key[skip + 1..];
+        }
+    }
+}
+
+impl<T, M> Clone for Art<T, M>
+where
+    T: Clone,
+    M: Default + Clone,
+{
+    // the nodes are copied as they are instead of inserting
+    // every key again, and interned prefixes are shared with
+    // this tree.
+    fn clone(&self) -> Art<T, M> {
+        let root = Box::into_raw(Box::new(unsafe {
+            (*self.root).shallow_clone()
+        }));
+
+        // every copy in the order they were made. the ones after
+        // `done` still point at the original children.
+        let mut copies = Copies(vec![root]);
+        let mut done = 0;
+        while done < copies.0.len() {
+            let node = unsafe { &mut *copies.0[done] };
+            for (byte, child) in node.children() {
+                let copy = Box::into_raw(Box::new(unsafe {
+                    (*child).shallow_clone()
+                }));
+                let idx = node.find_child(byte).unwrap();
+                node[idx] = copy;
+                copies.0.push(copy);
+            }
+            done += 1;
+        }
+        copies.0.clear();
+
+        Art {
+            root,
+            thresholds: self.thresholds,
+            interner: self.interner.clone(),
+            len: self.len,
+            path: vec![],
+            _nodes: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Drop for Art<T, M> {
+    fn drop(&mut self) {
+        unsafe { free(self.root) }
+    }
+}
+
+// `Node` and the pointers to it are private to the crate, so the
+// tree owns its nodes like a `Box` would: no caller can reach or
+// replace a node, changes need a `&mut Art`, and a `&Art` only
+// reads. sending or sharing the tree is then as safe as sending
+// or sharing its values and metadata.
+unsafe impl<T, M> Send for Art<T, M>
+where
+    T: Send,
+    M: Send,
+{
+}
+
+unsafe impl<T, M> Sync for Art<T, M>
+where
+    T: Sync,
+    M: Sync,
+{
+}
+
+impl<T, M> IntoIterator for Art<T, M> {
+    type Item = (Vec<u8>, T);
+    type IntoIter = IntoIter<T, M>;
+
+    fn into_iter(self) -> IntoIter<T, M> {
+        // the nodes now belong to the iterator, the prefixes
+        // keep their chunks alive without the interner.
+        let mut art = std::mem::ManuallyDrop::new(self);
+        drop(art.interner.take());
+        drop(std::mem::take(&mut art.path));
+        IntoIter::new(art.root)
+    }
+}
+
+impl<'a, T, M> IntoIterator for &'a Art<T, M>
+where
+    M: Default,
+{
+    type Item = (Vec<u8>, &'a T);
+    type IntoIter = Iter<'a, T, M>;
+
+    fn into_iter(self) -> Iter<'a, T, M> {
+        self.iter()
+    }
+}
+
+impl<'a, T, M> IntoIterator for &'a mut Art<T, M>
+where
+    M: Default,
+{
+    type Item = (Vec<u8>, &'a mut T);
+    type IntoIter = IterMut<'a, T, M>;
+
+    fn into_iter(self) -> IterMut<'a, T, M> {
+        self.iter_mut()
+    }
+}
+
+// every non-null entry of `pointers` came from `Box::into_raw`
+// and is owned by exactly that slot, which alone frees it or
+// hands it on. other copies of it, like the paths of cursors
+// and iterators, don't outlive a change to the tree unless
+// they are repaired after it. null marks an empty slot.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Node<T, M = ()> {
+    Node4 {
+        value: Option<T>,
+        prefix: Prefix,
+        index: [u8; 4],
+        pointers: [*mut Node<T, M>; 4],
+        version: Version,
+        meta: M,
+        count: usize,
+    },
+    Node16 {
+        value: Option<T>,
+        prefix: Prefix,
+        index: [u8; 16],
+        pointers: [*mut Node<T, M>; 16],
+        version: Version,
+        meta: M,
+        count: usize,
+    },
+    Node48 {
+        value: Option<T>,
+        prefix: Prefix,
+        index: [u8; 256],
+        pointers: [*mut Node<T, M>; 48],
+        version: Version,
+        meta: M,
+        count: usize,
+    },
+    Node256 {
+        value: Option<T>,
+        prefix: Prefix,
+        pointers: [*mut Node<T, M>; 256],
+        version: Version,
+        meta: M,
+        count: usize,
+    },
+}
+
+use Node::*;
+
+// where a key lives in the tree, or where the descent towards it
+// ended so that an insert can carry on from there.
+enum Slot<T, M> {
+    // the node whose full key is the searched key. it may or may
+    // not hold a value.
+    Found(*mut Node<T, M>),
+    // insertion has to continue at this node and key depth
+    Vacant(*mut Node<T, M>, usize),
+}
+
+// a node owns its children, see above, and only code in this
+// crate ever holds one. the only state changed behind a `&Node`
+// is the version, which is atomic when readers may race with
+// writers.
+unsafe impl<T, M> Send for Node<T, M>
+where
+    T: Send,
+    M: Send,
+{
+}
+
+unsafe impl<T, M> Sync for Node<T, M>
+where
+    T: Sync,
+    M: Sync,
+{
+}
+
+impl<T, M> Default for Node<T, M>
+where
+    M: Default,
+{
+    fn default() -> Node<T, M> {
+        Node4 {
+            value: None,
+            prefix: Prefix::default(),
+            index: [255; 4],
+            pointers: [null_mut(); 4],
+            version: Version::default(),
+            meta: M::default(),
+            count: 0,
+        }
+    }
+}
+
+impl<T, M> fmt::Debug for Node<T, M>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Node4 {
+                value,
                 prefix,
                 index,
                 pointers,
+                ..
+            } => write!(
+                f,
+                "Node4 {{ value: {:?}, prefix: {:?}, index: {:?}, pointers: {:?} }}",
+                value, prefix, index, pointers
+            ),
+            Node16 {
+                value,
+                prefix,
+                index,
+                pointers,
+                ..
+            } => write!(
+                f,
+                "Node16 {{ value: {:?}, prefix: {:?}, index: {:?}, pointers: {:?} }}",
+                value, prefix, index, pointers
+            ),
+            Node48 { value, prefix, .. } => write!(
+                f,
+                "Node48 {{ value: {:?}, prefix: {:?}, index: OMITTED, pointers: OMITTED }}",
+                value, prefix,
+            ),
+            Node256 { value, prefix, .. } => {
+                write!(f, "Node256 {{ value: {:?}, prefix: {:?}, OMITTED }}", value, prefix,)
+            }
+        }
+    }
+}
+
+impl<T, M> Index<usize> for Node<T, M> {
+    type Output = *mut Node<T, M>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Node4 { ref pointers, .. } => &pointers[index],
+            Node16 { ref pointers, .. } => &pointers[index],
+            Node48 { ref pointers, .. } => &pointers[index],
+            Node256 { ref pointers, .. } => &pointers[index],
+        }
+    }
+}
+
+impl<T, M> IndexMut<usize> for Node<T, M> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            Node4 {
+                ref mut pointers, ..
+            } => &mut pointers[index],
+            Node16 {
+                ref mut pointers, ..
+            } => &mut pointers[index],
+            Node48 {
+                ref mut pointers, ..
+            } => &mut pointers[index],
+            Node256 {
+                ref mut pointers, ..
+            } => &mut pointers[index],
+        }
+    }
+}
+
+// everything `free` needs, which runs from `Drop` and therefore
+// can't require anything of `T`, along with the descent that
+// `contains_key` does without looking at values.
+impl<T, M> Node<T, M> {
+    /// children as (byte, pointer) pairs in ascending byte order
+    fn children(&self) -> Vec<(u8, *mut Node<T, M>)> {
+        let mut children: Vec<(u8, *mut Node<T, M>)> = match self {
+            Node4 {
+                ref index,
+                ref pointers,
+                ..
+            } => index
+                .iter()
+                .cloned()
+                .zip(pointers.iter().cloned())
+                .filter(|(_, ptr)| !ptr.is_null())
+                .collect(),
+            Node16 {
+                ref index,
+                ref pointers,
+                ..
+            } => index
+                .iter()
+                .cloned()
+                .zip(pointers.iter().cloned())
+                .filter(|(_, ptr)| !ptr.is_null())
+                .collect(),
+            Node48 {
+                ref index,
+                ref pointers,
+                ..
+            } => index
+                .iter()
+                .enumerate()
+                .filter(|(_, idx)| **idx < 48)
+                .map(|(byte, idx)| {
+                    (byte as u8, pointers[*idx as usize])
+                })
+                .collect(),
+            Node256 { ref pointers, .. } => pointers
+                .iter()
+                .enumerate()
+                .filter(|(_, ptr)| !ptr.is_null())
+                .map(|(byte, ptr)| (byte as u8, *ptr))
+                .collect(),
+        };
+
+        children.sort_by_key(|(byte, _)| *byte);
+        children
+    }
+
+    fn prefix(&self) -> &[u8] {
+        match self {
+            Node4 { ref prefix, .. }
+            | Node16 { ref prefix, .. }
+            | Node48 { ref prefix, .. }
+            | Node256 { ref prefix, .. } => prefix,
+        }
+    }
+
+    /// get index for searched byte
+    fn find_child(&self, byte: u8) -> Option<usize> {
+        match self {
+            Node4 {
+                ref index,
+                ref pointers,
+                ..
+            } => {
+                for (i, b) in index.iter().enumerate() {
+                    // index and pointers have the same length
+                    if *b == byte
+                        && !unsafe { slot(pointers, i) }.is_null()
+                    {
+                        return Some(i);
+                    }
+                }
+                None
+            }
+            Node16 {
+                ref index,
+                ref pointers,
+                ..
+            } => {
+                // TODO SSE
+                for (i, b) in index.iter().enumerate() {
+                    // index and pointers have the same length
+                    if *b == byte
+                        && !unsafe { slot(pointers, i) }.is_null()
+                    {
+                        return Some(i);
+                    }
+                }
+                None
+            }
+            Node48 {
+                ref index,
+                ref pointers,
+                ..
+            } => {
+                // a u8 is always in bounds of the 256 entry index
+                let i = *unsafe { slot(index, byte as usize) };
+
+                if i >= 48 {
+                    // idx does not point to valid slot
+                    None
+                } else {
+                    #[cfg(not(feature = "performance"))]
+                    assert_ne!(
+                        null_mut(),
+                        pointers[i as usize],
+                        "should not have a null pointer with a valid index"
+                    );
+                    #[cfg(feature = "performance")]
+                    debug_assert_ne!(
+                        null_mut(),
+                        pointers[i as usize],
+                        "should not have a null pointer with a valid index"
+                    );
+                    Some(i as usize)
+                }
+            }
+            Node256 { ref pointers, .. } => {
+                // a u8 is always in bounds of the 256 pointers
+                if unsafe { slot(pointers, byte as usize) }.is_null()
+                {
+                    None
+                } else {
+                    Some(byte as usize)
+                }
+            }
+        }
+    }
+
+    /// The child pointer stored at `idx`, as returned by
+    /// `find_child`.
+    ///
+    /// # Safety
+    ///
+    /// With the `performance` feature the bounds check is
+    /// skipped, so `idx` must be a valid slot of this node's
+    /// variant. Every index returned by `find_child` is.
+    unsafe fn child_at(&self, idx: usize) -> *mut Node<T, M> {
+        match self {
+            Node4 { ref pointers, .. } => *slot(pointers, idx),
+            Node16 { ref pointers, .. } => *slot(pointers, idx),
+            Node48 { ref pointers, .. } => *slot(pointers, idx),
+            Node256 { ref pointers, .. } => *slot(pointers, idx),
+        }
+    }
+
+    /// the number of values in the subtree below this node,
+    /// including its own
+    fn count(&self) -> usize {
+        match self {
+            Node4 { count, .. }
+            | Node16 { count, .. }
+            | Node48 { count, .. }
+            | Node256 { count, .. } => *count,
+        }
+    }
+
+    fn count_mut(&mut self) -> &mut usize {
+        match self {
+            Node4 { ref mut count, .. }
+            | Node16 { ref mut count, .. }
+            | Node48 { ref mut count, .. }
+            | Node256 { ref mut count, .. } => count,
+        }
+    }
+
+    // adds `delta` to the count of every node whose full key is
+    // a prefix of `key`, from this node downwards. `depth` is
+    // where this node's prefix starts in `key`.
+    fn count_path(
+        &mut self,
+        key: &[u8],
+        mut depth: usize,
+        delta: isize,
+    ) {
+        let mut node: *mut Node<T, M> = self;
+
+        loop {
+            let current = unsafe { &mut *node };
+            if !key[depth..].starts_with(current.prefix()) {
+                return;
+            }
+            let count = current.count_mut();
+            *count = (*count as isize + delta) as usize;
+
+            depth += current.prefix().len();
+            if depth == key.len() {
+                return;
+            }
+            match current.find_child(key[depth]) {
+                Some(child_idx) => {
+                    node = unsafe { current.child_at(child_idx) };
+                    depth += 1;
+                }
+                None => return,
+            }
+        }
+    }
+
+    // the number of keys below this node that are smaller than
+    // `key`, or also equal to it with `inclusive`. `key` starts
+    // where this node's prefix does.
+    fn count_below(&self, mut key: &[u8], inclusive: bool) -> usize {
+        let mut node = self;
+        let mut below = 0;
+
+        loop {
+            let prefix = node.prefix();
+            let shared = common_prefix_len(key, prefix);
+            if shared < prefix.len() {
+                // the subtree lies entirely before or after `key`
+                if shared < key.len() && prefix[shared] < key[shared] {
+                    below += node.count();
+                }
+                return below;
+            }
+
+            let own = node.has_value() as usize;
+            let (byte, rest) = match key[shared..].split_first() {
+                Some((byte, rest)) => (*byte, rest),
+                None if inclusive => return below + own,
+                None => return below,
+            };
+            below += own;
+            for (child_byte, child) in node.children() {
+                if child_byte < byte {
+                    below += unsafe { (*child).count() };
+                }
+            }
+
+            match node.find_child(byte) {
+                Some(child_idx) => {
+                    node = unsafe { &*node.child_at(child_idx) };
+                    key = rest;
+                }
+                None => return below,
+            }
+        }
+    }
+
+    // sets the count from the node's own value and its
+    // children's counts, for passes that restore the tree
+    // bottom-up
+    fn recount(&mut self) {
+        let below: usize = self
+            .children()
+            .iter()
+            .map(|(_, child)| unsafe { (**child).count() })
+            .sum();
+        *self.count_mut() = below + self.has_value() as usize;
+    }
+
+    fn has_value(&self) -> bool {
+        match self {
+            Node4 { ref value, .. }
+            | Node16 { ref value, .. }
+            | Node48 { ref value, .. }
+            | Node256 { ref value, .. } => value.is_some(),
+        }
+    }
+}
+
+impl<T, M> Node<T, M> {
+    // inserts `value` for `key`, whose first `depth` bytes
+    // lead to this node. the nodes the descent enters, down to
+    // the one holding the value, are pushed onto `path`, so the
+    // counts along it can be updated if the key is new.
+    fn insert(
+        &mut self,
+        key: &[u8],
+        mut depth: usize,
+        value: T,
+        thresholds: &Thresholds,
+        mut interner: Option<&mut Interner>,
+        path: &mut Vec<(*mut Node<T, M>, u8)>,
+    ) -> Option<T>
+    where
+        M: Default,
+    {
+        let mut node = self;
+
+        loop {
+            let common_prefix_len =
+                common_prefix_len(&key[depth..], node.prefix());
+
+            // the key ends exactly at the end of this node's
+            // prefix, so this node holds its value.
+            if common_prefix_len == node.prefix().len()
+                && depth + common_prefix_len == key.len()
+            {
+                return node.set_value(value);
+            }
+
+            // prefix mismatch, create a new parent for the current node
+            if common_prefix_len != node.prefix().len() {
+                let common_prefix = make_prefix(
+                    &mut interner,
+                    &node.prefix()[..common_prefix_len],
+                );
+
+                let old_byte = node.prefix()[common_prefix_len];
+                let old_prefix = make_prefix(
+                    &mut interner,
+                    &node.prefix()[common_prefix_len + 1..],
+                );
+
+                let mut old = Node::default();
+                old.set_prefix(common_prefix);
+
+                std::mem::swap(node, &mut old);
+                // the node keeps its version, readers that saw the
+                // old contents have to notice the change.
+                std::mem::swap(node.version_mut(), old.version_mut());
+                *node.count_mut() = old.count();
+
+                old.set_prefix(old_prefix);
+
+                node.add_child(old_byte, old);
+
+                // the key may end right where it diverges from
+                // the old prefix, in which case the new parent
+                // holds its value.
+                let split = depth + common_prefix_len;
+                if split == key.len() {
+                    node.set_value(value);
+                    return None;
+                }
+
+                let new_byte = key[split];
+                let new_prefix =
+                    make_prefix(&mut interner, &key[split + 1..]);
+
+                let mut new_node = Node::default();
+                new_node.set_prefix(new_prefix);
+                new_node.set_value(value);
+                let leaf = node.add_child(new_byte, new_node);
+                path.push((leaf, new_byte));
+                return None;
+            }
+
+            depth += node.prefix().len();
+
+            if let Some(next_idx) = node.find_child(key[depth]) {
+                let child = node[next_idx];
+                path.push((child, key[depth]));
+                node = unsafe { &mut *child };
+                depth += 1;
+            } else {
+                if node.is_full(thresholds) {
+                    node.grow(thresholds);
+                }
+
+                let new_node = Node4 {
+                    value: Some(value),
+                    prefix: make_prefix(
+                        &mut interner,
+                        &key[depth + 1..],
+                    ),
+                    index: [0u8; 4],
+                    pointers: [null_mut(); 4],
+                    version: Version::default(),
+                    meta: M::default(),
+                    count: 0,
+                };
+
+                let leaf = node.add_child(key[depth], new_node);
+                path.push((leaf, key[depth]));
+                return None;
+            }
+        }
+    }
+
+    // links `subtree` into the tree below this node so that its
+    // full key becomes `key`, splitting a prefix or adding a
+    // child where the descent for `key` ends. no key in the
+    // tree may start with `key`.
+    fn attach(
+        &mut self,
+        key: &[u8],
+        mut depth: usize,
+        subtree: *mut Node<T, M>,
+        thresholds: &Thresholds,
+        mut interner: Option<&mut Interner>,
+    ) where
+        M: Default,
+    {
+        let mut node = self;
+
+        loop {
+            let common_prefix_len =
+                common_prefix_len(&key[depth..], node.prefix());
+            let split = depth + common_prefix_len;
+            assert!(
+                split < key.len(),
+                "attached subtree overlaps the tree"
+            );
+
+            if common_prefix_len != node.prefix().len() {
+                let common_prefix = make_prefix(
+                    &mut interner,
+                    &node.prefix()[..common_prefix_len],
+                );
+
+                let old_byte = node.prefix()[common_prefix_len];
+                let old_prefix = make_prefix(
+                    &mut interner,
+                    &node.prefix()[common_prefix_len + 1..],
+                );
+
+                let mut old = Node::default();
+                old.set_prefix(common_prefix);
+
+                std::mem::swap(node, &mut old);
+                std::mem::swap(node.version_mut(), old.version_mut());
+                *node.count_mut() = old.count();
+
+                old.set_prefix(old_prefix);
+                node.add_child(old_byte, old);
+            } else if let Some(next_idx) = node.find_child(key[split]) {
+                node = unsafe { &mut *node[next_idx] };
+                depth = split + 1;
+                continue;
+            } else if node.is_full(thresholds) {
+                node.grow(thresholds);
+            }
+
+            unsafe {
+                (*subtree).set_prefix(make_prefix(
+                    &mut interner,
+                    &key[split + 1..],
+                ));
+            }
+            node.set_child(key[split], subtree);
+            return;
+        }
+    }
+
+    // the name of this node's variant, like `"Node16"`
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn variant_name(&self) -> &'static str {
+        match self {
+            Node4 { .. } => "Node4",
+            Node16 { .. } => "Node16",
+            Node48 { .. } => "Node48",
+            Node256 { .. } => "Node256",
+        }
+    }
+
+    /// An empty node of the smallest variant that can hold
+    /// `children` children without growing.
+    fn with_capacity(
+        children: usize,
+        thresholds: &Thresholds,
+    ) -> Node<T, M>
+    where
+        M: Default,
+    {
+        if children <= thresholds.node4 {
+            Node::default()
+        } else if children <= thresholds.node16 {
+            Node16 {
+                value: None,
+                prefix: Prefix::default(),
+                index: [0u8; 16],
+                pointers: [null_mut(); 16],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            }
+        } else if children <= thresholds.node48
+            && thresholds.use_node48
+        {
+            Node48 {
+                value: None,
+                prefix: Prefix::default(),
+                index: [255u8; 256],
+                pointers: [null_mut(); 48],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            }
+        } else {
+            Node256 {
+                value: None,
+                prefix: Prefix::default(),
+                pointers: [null_mut(); 256],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            }
+        }
+    }
+
+    /// An empty node of the same variant as this one.
+    fn empty_like<U>(&self) -> Node<U, M>
+    where
+        M: Default,
+    {
+        match self {
+            Node4 { .. } => Node4 {
+                value: None,
+                prefix: Prefix::default(),
+                index: [0u8; 4],
+                pointers: [null_mut(); 4],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            },
+            Node16 { .. } => Node16 {
+                value: None,
+                prefix: Prefix::default(),
+                index: [0u8; 16],
+                pointers: [null_mut(); 16],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            },
+            Node48 { .. } => Node48 {
+                value: None,
+                prefix: Prefix::default(),
+                index: [255u8; 256],
+                pointers: [null_mut(); 48],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            },
+            Node256 { .. } => Node256 {
+                value: None,
+                prefix: Prefix::default(),
+                pointers: [null_mut(); 256],
+                version: Version::default(),
+                meta: M::default(),
+                count: 0,
+            },
+        }
+    }
+
+    // finds where `key` lives below `root`, recording the nodes
+    // on the way in `path`, down to the one in the slot. the
+    // descent only reads the nodes it passes, so values that
+    // were handed out from nodes on the path stay valid, as
+    // `get_many_mut` needs for keys that are prefixes of each
+    // other.
+    unsafe fn slot(
+        root: *mut Node<T, M>,
+        key: &[u8],
+        path: &mut Vec<(*mut Node<T, M>, u8)>,
+    ) -> Slot<T, M> {
+        path.clear();
+        path.push((root, 0));
+        let mut node = root;
+        let mut depth = 0;
+
+        loop {
+            let current = &*node;
+
+            if current.prefix() == &key[depth..] {
+                return Slot::Found(node);
+            }
+            if !key[depth..].starts_with(current.prefix()) {
+                return Slot::Vacant(node, depth);
+            }
+
+            let next = depth + current.prefix().len();
+            match current.find_child(key[next]) {
+                Some(child_idx) => {
+                    node = current[child_idx];
+                    path.push((node, key[next]));
+                    depth = next + 1;
+                }
+                None => return Slot::Vacant(node, depth),
+            }
+        }
+    }
+
+    fn set_prefix(&mut self, p: Prefix) {
+        self.version().bump();
+        match self {
+            Node4 { ref mut prefix, .. }
+            | Node16 { ref mut prefix, .. }
+            | Node48 { ref mut prefix, .. }
+            | Node256 { ref mut prefix, .. } => *prefix = p,
+        }
+    }
+
+    // returns the value that was replaced
+    fn set_value(&mut self, v: T) -> Option<T> {
+        self.version().bump();
+        match self {
+            Node4 { ref mut value, .. }
+            | Node16 { ref mut value, .. }
+            | Node48 { ref mut value, .. }
+            | Node256 { ref mut value, .. } => value.replace(v),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &[u8]) -> Option<&T> {
+        'restart: loop {
+            let mut node = self;
+            let mut key = key;
+
+            loop {
+                // every decision below is only valid if the node
+                // did not change while we were looking at it.
+                let version = node.version().load();
+
+                if !key.starts_with(node.prefix()) {
+                    if !node.version().is_unchanged(version) {
+                        continue 'restart;
+                    }
+                    return None;
+                }
+                let skip = node.prefix().len();
+
+                if skip == key.len() {
+                    let value = node.value();
+                    if !node.version().is_unchanged(version) {
+                        continue 'restart;
+                    }
+                    return value;
+                }
+
+                let child = node
+                    .find_child(key[skip])
+                    .map(|idx| unsafe { node.child_at(idx) });
+                if !node.version().is_unchanged(version) {
+                    continue 'restart;
+                }
+
+                node = unsafe { &*child? };
+                key = &key[skip + 1..];
+            }
+        }
+    }
+
+    fn value_mut(&mut self) -> Option<&mut T> {
+        self.version().bump();
+        match self {
+            Node4 {
+                value: Some(ref mut v),
+                ..
+            }
+            | Node16 {
+                value: Some(ref mut v),
+                ..
+            }
+            | Node48 {
+                value: Some(ref mut v),
+                ..
+            }
+            | Node256 {
+                value: Some(ref mut v),
+                ..
+            } => Some(v),
+            _ => None,
+        }
+    }
+
+    fn value(&self) -> Option<&T> {
+        match self {
+            Node4 {
+                value: Some(ref v), ..
+            }
+            | Node16 {
+                value: Some(ref v), ..
+            }
+            | Node48 {
+                value: Some(ref v), ..
+            }
+            | Node256 {
+                value: Some(ref v), ..
+            } => Some(v),
+            _ => None,
+        }
+    }
+
+    fn take_value(&mut self) -> Option<T> {
+        self.version().bump();
+        match self {
+            Node4 { ref mut value, .. }
+            | Node16 { ref mut value, .. }
+            | Node48 { ref mut value, .. }
+            | Node256 { ref mut value, .. } => value.take(),
+        }
+    }
+
+    fn child_count(&self) -> usize {
+        match self {
+            Node4 { ref pointers, .. } => {
+                pointers.iter().filter(|p| !p.is_null()).count()
+            }
+            Node16 { ref pointers, .. } => {
+                pointers.iter().filter(|p| !p.is_null()).count()
+            }
+            Node48 { ref pointers, .. } => {
+                pointers.iter().filter(|p| !p.is_null()).count()
+            }
+            Node256 { ref pointers, .. } => {
+                pointers.iter().filter(|p| !p.is_null()).count()
+            }
+        }
+    }
+
+    /// the child with the smallest or the largest byte, without
+    /// collecting all of them like `children` does. with a
+    /// `byte`, only children above it, or below it for
+    /// `largest`, are considered.
+    fn adjacent_child(
+        &self,
+        byte: Option<u8>,
+        largest: bool,
+    ) -> Option<(u8, *mut Node<T, M>)> {
+        match self {
+            Node4 {
+                ref index,
+                ref pointers,
+                ..
+            } => pick_child(
+                index.iter().cloned().zip(pointers.iter().cloned()),
+                byte,
+                largest,
+            ),
+            Node16 {
+                ref index,
+                ref pointers,
+                ..
+            } => pick_child(
+                index.iter().cloned().zip(pointers.iter().cloned()),
+                byte,
+                largest,
+            ),
+            Node48 {
+                ref index,
+                ref pointers,
+                ..
+            } => pick_child(
+                index
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, idx)| **idx < 48)
+                    .map(|(byte, idx)| {
+                        (byte as u8, pointers[*idx as usize])
+                    }),
+                byte,
+                largest,
+            ),
+            Node256 { ref pointers, .. } => pick_child(
+                pointers
+                    .iter()
+                    .enumerate()
+                    .map(|(byte, ptr)| (byte as u8, *ptr)),
+                byte,
+                largest,
+            ),
+        }
+    }
+
+    fn is_full(&self, thresholds: &Thresholds) -> bool {
+        let capacity = match self {
+            Node4 { .. } => thresholds.node4,
+            Node16 { .. } => thresholds.node16,
+            Node48 { .. } => thresholds.node48,
+            Node256 { .. } => return false,
+        };
+
+        self.child_count() >= capacity
+    }
+
+    // returns where the child ended up
+    fn add_child(
+        &mut self,
+        byte: u8,
+        child: Node<T, M>,
+    ) -> *mut Node<T, M> {
+        let ptr = Box::into_raw(Box::new(child));
+        self.set_child(byte, ptr);
+        ptr
+    }
+
+    // callers make room before adding a child: `insert` and
+    // `attach` grow a full node first, a split adds to a fresh
+    // Node4, and `grow`, `shrink`, `map` and bulk loading fill
+    // nodes sized for the children they copy. a byte only gets
+    // a child once `find_child` found none for it. no input
+    // reaches a full node or a taken slot here, so the checks
+    // only run in debug builds.
+    fn set_child(&mut self, byte: u8, ptr: *mut Node<T, M>) {
+        self.version().bump();
+        match self {
+            Node4 {
+                index, pointers, ..
+            } => {
+                let idx = first_free(pointers);
+                index[idx] = byte;
+                pointers[idx] = ptr;
+            }
+            Node16 {
+                index, pointers, ..
             } => {
-                let old = index.iter().enumerate().filter_map(
-                    |(byte, idx)| {
-                        if *idx < 48 {
-                            Some((
-                                byte as u8,
-                                pointers[*idx as usize],
-                            ))
-                        } else {
-                            None
-                        }
-                    },
+                let idx = first_free(pointers);
+                index[idx] = byte;
+                pointers[idx] = ptr;
+            }
+            Node48 {
+                index, pointers, ..
+            } => {
+                let idx = first_free(pointers);
+                index[byte as usize] = idx as u8;
+                pointers[idx] = ptr;
+            }
+            Node256 { pointers, .. } => {
+                debug_assert!(
+                    pointers[byte as usize].is_null(),
+                    "replacing existing node"
                 );
+                pointers[byte as usize] = ptr;
+            }
+        }
+    }
 
-                let mut pointers = [null_mut(); 256];
-                for (byte, ptr) in old {
-                    pointers[byte as usize] = ptr;
-                }
+    fn grow(&mut self, thresholds: &Thresholds)
+    where
+        M: Default,
+    {
+        let children = self.children();
+        let value = self.take_value();
+        let prefix = std::mem::take(self.prefix_mut());
+        let meta = std::mem::take(self.meta_mut());
+        let count = self.count();
 
-                Node256 {
-                    value: value.take(),
-                    prefix: prefix.clone(),
-                    pointers: pointers,
-                }
-            }
+        let mut new = match self {
+            Node4 { .. } => Node16 {
+                value,
+                prefix,
+                index: [0u8; 16],
+                pointers: [null_mut(); 16],
+                version: Version::default(),
+                meta,
+                count,
+            },
+            Node16 { .. } if thresholds.use_node48 => Node48 {
+                value,
+                prefix,
+                index: [255u8; 256],
+                pointers: [null_mut(); 48],
+                version: Version::default(),
+                meta,
+                count,
+            },
+            Node16 { .. } | Node48 { .. } => Node256 {
+                value,
+                prefix,
+                pointers: [null_mut(); 256],
+                version: Version::default(),
+                meta,
+                count,
+            },
             Node256 { .. } => panic!("tried to grow a Node256"),
         };
 
+        for (byte, ptr) in children {
+            new.set_child(byte, ptr);
+        }
+
+        *new.version_mut() = self.version().clone();
         *self = new;
+        self.version().bump();
     }
 
-    fn prefix(&self) -> &[u8] {
+    fn remove_child(&mut self, byte: u8) {
+        self.version().bump();
+        let idx = self.find_child(byte).expect("child must exist");
         match self {
-            Node4 { ref prefix, .. }
-            | Node16 { ref prefix, .. }
-            | Node48 { ref prefix, .. }
-            | Node256 { ref prefix, .. } => &*prefix,
+            Node4 { pointers, .. } => pointers[idx] = null_mut(),
+            Node16 { pointers, .. } => pointers[idx] = null_mut(),
+            Node48 {
+                index, pointers, ..
+            } => {
+                index[byte as usize] = 255;
+                pointers[idx] = null_mut();
+            }
+            Node256 { pointers, .. } => pointers[idx] = null_mut(),
         }
     }
 
-    /// get index for searched byte
-    fn find_child(&self, byte: u8) -> Option<usize> {
+    // the inverse of `grow`. a node only shrinks once it could
+    // take another child without growing right away again.
+    fn shrink(&mut self, thresholds: &Thresholds)
+    where
+        M: Default,
+    {
+        fn rank<T, M>(node: &Node<T, M>) -> u8 {
+            match node {
+                Node4 { .. } => 0,
+                Node16 { .. } => 1,
+                Node48 { .. } => 2,
+                Node256 { .. } => 3,
+            }
+        }
+
+        let children = self.children();
+        let mut new =
+            Node::with_capacity(children.len() + 1, thresholds);
+        if rank(&new) >= rank(self) {
+            return;
+        }
+
+        if let Some(value) = self.take_value() {
+            new.set_value(value);
+        }
+        new.set_prefix(std::mem::take(self.prefix_mut()));
+        std::mem::swap(new.meta_mut(), self.meta_mut());
+        *new.count_mut() = self.count();
+        for (byte, ptr) in children {
+            new.set_child(byte, ptr);
+        }
+
+        *new.version_mut() = self.version().clone();
+        *self = new;
+        self.version().bump();
+    }
+
+    // replaces this node, which has no value and a single child,
+    // by that child, prepending this node's prefix and the byte
+    // leading to the child to the child's prefix.
+    fn merge_with_child(
+        &mut self,
+        mut interner: Option<&mut Interner>,
+    ) {
+        let (byte, child) = self.children()[0];
+        let child = unsafe { *Box::from_raw(child) };
+
+        let mut prefix = self.prefix().to_vec();
+        prefix.push(byte);
+        prefix.extend_from_slice(child.prefix());
+
+        let version = self.version().clone();
+        *self = child;
+        *self.version_mut() = version;
+        self.set_prefix(make_prefix(&mut interner, &prefix));
+    }
+
+    // a copy of this node that still points to its children,
+    // which the caller has to replace with copies of their own
+    fn shallow_clone(&self) -> Node<T, M>
+    where
+        T: Clone,
+        M: Clone,
+    {
         match self {
             Node4 {
-                ref index,
-                ref pointers,
-                ..
-            } => {
-                for (i, b) in index.iter().enumerate() {
-                    if *b == byte && !pointers[i].is_null() {
-                        return Some(i as usize);
-                    }
+                value,
+                prefix,
+                index,
+                pointers,
+                version,
+                meta,
+                count,
+            } => Node4 {
+                value: value.clone(),
+                prefix: prefix.clone(),
+                index: *index,
+                pointers: *pointers,
+                version: version.clone(),
+                meta: meta.clone(),
+                count: *count,
+            },
+            Node16 {
+                value,
+                prefix,
+                index,
+                pointers,
+                version,
+                meta,
+                count,
+            } => Node16 {
+                value: value.clone(),
+                prefix: prefix.clone(),
+                index: *index,
+                pointers: *pointers,
+                version: version.clone(),
+                meta: meta.clone(),
+                count: *count,
+            },
+            Node48 {
+                value,
+                prefix,
+                index,
+                pointers,
+                version,
+                meta,
+                count,
+            } => Node48 {
+                value: value.clone(),
+                prefix: prefix.clone(),
+                index: *index,
+                pointers: *pointers,
+                version: version.clone(),
+                meta: meta.clone(),
+                count: *count,
+            },
+            Node256 {
+                value,
+                prefix,
+                pointers,
+                version,
+                meta,
+                count,
+            } => Node256 {
+                value: value.clone(),
+                prefix: prefix.clone(),
+                pointers: *pointers,
+                version: version.clone(),
+                meta: meta.clone(),
+                count: *count,
+            },
+        }
+    }
+
+    fn version(&self) -> &Version {
+        match self {
+            Node4 { ref version, .. }
+            | Node16 { ref version, .. }
+            | Node48 { ref version, .. }
+            | Node256 { ref version, .. } => version,
+        }
+    }
+
+    fn version_mut(&mut self) -> &mut Version {
+        match self {
+            Node4 {
+                ref mut version, ..
+            }
+            | Node16 {
+                ref mut version, ..
+            }
+            | Node48 {
+                ref mut version, ..
+            }
+            | Node256 {
+                ref mut version, ..
+            } => version,
+        }
+    }
+
+    fn prefix_mut(&mut self) -> &mut Prefix {
+        match self {
+            Node4 { ref mut prefix, .. }
+            | Node16 { ref mut prefix, .. }
+            | Node48 { ref mut prefix, .. }
+            | Node256 { ref mut prefix, .. } => prefix,
+        }
+    }
+
+    fn meta(&self) -> &M {
+        match self {
+            Node4 { ref meta, .. }
+            | Node16 { ref meta, .. }
+            | Node48 { ref meta, .. }
+            | Node256 { ref meta, .. } => meta,
+        }
+    }
+
+    fn meta_mut(&mut self) -> &mut M {
+        match self {
+            Node4 { ref mut meta, .. }
+            | Node16 { ref mut meta, .. }
+            | Node48 { ref mut meta, .. }
+            | Node256 { ref mut meta, .. } => meta,
+        }
+    }
+
+    /// The topmost node below which all keys starting with
+    /// `prefix` are found, along with its full key.
+    fn find_prefix(
+        &self,
+        prefix: &[u8],
+    ) -> Option<(Vec<u8>, &Node<T, M>)> {
+        let mut node = self;
+        let mut depth = 0;
+
+        loop {
+            let rest = &prefix[depth..];
+            if rest.len() <= node.prefix().len() {
+                if !node.prefix().starts_with(rest) {
+                    return None;
                 }
-                None
+
+                let mut key = prefix[..depth].to_vec();
+                key.extend_from_slice(node.prefix());
+                return Some((key, node));
             }
-            Node16 {
-                ref index,
-                ref pointers,
-                ..
-            } => {
-                // TODO SSE
-                for (i, b) in index.iter().enumerate() {
-                    if *b == byte && !pointers[i].is_null() {
-                        return Some(i as usize);
+            if !rest.starts_with(node.prefix()) {
+                return None;
+            }
+
+            let next = depth + node.prefix().len();
+            let child_idx = node.find_child(prefix[next])?;
+            node = unsafe { &*node.child_at(child_idx) };
+            depth = next + 1;
+        }
+    }
+
+    // checks the structural invariants of this node and all of
+    // its descendants, panicking with a description of the
+    // first broken one.
+    pub(crate) fn validate(&self) {
+        let mut stack = vec![self as *const Node<T, M>];
+
+        while let Some(ptr) = stack.pop() {
+            let node = unsafe { &*ptr };
+
+            match node {
+                Node4 {
+                    ref index,
+                    ref pointers,
+                    ..
+                } => check_unique_bytes(index, pointers),
+                Node16 {
+                    ref index,
+                    ref pointers,
+                    ..
+                } => check_unique_bytes(index, pointers),
+                Node48 {
+                    ref index,
+                    ref pointers,
+                    ..
+                } => {
+                    let mut referenced = [false; 48];
+                    for (byte, idx) in index.iter().enumerate() {
+                        if *idx == 255 {
+                            continue;
+                        }
+                        assert!(
+                            *idx < 48,
+                            "Node48 index for byte {} is out of bounds: {}",
+                            byte,
+                            idx
+                        );
+                        assert!(
+                            !pointers[*idx as usize].is_null(),
+                            "Node48 index for byte {} points to a null child",
+                            byte
+                        );
+                        assert!(
+                            !referenced[*idx as usize],
+                            "Node48 slot {} is indexed twice",
+                            idx
+                        );
+                        referenced[*idx as usize] = true;
+                    }
+                    for (idx, ptr) in pointers.iter().enumerate() {
+                        assert!(
+                            ptr.is_null() || referenced[idx],
+                            "Node48 slot {} is not indexed",
+                            idx
+                        );
                     }
                 }
-                None
+                Node256 { .. } => {}
             }
-            Node48 {
-                ref index,
-                ref pointers,
-                ..
-            } => {
-                let i = index[byte as usize];
 
-                if i >= 48 {
-                    // idx does not point to valid slot
-                    None
-                } else {
-                    assert_ne!(
-                        null_mut(),
-                        pointers[i as usize],
-                        "should not have a null pointer with a valid index"
-                    );
-                    Some(i as usize)
-                }
+            let below: usize = node
+                .children()
+                .iter()
+                .map(|(_, child)| unsafe { (**child).count() })
+                .sum();
+            assert_eq!(
+                node.count(),
+                below + node.has_value() as usize,
+                "node count is not the number of values below it"
+            );
+
+            for (_, child) in node.children() {
+                stack.push(child);
+            }
+        }
+    }
+}
+
+fn check_unique_bytes<T, M>(
+    index: &[u8],
+    pointers: &[*mut Node<T, M>],
+) {
+    assert_eq!(index.len(), pointers.len());
+
+    for (i, ptr) in pointers.iter().enumerate() {
+        if ptr.is_null() {
+            continue;
+        }
+        for (j, other) in pointers.iter().enumerate().skip(i + 1) {
+            assert!(
+                other.is_null() || index[i] != index[j],
+                "byte {} is used by two children",
+                index[j]
+            );
+        }
+    }
+}
+
+// the index of the first empty slot in `pointers`, which
+// `set_child` makes sure there is
+fn first_free<P>(pointers: &[*mut P]) -> usize {
+    let idx = pointers.iter().take_while(|p| !p.is_null()).count();
+    debug_assert!(idx < pointers.len(), "node must not be full");
+    idx
+}
+
+/// `items[i]`, without a bounds check when the `performance`
+/// feature is enabled.
+///
+/// # Safety
+///
+/// `i` must be smaller than `items.len()`. The node invariants
+/// checked by `Node::validate` make sure every caller's index
+/// is.
+#[inline(always)]
+unsafe fn slot<I>(items: &[I], i: usize) -> &I {
+    #[cfg(feature = "performance")]
+    {
+        items.get_unchecked(i)
+    }
+    #[cfg(not(feature = "performance"))]
+    {
+        &items[i]
+    }
+}
+
+// an entry kept by `top_k_in_prefix`. higher scores and then
+// earlier keys order first, so the top of a max-heap is the
+// entry to let go of.
+struct Ranked<S, V> {
+    score: S,
+    seq: usize,
+    key: Vec<u8>,
+    value: V,
+}
+
+impl<S: Ord, V> Ord for Ranked<S, V> {
+    fn cmp(&self, other: &Ranked<S, V>) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl<S: Ord, V> PartialOrd for Ranked<S, V> {
+    fn partial_cmp(&self, other: &Ranked<S, V>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Ord, V> PartialEq for Ranked<S, V> {
+    fn eq(&self, other: &Ranked<S, V>) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: Ord, V> Eq for Ranked<S, V> {}
+
+fn pick_child<T, M, I>(
+    children: I,
+    beyond: Option<u8>,
+    largest: bool,
+) -> Option<(u8, *mut Node<T, M>)>
+where
+    I: Iterator<Item = (u8, *mut Node<T, M>)>,
+{
+    let children = children.filter(|(byte, ptr)| {
+        !ptr.is_null()
+            && match beyond {
+                Some(b) if largest => *byte < b,
+                Some(b) => *byte > b,
+                None => true,
+            }
+    });
+    if largest {
+        children.max_by_key(|(byte, _)| *byte)
+    } else {
+        children.min_by_key(|(byte, _)| *byte)
+    }
+}
+
+/// Frees the node behind `ptr` and its entire subtree,
+/// dropping every stored value.
+///
+/// # Safety
+///
+/// `ptr` must have come from `Box::into_raw` and must not be
+/// reachable from anywhere else afterwards.
+unsafe fn free<T, M>(ptr: *mut Node<T, M>) {
+    let mut stack = vec![ptr];
+
+    while let Some(ptr) = stack.pop() {
+        let node = Box::from_raw(ptr);
+        stack.extend(
+            node.children().into_iter().map(|(_, child)| child),
+        );
+    }
+}
+
+// a value that `fetch_and_update` took out of the tree to hand
+// to user code. dropping this, which only happens should that
+// code panic, removes the key for good.
+struct Taken<'a, T: 'a, M: 'a>
+where
+    M: Default,
+{
+    art: &'a mut Art<T, M>,
+}
+
+impl<'a, T, M> Drop for Taken<'a, T, M>
+where
+    M: Default,
+{
+    fn drop(&mut self) {
+        self.art.counted(-1);
+        self.art.prune();
+    }
+}
+
+// the walk of `retain`, see there. should `f` panic, dropping
+// it still prunes the nodes that were expanded, leaving a valid
+// tree without the entries removed so far.
+#[allow(clippy::type_complexity)]
+struct Retain<'a, T: 'a, M: 'a>
+where
+    M: Default,
+{
+    art: &'a mut Art<T, M>,
+    stack: Vec<(Vec<u8>, *mut Node<T, M>, *mut Node<T, M>, u8, bool)>,
+}
+
+impl<'a, T, M> Retain<'a, T, M>
+where
+    M: Default,
+{
+    // restores the invariants of a node whose value and
+    // children were all filtered
+    fn prune(
+        &mut self,
+        ptr: *mut Node<T, M>,
+        parent: *mut Node<T, M>,
+        byte: u8,
+    ) {
+        let node = unsafe { &mut *ptr };
+        node.recount();
+        let children = node.child_count();
+        if node.value().is_some() || children > 1 {
+            node.shrink(&self.art.thresholds);
+        } else if children == 1 {
+            node.merge_with_child(self.art.interner.as_mut());
+        } else if parent.is_null() {
+            // the tree is empty now
+            node.set_prefix(Prefix::default());
+        } else {
+            unsafe {
+                (*parent).remove_child(byte);
+                free(ptr);
+            }
+        }
+    }
+}
+
+impl<'a, T, M> Drop for Retain<'a, T, M>
+where
+    M: Default,
+{
+    fn drop(&mut self) {
+        while let Some((_, ptr, parent, byte, expanded)) =
+            self.stack.pop()
+        {
+            if expanded {
+                self.prune(ptr, parent, byte);
             }
-            Node256 { .. } => Some(byte as usize),
+        }
+    }
+}
+
+// what `map` owns while it runs: the new tree as far as it got,
+// and the old subtrees that still have to be mapped, along with
+// their keys and where they go. both are freed should `f` panic.
+#[allow(clippy::type_complexity)]
+struct Mapping<T, U, M> {
+    new_root: *mut Node<U, M>,
+    stack: Vec<(
+        Vec<u8>,
+        *mut Node<T, M>,
+        Option<(*mut Node<U, M>, u8)>,
+    )>,
+}
+
+impl<T, U, M> Drop for Mapping<T, U, M> {
+    fn drop(&mut self) {
+        if !self.new_root.is_null() {
+            unsafe { free(self.new_root) }
+        }
+        for (_, ptr, _) in self.stack.drain(..) {
+            unsafe { free(ptr) }
+        }
+    }
+}
+
+// the nodes `clone` copied so far. should cloning a value
+// panic they are freed one by one, without their children, which
+// may still be the originals.
+struct Copies<T, M>(Vec<*mut Node<T, M>>);
+
+impl<T, M> Drop for Copies<T, M> {
+    fn drop(&mut self) {
+        for ptr in self.0.drain(..) {
+            drop(unsafe { Box::from_raw(ptr) });
         }
     }
 }
@@ -539,3 +3515,94 @@ fn test_common_prefix_len() {
     assert_eq!(common_prefix_len(b"bc", b"abc"), 0);
     assert_eq!(common_prefix_len(b"abc", b"bc"), 0);
 }
+
+#[test]
+#[cfg(feature = "optimistic")]
+fn test_mutation_bumps_version() {
+    let mut art = Art::default();
+    art.set(b"abc".to_vec(), 1);
+
+    let seen = unsafe { &*art.root }.version().load();
+    assert_eq!(art.get(b"abc"), Some(&1));
+    assert!(unsafe { &*art.root }.version().is_unchanged(seen));
+
+    art.set(b"abd".to_vec(), 2);
+    art.set(b"x".to_vec(), 3);
+    let root = unsafe { &*art.root };
+    assert!(!root.version().is_unchanged(seen));
+    assert_eq!(art.get(b"abc"), Some(&1));
+    assert_eq!(art.get(b"abd"), Some(&2));
+}
+
+// every entry of `art` in the order `iter` yields them, for
+// comparing trees whose internal shapes differ.
+#[cfg(test)]
+fn entries<T>(art: &Art<T>) -> Vec<(Vec<u8>, T)>
+where
+    T: Clone,
+{
+    art.iter().map(|(k, v)| (k, v.clone())).collect()
+}
+
+#[cfg(test)]
+fn check_shuffled_orders(
+    builder: ArtBuilder,
+    keys: Vec<Vec<u8>>,
+    root_variant: &str,
+) {
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
+    let mut expected: Vec<(Vec<u8>, usize)> = keys
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, k)| (k, i))
+        .collect();
+    expected.sort();
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut order = expected.clone();
+
+    for _ in 0..50 {
+        rng.shuffle(&mut order);
+
+        let mut art = builder.clone().build();
+        for (k, v) in &order {
+            art.set(k.clone(), *v);
+        }
+
+        assert_eq!(art.root_variant(), root_variant);
+        assert_eq!(entries(&art), expected);
+    }
+}
+
+#[test]
+fn test_iter_order_independent_of_insertion_order() {
+    // a Node48 at the root
+    let keys = (0..40u8).map(|b| vec![b * 5, b]).collect();
+    check_shuffled_orders(ArtBuilder::new(), keys, "Node48");
+
+    // a Node256 at the root, with nested Node48s below it
+    let keys = (0..=255u8)
+        .flat_map(|a| (0..20u8).map(move |b| vec![a, b * 3]))
+        .collect();
+    check_shuffled_orders(ArtBuilder::new(), keys, "Node256");
+
+    // Node16s growing straight into a Node256
+    let keys = (0..30u8).map(|b| vec![255 - b]).collect();
+    check_shuffled_orders(
+        ArtBuilder::new().node48(false),
+        keys,
+        "Node256",
+    );
+
+    // keys that are prefixes of each other, so values sit on
+    // interior nodes and prefixes get split
+    let keys = (0..64u8)
+        .map(|b| (0..b % 7).map(|i| b'a' + i).collect())
+        .chain((0..64u8).map(|b| vec![b'a', b]))
+        .collect::<HashSet<Vec<u8>>>()
+        .into_iter()
+        .collect();
+    check_shuffled_orders(ArtBuilder::new(), keys, "Node4");
+}
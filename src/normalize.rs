@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use super::iter::{Iter, Range};
+use super::Art;
+
+/// Turns a key into the form it is stored and looked up in.
+/// Keys that are already normalized can be returned borrowed.
+pub type Normalizer = fn(&[u8]) -> Cow<'_, [u8]>;
+
+/// A `Normalizer` for ASCII case-insensitive keys, like host
+/// names. Bytes outside of ASCII are left alone.
+pub fn ascii_lowercase(key: &[u8]) -> Cow<'_, [u8]> {
+    if key.iter().any(u8::is_ascii_uppercase) {
+        Cow::Owned(key.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+/// An `Art` that normalizes every key it is passed, so that keys
+/// with the same normalized form refer to the same entry.
+///
+/// Keys are stored normalized, so that is what iterating
+/// yields. Prefixes and range bounds are normalized as well,
+/// which makes scans agree with lookups.
+///
+/// ```
+/// use cart::{ascii_lowercase, NormalizedArt};
+///
+/// let mut hosts = NormalizedArt::new(ascii_lowercase);
+/// hosts.set(b"Example.COM", 1);
+/// assert_eq!(hosts.get(b"example.com"), Some(&1));
+/// assert_eq!(hosts.iter_prefix(b"EXAMPLE").count(), 1);
+/// ```
+pub struct NormalizedArt<T> {
+    art: Art<T>,
+    normalize: Normalizer,
+}
+
+impl<T> NormalizedArt<T> {
+    /// An empty tree that normalizes keys with `normalize`. See
+    /// `ArtBuilder::build_normalized` for tuning the tree.
+    pub fn new(normalize: Normalizer) -> NormalizedArt<T> {
+        NormalizedArt::with_art(Art::default(), normalize)
+    }
+
+    pub(crate) fn with_art(
+        art: Art<T>,
+        normalize: Normalizer,
+    ) -> NormalizedArt<T> {
+        NormalizedArt { art, normalize }
+    }
+
+    /// Sets the value of the normalized `k`, returning the value
+    /// it replaced if the key was already present.
+    pub fn set(&mut self, k: &[u8], v: T) -> Option<T> {
+        let k = (self.normalize)(k);
+        self.art.set(&*k, v)
+    }
+
+    pub fn get(&self, k: &[u8]) -> Option<&T> {
+        self.art.get(&*(self.normalize)(k))
+    }
+
+    pub fn get_mut(&mut self, k: &[u8]) -> Option<&mut T> {
+        let k = (self.normalize)(k);
+        self.art.get_mut(&k)
+    }
+
+    pub fn remove(&mut self, k: &[u8]) -> Option<T> {
+        let k = (self.normalize)(k);
+        self.art.remove(&k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.art.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.art.is_empty()
+    }
+
+    /// Iterates over the entries in the order of their
+    /// normalized keys.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.art.iter()
+    }
+
+    /// Iterates over the entries whose normalized keys start
+    /// with the normalized `prefix`.
+    pub fn iter_prefix(&self, prefix: &[u8]) -> Iter<'_, T> {
+        self.art.iter_prefix(&(self.normalize)(prefix))
+    }
+
+    /// Iterates over the entries whose normalized keys lie
+    /// between the normalized `start` and `end`.
+    pub fn range(&self, start: &[u8], end: &[u8]) -> Range<'_, T> {
+        let start = (self.normalize)(start);
+        let end = (self.normalize)(end);
+        self.art.range(&*start..&*end)
+    }
+
+    /// The tree of normalized keys the entries are kept in.
+    pub fn as_art(&self) -> &Art<T> {
+        &self.art
+    }
+
+    pub fn into_art(self) -> Art<T> {
+        self.art
+    }
+}
@@ -0,0 +1,47 @@
+use rayon::prelude::*;
+
+use super::iter::{child_key, Iter};
+use super::{Art, Node};
+
+// a subtree below the root, handed to rayon as one unit of work
+struct Subtree<'a, T: 'a, M: 'a> {
+    key: Vec<u8>,
+    node: &'a Node<T, M>,
+}
+
+impl<T, M> Art<T, M>
+where
+    T: Sync,
+    M: Default + Sync,
+{
+    /// Iterates over the entries in parallel. Every child of the
+    /// root becomes a unit of work that is walked in key order
+    /// on its own, and collecting the entries keeps key order.
+    pub fn par_iter(
+        &self,
+    ) -> impl ParallelIterator<Item = (Vec<u8>, &T)> + '_ {
+        let root = unsafe { &*self.root };
+        let key = root.prefix().to_vec();
+
+        let subtrees: Vec<Subtree<T, M>> = root
+            .children()
+            .into_iter()
+            .map(|(byte, child)| {
+                let child = unsafe { &*child };
+                Subtree {
+                    key: child_key(&key, byte, child),
+                    node: child,
+                }
+            })
+            .collect();
+
+        root.value()
+            .map(|value| (key, value))
+            .into_par_iter()
+            .chain(
+                subtrees
+                    .into_par_iter()
+                    .flat_map_iter(|s| Iter::subtree(s.key, s.node)),
+            )
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+// size of the buffers that interned prefixes are copied into
+const CHUNK_SIZE: usize = 4096;
+
+// prefixes longer than this are not worth interning, they are
+// owned by their node instead.
+const MAX_INTERNED_LEN: usize = CHUNK_SIZE / 8;
+
+/// The compressed path stored in a node.
+///
+/// A prefix either owns its bytes or points into a chunk of an
+/// `Interner`, which keeps the chunk alive for as long as any
+/// prefix refers to it.
+#[derive(Clone)]
+pub struct Prefix {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Owned(Vec<u8>),
+    // only built by `Prefix::shared`, so the bytes always lie
+    // within the chunk
+    Shared {
+        chunk: Arc<Chunk>,
+        offset: u32,
+        len: u32,
+    },
+}
+
+impl Prefix {
+    // points at `len` bytes of `chunk` starting at `offset`,
+    // which have to be written already
+    fn shared(chunk: Arc<Chunk>, offset: usize, len: usize) -> Prefix {
+        assert!(
+            offset + len <= chunk.capacity,
+            "interned prefix exceeds its chunk"
+        );
+        Prefix {
+            repr: Repr::Shared {
+                chunk,
+                offset: offset as u32,
+                len: len as u32,
+            },
+        }
+    }
+
+    /// The heap memory owned by this prefix alone, not counting
+    /// shared chunks.
+    pub(crate) fn owned_bytes(&self) -> usize {
+        match self.repr {
+            Repr::Owned(ref bytes) => bytes.capacity(),
+            Repr::Shared { .. } => 0,
+        }
+    }
+
+    pub(crate) fn chunk(&self) -> Option<&Arc<Chunk>> {
+        match self.repr {
+            Repr::Owned(_) => None,
+            Repr::Shared { ref chunk, .. } => Some(chunk),
+        }
+    }
+}
+
+impl Default for Prefix {
+    fn default() -> Prefix {
+        Prefix::from(vec![])
+    }
+}
+
+impl Deref for Prefix {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self.repr {
+            Repr::Owned(ref bytes) => bytes,
+            Repr::Shared {
+                ref chunk,
+                offset,
+                len,
+            } => unsafe {
+                // the interner never touches bytes that were
+                // handed out already
+                std::slice::from_raw_parts(
+                    chunk.ptr.add(offset as usize),
+                    len as usize,
+                )
+            },
+        }
+    }
+}
+
+impl From<Vec<u8>> for Prefix {
+    fn from(bytes: Vec<u8>) -> Prefix {
+        Prefix {
+            repr: Repr::Owned(bytes),
+        }
+    }
+}
+
+impl<'a> From<&'a [u8]> for Prefix {
+    fn from(bytes: &'a [u8]) -> Prefix {
+        Prefix::from(bytes.to_vec())
+    }
+}
+
+impl PartialEq for Prefix {
+    fn eq(&self, other: &Prefix) -> bool {
+        **self == **other
+    }
+}
+
+impl fmt::Debug for Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A fixed size, append-only byte buffer.
+pub(crate) struct Chunk {
+    ptr: *mut u8,
+    capacity: usize,
+}
+
+impl Chunk {
+    fn new(capacity: usize) -> Chunk {
+        let mut buf = Vec::with_capacity(capacity);
+        let ptr = buf.as_mut_ptr();
+        std::mem::forget(buf);
+
+        Chunk { ptr, capacity }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+// bytes are only written once, by the interner that owns the
+// chunk, before any prefix referring to them exists. readers
+// therefore never observe a write.
+unsafe impl Send for Chunk {}
+unsafe impl Sync for Chunk {}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Vec::from_raw_parts(self.ptr, 0, self.capacity))
+        }
+    }
+}
+
+/// Copies short prefixes into shared chunks, so that nodes do
+/// not need an allocation of their own for them.
+///
+/// Chunks are only ever appended to. Bytes of prefixes that
+/// were replaced stay in their chunk until the tree is
+/// compacted and the chunk loses its last reference.
+pub(crate) struct Interner {
+    chunk: Arc<Chunk>,
+    used: usize,
+}
+
+impl Default for Interner {
+    fn default() -> Interner {
+        Interner {
+            chunk: Arc::new(Chunk::new(CHUNK_SIZE)),
+            used: 0,
+        }
+    }
+}
+
+// each tree appends to its own chunk, so a cloned tree starts
+// out with a fresh one.
+impl Clone for Interner {
+    fn clone(&self) -> Interner {
+        Interner::default()
+    }
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Interner {{ used: {} }}", self.used)
+    }
+}
+
+impl Interner {
+    pub(crate) fn intern(&mut self, bytes: &[u8]) -> Prefix {
+        if bytes.is_empty() || bytes.len() > MAX_INTERNED_LEN {
+            return Prefix::from(bytes);
+        }
+
+        if self.used + bytes.len() > self.chunk.capacity {
+            *self = Interner::default();
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                self.chunk.ptr.add(self.used),
+                bytes.len(),
+            );
+        }
+
+        let prefix =
+            Prefix::shared(self.chunk.clone(), self.used, bytes.len());
+        self.used += bytes.len();
+
+        prefix
+    }
+}
+
+// either interns `bytes` or copies them into an owned prefix
+pub(crate) fn make_prefix(
+    interner: &mut Option<&mut Interner>,
+    bytes: &[u8],
+) -> Prefix {
+    match interner {
+        Some(interner) => interner.intern(bytes),
+        None => Prefix::from(bytes),
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_shared_prefix_stays_within_its_chunk() {
+    let interner = Interner::default();
+    Prefix::shared(interner.chunk.clone(), CHUNK_SIZE - 4, 8);
+}
@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use super::iter::child_key;
+use super::Art;
+
+impl<T> Art<T, usize> {
+    /// Sets the metadata of every node to the number of entries
+    /// at or below it, which `sample` weighs children by.
+    pub fn update_counts(&mut self) {
+        self.update_metadata(|_, value, children| {
+            let below: usize = children.iter().cloned().sum();
+            value.is_some() as usize + below
+        });
+    }
+
+    /// Returns `k` entries picked at random, with replacement.
+    /// Each one is found by a single descent that picks a node's
+    /// own value or one of its children in proportion to the
+    /// entries below them, so the draw is uniform as long as the
+    /// counts of `update_counts` are up to date. Nodes created
+    /// since then count as holding one entry.
+    pub fn sample<R: Rng>(
+        &self,
+        rng: &mut R,
+        k: usize,
+    ) -> Vec<(Vec<u8>, &T)> {
+        let root = unsafe { &*self.root };
+        if self.len == 0 {
+            return vec![];
+        }
+
+        (0..k)
+            .map(|_| {
+                let mut node = root;
+                let mut key = root.prefix().to_vec();
+
+                loop {
+                    let children = node.children();
+                    let weights: Vec<usize> = children
+                        .iter()
+                        .map(|(_, child)| unsafe { (**child).meta() })
+                        .map(|count| (*count).max(1))
+                        .collect();
+                    let own = node.has_value() as usize;
+                    let total = own + weights.iter().sum::<usize>();
+
+                    let mut pick = rng.gen_range(0, total);
+                    if pick < own {
+                        return (key, node.value().unwrap());
+                    }
+                    pick -= own;
+
+                    let mut chosen = children.len() - 1;
+                    for (i, weight) in weights.iter().enumerate() {
+                        if pick < *weight {
+                            chosen = i;
+                            break;
+                        }
+                        pick -= weight;
+                    }
+
+                    let (byte, child) = children[chosen];
+                    node = unsafe { &*child };
+                    key = child_key(&key, byte, node);
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,75 @@
+use super::iter::{Iter, StripPrefix};
+use super::Node;
+
+/// A read-only view of the entries of an `Art` whose keys start
+/// with a prefix. Keys passed to and returned from the view are
+/// relative to that prefix, so it can be handed to code that
+/// should only see its own namespace.
+///
+/// Created by [`Art::subtree`](struct.Art.html#method.subtree).
+pub struct Subtree<'a, T: 'a, M: 'a = ()> {
+    prefix: Vec<u8>,
+    // the topmost node below which all keys starting with
+    // `prefix` are found, along with its full key. `None` if
+    // there are no such keys.
+    found: Option<(Vec<u8>, &'a Node<T, M>)>,
+}
+
+impl<'a, T, M> Subtree<'a, T, M> {
+    pub(crate) fn new(
+        root: &'a Node<T, M>,
+        prefix: &[u8],
+    ) -> Subtree<'a, T, M> {
+        // only an empty root holds neither a value nor children
+        let found = root.find_prefix(prefix).filter(|(_, node)| {
+            node.has_value() || node.child_count() > 0
+        });
+
+        Subtree {
+            prefix: prefix.to_vec(),
+            found,
+        }
+    }
+
+    /// The prefix that every key of the view starts with.
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    /// Looks up `key` below the prefix.
+    pub fn get(&self, key: &[u8]) -> Option<&'a T> {
+        let (ref found_key, node) = *self.found.as_ref()?;
+
+        let mut full =
+            Vec::with_capacity(self.prefix.len() + key.len());
+        full.extend_from_slice(&self.prefix);
+        full.extend_from_slice(key);
+
+        // the node's prefix starts this far into its full key
+        let start = found_key.len() - node.prefix().len();
+        if !full.starts_with(&found_key[..start]) {
+            return None;
+        }
+        node.get(&full[start..])
+    }
+
+    /// The number of entries below the prefix. Nodes keep the
+    /// number of entries below them, so this takes no walk.
+    pub fn len(&self) -> usize {
+        self.found.as_ref().map_or(0, |(_, node)| node.count())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.found.is_none()
+    }
+
+    /// Iterates over the entries below the prefix in key order,
+    /// with the prefix removed from their keys.
+    pub fn iter(&self) -> StripPrefix<'a, T, M> {
+        let inner = match self.found {
+            Some((ref key, node)) => Iter::subtree(key.clone(), node),
+            None => Iter::empty(),
+        };
+        StripPrefix::new(inner, self.prefix.len())
+    }
+}
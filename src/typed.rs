@@ -0,0 +1,246 @@
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+use super::iter::{Iter, Range};
+use super::{Art, DecodeKey, Key};
+
+/// An `Art` indexed by keys of type `K` instead of bytes.
+///
+/// Keys are encoded with `Key::to_bytes` when they are passed
+/// in and decoded with `DecodeKey::from_bytes` when they are
+/// yielded, so entries are kept in the order of their encoding,
+/// which for the provided `Key` implementations is the order of
+/// the keys themselves.
+///
+/// ```
+/// let mut art = cart::TypedArt::new();
+/// art.set(-5i64, "a");
+/// art.set(3i64, "b");
+/// art.set(40i64, "c");
+///
+/// let keys: Vec<i64> = art.range(0..).map(|(k, _)| k).collect();
+/// assert_eq!(keys, vec![3, 40]);
+/// ```
+pub struct TypedArt<K, V> {
+    art: Art<V>,
+    keys: PhantomData<K>,
+}
+
+impl<K, V> Default for TypedArt<K, V> {
+    fn default() -> TypedArt<K, V> {
+        TypedArt {
+            art: Art::default(),
+            keys: PhantomData,
+        }
+    }
+}
+
+impl<K, V> TypedArt<K, V>
+where
+    K: DecodeKey,
+{
+    pub fn new() -> TypedArt<K, V> {
+        TypedArt::default()
+    }
+
+    /// Sets the value of `k`, returning the value it replaced if
+    /// the key was already present.
+    pub fn set(&mut self, k: K, v: V) -> Option<V> {
+        self.art.set(k, v)
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.art.get(k)
+    }
+
+    pub fn get_mut(&mut self, k: &K) -> Option<&mut V> {
+        self.art.get_mut(&k.to_bytes())
+    }
+
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        self.art.remove(&k.to_bytes())
+    }
+
+    pub fn len(&self) -> usize {
+        self.art.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.art.is_empty()
+    }
+
+    /// Iterates over the entries in the order of the encoded
+    /// keys.
+    pub fn iter(&self) -> TypedIter<'_, K, V, Iter<'_, V>> {
+        TypedIter::new(self.art.iter())
+    }
+
+    /// Iterates over the entries whose keys lie in `range`.
+    pub fn range<R>(
+        &self,
+        range: R,
+    ) -> TypedIter<'_, K, V, Range<'_, V>>
+    where
+        R: RangeBounds<K>,
+    {
+        let start = encode(range.start_bound());
+        let end = encode(range.end_bound());
+        TypedIter::new(self.art.range((start, end)))
+    }
+
+    /// Iterates over the entries whose encoded keys start with
+    /// the encoding of `prefix`, like the strings starting with
+    /// a string or the composite keys sharing their first
+    /// components.
+    pub fn iter_prefix(
+        &self,
+        prefix: &K,
+    ) -> TypedIter<'_, K, V, Iter<'_, V>> {
+        TypedIter::new(self.art.iter_prefix(&prefix.to_bytes()))
+    }
+
+    /// The tree of encoded keys the entries are kept in.
+    pub fn as_art(&self) -> &Art<V> {
+        &self.art
+    }
+
+    pub fn into_art(self) -> Art<V> {
+        self.art
+    }
+}
+
+/// An `Art` keyed by strings.
+///
+/// Keys are passed in as `&str` and yielded as `String`, which
+/// can't fail to decode as every key was a string when it was
+/// set. Strings are kept in the order of their bytes, which is
+/// also the order of their code points.
+///
+/// ```
+/// let mut hosts = cart::StrArt::new();
+/// hosts.set("api.example.com", 1);
+/// hosts.set("app.example.com", 2);
+/// hosts.set("db.internal", 3);
+///
+/// let found: Vec<String> =
+///     hosts.scan_prefix("ap").map(|(host, _)| host).collect();
+/// assert_eq!(found, vec!["api.example.com", "app.example.com"]);
+/// ```
+pub struct StrArt<T> {
+    art: Art<T>,
+}
+
+impl<T> Default for StrArt<T> {
+    fn default() -> StrArt<T> {
+        StrArt {
+            art: Art::default(),
+        }
+    }
+}
+
+impl<T> StrArt<T> {
+    pub fn new() -> StrArt<T> {
+        StrArt::default()
+    }
+
+    /// Sets the value of `k`, returning the value it replaced if
+    /// the key was already present.
+    pub fn set(&mut self, k: &str, v: T) -> Option<T> {
+        self.art.set(k, v)
+    }
+
+    pub fn get(&self, k: &str) -> Option<&T> {
+        self.art.get(k)
+    }
+
+    pub fn get_mut(&mut self, k: &str) -> Option<&mut T> {
+        self.art.get_mut(k.as_bytes())
+    }
+
+    pub fn remove(&mut self, k: &str) -> Option<T> {
+        self.art.remove(k.as_bytes())
+    }
+
+    pub fn len(&self) -> usize {
+        self.art.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.art.is_empty()
+    }
+
+    /// Iterates over the entries in key order.
+    pub fn iter(&self) -> TypedIter<'_, String, T, Iter<'_, T>> {
+        TypedIter::new(self.art.iter())
+    }
+
+    /// Iterates over the entries whose keys start with `prefix`,
+    /// in key order.
+    pub fn scan_prefix(
+        &self,
+        prefix: &str,
+    ) -> TypedIter<'_, String, T, Iter<'_, T>> {
+        TypedIter::new(self.art.iter_prefix(prefix.as_bytes()))
+    }
+
+    /// The tree of the keys' bytes the entries are kept in.
+    pub fn as_art(&self) -> &Art<T> {
+        &self.art
+    }
+
+    pub fn into_art(self) -> Art<T> {
+        self.art
+    }
+}
+
+fn encode<K: Key>(bound: Bound<&K>) -> Bound<Vec<u8>> {
+    match bound {
+        Bound::Included(k) => Bound::Included(k.to_bytes().into()),
+        Bound::Excluded(k) => Bound::Excluded(k.to_bytes().into()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// An iterator over the entries of a `TypedArt`, which decodes
+/// the keys yielded by the underlying iterator `I`.
+pub struct TypedIter<'a, K, V: 'a, I> {
+    inner: I,
+    types: PhantomData<(K, &'a V)>,
+}
+
+impl<'a, K, V, I> TypedIter<'a, K, V, I> {
+    fn new(inner: I) -> TypedIter<'a, K, V, I> {
+        TypedIter {
+            inner,
+            types: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V, I> Iterator for TypedIter<'a, K, V, I>
+where
+    K: DecodeKey,
+    I: Iterator<Item = (Vec<u8>, &'a V)>,
+{
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<(K, &'a V)> {
+        let (key, value) = self.inner.next()?;
+        Some((decode(&key), value))
+    }
+}
+
+impl<'a, K, V, I> DoubleEndedIterator for TypedIter<'a, K, V, I>
+where
+    K: DecodeKey,
+    I: DoubleEndedIterator<Item = (Vec<u8>, &'a V)>,
+{
+    fn next_back(&mut self) -> Option<(K, &'a V)> {
+        let (key, value) = self.inner.next_back()?;
+        Some((decode(&key), value))
+    }
+}
+
+fn decode<K: DecodeKey>(key: &[u8]) -> K {
+    K::from_bytes(key).expect("keys are only set through TypedArt")
+}
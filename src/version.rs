@@ -0,0 +1,68 @@
+#[cfg(feature = "optimistic")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A per-node modification counter.
+///
+/// Every mutation of a node bumps its version. A reader can
+/// remember the version before looking at a node and check it
+/// afterwards, restarting its lookup if the node changed in the
+/// meantime. Without the `optimistic` feature this is a zero
+/// sized no-op and every check succeeds.
+#[derive(Default)]
+pub struct Version {
+    #[cfg(feature = "optimistic")]
+    counter: AtomicU64,
+}
+
+impl Version {
+    #[inline(always)]
+    pub(crate) fn load(&self) -> u64 {
+        #[cfg(feature = "optimistic")]
+        {
+            self.counter.load(Ordering::Acquire)
+        }
+        #[cfg(not(feature = "optimistic"))]
+        {
+            0
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn bump(&self) {
+        #[cfg(feature = "optimistic")]
+        self.counter.fetch_add(1, Ordering::Release);
+    }
+
+    /// Whether the node was left alone since `seen` was loaded.
+    #[inline(always)]
+    pub(crate) fn is_unchanged(&self, seen: u64) -> bool {
+        self.load() == seen
+    }
+}
+
+impl Clone for Version {
+    fn clone(&self) -> Version {
+        #[cfg(feature = "optimistic")]
+        {
+            Version {
+                counter: AtomicU64::new(self.load()),
+            }
+        }
+        #[cfg(not(feature = "optimistic"))]
+        {
+            Version {}
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "optimistic")]
+fn test_version_bumps() {
+    let version = Version::default();
+    let seen = version.load();
+    assert!(version.is_unchanged(seen));
+
+    version.bump();
+    assert!(!version.is_unchanged(seen));
+    assert!(version.clone().is_unchanged(version.load()));
+}
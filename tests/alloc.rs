@@ -0,0 +1,90 @@
+extern crate cart;
+
+mod common;
+
+use common::count;
+
+fn populate() -> cart::Art<u32> {
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(format!("key{}", i * 7919 % 1000).into_bytes(), i);
+    }
+    art
+}
+
+#[test]
+fn test_get_does_not_allocate() {
+    let art = populate();
+
+    let (found, allocated, freed) = count(|| {
+        (0..1000u32)
+            .filter(|i| art.get(format!("key{}", i).as_bytes()).is_some())
+            .count()
+    });
+    assert_eq!(found, 1000);
+    // only the formatted keys themselves
+    assert_eq!(allocated, 1000);
+    assert_eq!(freed, 1000);
+
+    let keys: Vec<Vec<u8>> = (0..2000u32)
+        .map(|i| format!("key{}", i).into_bytes())
+        .collect();
+    let (_, allocated, freed) = count(|| {
+        for key in &keys {
+            art.get(key);
+            art.get_with_consumed(key);
+            art.contains_key(key);
+            art.common_branch_depth(key, b"key999");
+        }
+    });
+    assert_eq!(allocated, 0);
+    assert_eq!(freed, 0);
+}
+
+#[test]
+fn test_set_allocations() {
+    let mut art = populate();
+
+    // a new leaf: the node and its prefix
+    let key = b"key10000".to_vec();
+    let (_, allocated, freed) = count(|| art.set(key, 1));
+    assert_eq!(allocated, 2);
+    // the key passed in
+    assert_eq!(freed, 1);
+
+    // a new leaf without a prefix of its own
+    let key = b"key1000".to_vec();
+    let (_, allocated, _) = count(|| art.set(key, 1));
+    assert_eq!(allocated, 1);
+
+    // overwriting only drops the key passed in
+    let key = b"key10000".to_vec();
+    let (_, allocated, freed) = count(|| art.set(key, 2));
+    assert_eq!(allocated, 0);
+    assert_eq!(freed, 1);
+    assert_eq!(art.get(b"key10000"), Some(&2));
+}
+
+#[test]
+fn test_get_or_insert_with_allocations() {
+    let mut art = populate();
+    let before = *art.get(b"key42").unwrap();
+
+    // a hit neither copies the key nor calls `default`
+    let (_, allocated, freed) = count(|| {
+        *art.get_or_insert_with(b"key42", || panic!("key42 is present")) += 1;
+    });
+    assert_eq!(allocated, 0);
+    assert_eq!(freed, 0);
+    assert_eq!(art.get(b"key42"), Some(&(before + 1)));
+
+    // a miss allocates the new leaf and its prefix
+    let (_, allocated, freed) = count(|| {
+        *art.get_or_insert_with(b"key10000", || 7) += 1;
+    });
+    assert_eq!(allocated, 2);
+    assert_eq!(freed, 0);
+    assert_eq!(art.get(b"key10000"), Some(&8));
+    assert_eq!(art.len(), 1001);
+    art.validate();
+}
@@ -0,0 +1,112 @@
+extern crate cart;
+extern crate rand;
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::count;
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+fn build(keys: &[Vec<u8>], value: usize) -> (cart::Art<usize>, BTreeMap<Vec<u8>, usize>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.clone(), value + i);
+        model.insert(key.clone(), value + i);
+    }
+    (art, model)
+}
+
+fn check_append(a: &[Vec<u8>], b: &[Vec<u8>]) {
+    let (mut art, mut model) = build(a, 0);
+    let (mut other, other_model) = build(b, 1000);
+
+    art.append(&mut other);
+    model.extend(other_model);
+
+    art.validate();
+    other.validate();
+    assert!(other.is_empty());
+    assert_eq!(other.iter().count(), 0);
+    assert_eq!(art.len(), model.len());
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.clone()));
+
+    // both trees are still usable
+    other.set(b"abc".to_vec(), 1);
+    assert_eq!(other.get(b"abc"), Some(&1));
+    art.set(b"abc".to_vec(), 2);
+    assert_eq!(art.get(b"abc"), Some(&2));
+}
+
+fn keys(keys: &[&[u8]]) -> Vec<Vec<u8>> {
+    keys.iter().map(|k| k.to_vec()).collect()
+}
+
+#[test]
+fn test_append() {
+    let a = keys(&[b"abcdef", b"abcxyz", b"abc", b"b"]);
+    let b = keys(&[b"abcdeg", b"abd", b"ab", b"", b"b", b"bcd", b"c"]);
+
+    check_append(&a, &b);
+    check_append(&b, &a);
+    check_append(&a, &a);
+    check_append(&a, &[]);
+    check_append(&[], &a);
+    check_append(&[], &[]);
+
+    // the appended keys split or extend prefixes of this tree
+    check_append(&keys(&[b"abcdef"]), &keys(&[b"abcxyz"]));
+    check_append(&keys(&[b"abcdef"]), &keys(&[b"xyz"]));
+    check_append(&keys(&[b"abcdef"]), &keys(&[b"abcdefgh"]));
+    check_append(&keys(&[b"abcdefgh"]), &keys(&[b"abcdef"]));
+}
+
+#[test]
+fn test_append_random() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+    for _ in 0..50 {
+        let mut random_keys = || -> Vec<Vec<u8>> {
+            let n = rng.gen_range(0, 100);
+            (0..n)
+                .map(|_| {
+                    let len = rng.gen_range(0, 5);
+                    (0..len).map(|_| rng.gen_range(0, 4)).collect()
+                })
+                .collect()
+        };
+        let a = random_keys();
+        let b = random_keys();
+        check_append(&a, &b);
+    }
+}
+
+#[test]
+fn test_append_disjoint_shards_reuses_nodes() {
+    let shard = |first: u8| -> Vec<Vec<u8>> {
+        (0..1000u32)
+            .map(|i| {
+                let mut key = vec![first];
+                key.extend_from_slice(format!("{}", i * 7919 % 1000).as_bytes());
+                key
+            })
+            .collect()
+    };
+
+    let (mut art, mut model) = build(&shard(b'a'), 0);
+    for first in b'b'..b'e' {
+        let (mut other, other_model) = build(&shard(first), 0);
+
+        // a handful of allocations for the walk and the new
+        // parent node, instead of some for each of the 1000 keys
+        let (_, allocated, _) = count(|| art.append(&mut other));
+        assert!(allocated < 10, "{} allocations", allocated);
+
+        model.extend(other_model);
+    }
+
+    art.validate();
+    assert_eq!(art.len(), 4000);
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model));
+}
@@ -0,0 +1,94 @@
+extern crate cart;
+
+use std::cell::Cell;
+
+use cart::Automaton;
+
+// matches keys of the same length as the pattern, where `?`
+// stands for any byte. the state is the number of bytes matched
+// so far, or `None` after a mismatch.
+struct Wildcard<'a> {
+    pattern: &'a [u8],
+    steps: Cell<usize>,
+}
+
+impl<'a> Automaton for Wildcard<'a> {
+    type State = Option<usize>;
+
+    fn start(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn step(&self, state: &Option<usize>, byte: u8) -> Option<usize> {
+        self.steps.set(self.steps.get() + 1);
+        let matched = (*state)?;
+        match self.pattern.get(matched) {
+            Some(&b'?') => Some(matched + 1),
+            Some(&p) if p == byte => Some(matched + 1),
+            _ => None,
+        }
+    }
+
+    fn is_match(&self, state: &Option<usize>) -> bool {
+        *state == Some(self.pattern.len())
+    }
+
+    fn can_match(&self, state: &Option<usize>) -> bool {
+        state.is_some()
+    }
+}
+
+fn populated() -> cart::Art<u32> {
+    let mut art = cart::Art::default();
+    for i in 0..10_000u32 {
+        art.set(format!("{:04}", i).into_bytes(), i);
+    }
+    art
+}
+
+#[test]
+fn test_search_with_wildcards() {
+    let art = populated();
+
+    let wildcard = Wildcard {
+        pattern: b"1?3?",
+        steps: Cell::new(0),
+    };
+    let found: Vec<u32> = art.search(&wildcard).into_iter().map(|(_, v)| *v).collect();
+    let expected: Vec<u32> = (0..10_000)
+        .filter(|i| i / 1000 == 1 && i / 10 % 10 == 3)
+        .collect();
+    assert_eq!(found, expected);
+
+    // dead branches are cut off instead of walked to the end
+    assert!(
+        wildcard.steps.get() < 1000,
+        "{} steps",
+        wildcard.steps.get()
+    );
+}
+
+// accepts every key, to check that nothing is lost on the way
+struct Everything;
+
+impl Automaton for Everything {
+    type State = ();
+
+    fn start(&self) {}
+
+    fn step(&self, _: &(), _: u8) {}
+
+    fn is_match(&self, _: &()) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_search_everything() {
+    let art = populated();
+    let found: Vec<(Vec<u8>, &u32)> = art.search(&Everything);
+    assert!(found.into_iter().eq(art.iter()));
+
+    let empty: cart::Art<u32> = cart::Art::default();
+    assert!(empty.search(&Everything).is_empty());
+}
@@ -0,0 +1,105 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+// inserts `keys` in every order and compares against a model
+fn check_all_orders(keys: &[&[u8]]) {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    permute(&mut order, 0, keys);
+}
+
+fn permute(order: &mut Vec<usize>, start: usize, keys: &[&[u8]]) {
+    if start == order.len() {
+        check(order, keys);
+        return;
+    }
+    for i in start..order.len() {
+        order.swap(start, i);
+        permute(order, start + 1, keys);
+        order.swap(start, i);
+    }
+}
+
+fn check(order: &[usize], keys: &[&[u8]]) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for &i in order {
+        art.set(keys[i].to_vec(), i);
+        model.insert(keys[i].to_vec(), i);
+    }
+    art.validate();
+
+    for key in keys {
+        assert_eq!(art.get(key), model.get(*key), "order {:?}", order);
+    }
+
+    let drained: Vec<(Vec<u8>, usize)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, usize)> = model.into_iter().collect();
+    assert_eq!(drained, expected, "order {:?}", order);
+}
+
+#[test]
+fn test_key_ends_at_node_boundary() {
+    // "abc" ends exactly where the prefix of the node holding
+    // "abcdef" and "abcxyz" ends
+    check_all_orders(&[b"abcdef", b"abcxyz", b"abc"]);
+    // one byte past the boundary
+    check_all_orders(&[b"abcdef", b"abcxyz", b"abcd"]);
+    // one byte short of it
+    check_all_orders(&[b"abcdef", b"abcxyz", b"ab"]);
+}
+
+#[test]
+fn test_key_ends_inside_prefix() {
+    check_all_orders(&[b"abcdef", b"abc"]);
+    check_all_orders(&[b"abcdef", b"a", b"abcde"]);
+    check_all_orders(&[b"abcdef", b"", b"abcdefg"]);
+    check_all_orders(&[b"abcdef", b"abcdeg", b"abcde", b"abcd"]);
+}
+
+// removes `keys` in every order, after inserting all of them
+fn check_removal_orders(keys: &[&[u8]]) {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    permute_removals(&mut order, 0, keys);
+}
+
+fn permute_removals(order: &mut Vec<usize>, start: usize, keys: &[&[u8]]) {
+    if start == order.len() {
+        let mut art = cart::Art::default();
+        let mut model = BTreeMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            art.set(key.to_vec(), i);
+            model.insert(key.to_vec(), i);
+        }
+
+        for &i in order.iter() {
+            assert_eq!(art.remove(keys[i]), model.remove(keys[i]));
+            art.validate();
+            for key in keys {
+                assert_eq!(art.get(key), model.get(*key), "order {:?}", order);
+            }
+            assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.clone()));
+        }
+        return;
+    }
+    for i in start..order.len() {
+        order.swap(start, i);
+        permute_removals(order, start + 1, keys);
+        order.swap(start, i);
+    }
+}
+
+#[test]
+fn test_keys_prefixing_each_other() {
+    // every key is a strict prefix of the next one, so each
+    // value sits on the path to the others
+    let chain: &[&[u8]] = &[b"", b"a", b"ab", b"abc", b"abcdef"];
+    check_all_orders(chain);
+    check_removal_orders(chain);
+
+    // and with branches off the chain
+    let branched: &[&[u8]] = &[b"ab", b"abc", b"abd", b"abcd", b"b"];
+    check_all_orders(branched);
+    check_removal_orders(branched);
+}
@@ -0,0 +1,48 @@
+extern crate cart;
+
+#[test]
+fn test_common_branch_depth() {
+    let mut art = cart::Art::default();
+    for key in &[
+        &b"abcdef"[..],
+        b"abcxyz",
+        b"abq",
+        b"prefix_common_a",
+        b"prefix_common_b",
+        b"z",
+    ] {
+        art.set(key.to_vec(), ());
+    }
+
+    // branching below "abc"
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abcxyz"), 3);
+    // branching below "ab"
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abq"), 2);
+    assert_eq!(art.common_branch_depth(b"abcxyz", b"abq"), 2);
+    // branching at the root
+    assert_eq!(art.common_branch_depth(b"abcdef", b"z"), 0);
+    assert_eq!(art.common_branch_depth(b"abq", b"prefix_common_a"), 0);
+    // the shared path is compressed into a single prefix
+    assert_eq!(
+        art.common_branch_depth(b"prefix_common_a", b"prefix_common_b"),
+        14
+    );
+    // the same key never branches
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abcdef"), 6);
+}
+
+#[test]
+fn test_common_branch_depth_is_structural() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), ());
+    art.set(b"abcdeg".to_vec(), ());
+
+    // "abcde" is shared by the keys and compressed into one node
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abcdeg"), 5);
+
+    // a third key splits the shared path higher up, but the two
+    // original keys still branch at the same node
+    art.set(b"abx".to_vec(), ());
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abcdeg"), 5);
+    assert_eq!(art.common_branch_depth(b"abcdef", b"abx"), 2);
+}
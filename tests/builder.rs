@@ -0,0 +1,131 @@
+extern crate cart;
+
+use cart::{Art, ArtBuilder, Stats};
+
+fn populate(art: &mut Art<usize>) {
+    for a in 0..40u8 {
+        for b in 0..20u8 {
+            art.set(vec![a, b, a], a as usize * 20 + b as usize);
+        }
+    }
+}
+
+fn check(art: &Art<usize>) {
+    for a in 0..40u8 {
+        for b in 0..20u8 {
+            assert_eq!(art.get(&[a, b, a]), Some(&(a as usize * 20 + b as usize)));
+        }
+        assert_eq!(art.get(&[a, 20, a]), None);
+    }
+}
+
+#[test]
+fn test_default_thresholds() {
+    let mut art = ArtBuilder::new().build();
+    populate(&mut art);
+    check(&art);
+
+    assert_eq!(
+        art.stats(),
+        Stats {
+            node4: 800,
+            node16: 0,
+            node48: 41,
+            node256: 0,
+        }
+    );
+}
+
+#[test]
+fn test_lowered_node48_capacity() {
+    let mut default = Art::default();
+    populate(&mut default);
+
+    let mut art = ArtBuilder::new().node48_capacity(16).build();
+    populate(&mut art);
+    check(&art);
+
+    assert!(art.stats().node256 > default.stats().node256);
+    assert_eq!(art.stats().node256, 41);
+}
+
+#[test]
+fn test_disabled_node48() {
+    let mut art = ArtBuilder::new().node16_capacity(8).node48(false).build();
+    populate(&mut art);
+    check(&art);
+
+    let stats = art.stats();
+    assert_eq!(stats.node48, 0);
+    assert_eq!(stats.node256, 41);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_capacity() {
+    ArtBuilder::new().node4_capacity(5);
+}
+
+#[test]
+fn test_try_capacity() {
+    let err = ArtBuilder::new().try_node16_capacity(0).unwrap_err();
+    assert_eq!(
+        err,
+        cart::CartError::InvalidCapacity {
+            variant: "Node16",
+            capacity: 0,
+            max: 16
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Node16 capacity must be between 1 and 16, not 0"
+    );
+
+    let mut art = ArtBuilder::new()
+        .try_node4_capacity(2)
+        .and_then(|b| b.try_node48_capacity(48))
+        .unwrap()
+        .build();
+    populate(&mut art);
+    check(&art);
+}
+
+#[test]
+fn test_root_capacity() {
+    let mut default = Art::default();
+    populate(&mut default);
+
+    let mut art = ArtBuilder::new().root_capacity(256).build();
+    assert_eq!(art.root_variant(), "Node256");
+    populate(&mut art);
+    check(&art);
+    art.validate();
+
+    assert_eq!(
+        art.stats(),
+        Stats {
+            node4: 800,
+            node16: 0,
+            node48: 40,
+            node256: 1,
+        }
+    );
+
+    // values and prefixes at the root behave as before
+    for art in &mut [&mut default, &mut art] {
+        art.set(vec![], 1000);
+        art.set(vec![0, 0], 1001);
+    }
+    assert_eq!(art.get(&[]), Some(&1000));
+    assert!(art.drain().eq(default.drain()));
+}
+
+#[test]
+fn test_small_root_capacity() {
+    let art: Art<u8> = ArtBuilder::new().root_capacity(10).build();
+    assert_eq!(art.root_variant(), "Node16");
+
+    let art: Art<u8> = ArtBuilder::new().root_capacity(0).build();
+    assert_eq!(art.root_variant(), "Node4");
+}
@@ -0,0 +1,145 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+use cart::Art;
+
+fn sorted_keys() -> Vec<Vec<u8>> {
+    let mut keys: Vec<Vec<u8>> = (0..3000u32)
+        .map(|i| {
+            let x = i.wrapping_mul(2_654_435_761);
+            let len = 1 + (x % 5) as usize;
+            (x >> 8).to_be_bytes()[..len.min(4)].to_vec()
+        })
+        .collect();
+    keys.push(vec![]);
+    keys.push(b"abc".to_vec());
+    keys.push(b"abcd".to_vec());
+    keys.push(b"ab".to_vec());
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_from_sorted_refs_matches_bulk_load() {
+    let keys = sorted_keys();
+
+    // all keys live in one contiguous buffer
+    let buf: Vec<u8> = keys.iter().flat_map(|k| k.iter().cloned()).collect();
+    let mut refs = vec![];
+    let mut offset = 0;
+    for (i, key) in keys.iter().enumerate() {
+        refs.push((&buf[offset..offset + key.len()], i));
+        offset += key.len();
+    }
+
+    let mut from_refs = Art::from_sorted_refs(refs);
+    let mut bulk = Art::bulk_load(keys.iter().cloned().zip(0..));
+    from_refs.validate();
+    bulk.validate();
+    assert_eq!(from_refs.stats(), bulk.stats());
+
+    let mut model = BTreeMap::new();
+    let mut inserted = Art::default();
+    for (i, key) in keys.iter().enumerate() {
+        model.insert(key.clone(), i);
+        inserted.set(key.clone(), i);
+    }
+    for (k, v) in &model {
+        assert_eq!(from_refs.get(k), Some(v));
+    }
+
+    let expected: Vec<(Vec<u8>, usize)> = model.into_iter().collect();
+    assert_eq!(from_refs.drain().collect::<Vec<_>>(), expected);
+    assert_eq!(bulk.drain().collect::<Vec<_>>(), expected);
+    assert_eq!(inserted.drain().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_from_sorted_refs_edge_cases() {
+    let mut empty: Art<u8> = Art::from_sorted_refs(vec![]);
+    assert_eq!(empty.drain().count(), 0);
+
+    let mut dups = Art::from_sorted_refs(vec![(&b"a"[..], 1), (b"a", 2), (b"b", 3)]);
+    assert_eq!(dups.get(b"a"), Some(&2));
+    assert_eq!(
+        dups.drain().collect::<Vec<_>>(),
+        vec![(b"a".to_vec(), 2), (b"b".to_vec(), 3)]
+    );
+
+    // the tree keeps working like any other afterwards
+    let mut art = Art::from_sorted_refs(vec![(&b"abc"[..], 1), (b"abd", 2)]);
+    art.set(b"abe".to_vec(), 3);
+    art.set(b"x".to_vec(), 4);
+    assert_eq!(art.get(b"abc"), Some(&1));
+    assert_eq!(art.get(b"abe"), Some(&3));
+    assert_eq!(art.get(b"x"), Some(&4));
+}
+
+#[test]
+#[should_panic]
+fn test_from_sorted_refs_unsorted() {
+    Art::from_sorted_refs(vec![(&b"b"[..], 1), (b"a", 2)]);
+}
+
+#[test]
+fn test_try_from_sorted_refs() {
+    let keys: Vec<&[u8]> = vec![b"a", b"b", b"b", b"c", b"ab"];
+    let err = Art::try_from_sorted_refs(keys.iter().map(|k| (*k, 0))).unwrap_err();
+    assert_eq!(err, cart::CartError::UnsortedKeys { index: 4 });
+
+    let art = Art::try_from_sorted_refs(keys[..4].iter().map(|k| (*k, 0))).unwrap();
+    art.validate();
+    assert_eq!(art.len(), 3);
+}
+
+#[test]
+fn test_collect_and_extend() {
+    let keys = sorted_keys();
+
+    // sorted input, then the same keys shuffled, with
+    // duplicates whose last value has to win
+    let sorted: Art<usize> = keys.iter().cloned().zip(0..).collect();
+    sorted.validate();
+    let expected: BTreeMap<Vec<u8>, usize> = keys.iter().cloned().zip(0..).collect();
+    assert_eq!(sorted.len(), expected.len());
+    assert!(sorted.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+
+    let mut shuffled: Vec<(Vec<u8>, usize)> = keys.iter().cloned().zip(0..).collect();
+    for i in 0..shuffled.len() {
+        let j = i * 7919 % shuffled.len();
+        shuffled.swap(i, j);
+    }
+    let art: Art<usize> = shuffled.iter().cloned().collect();
+    let expected: BTreeMap<Vec<u8>, usize> = shuffled.iter().cloned().collect();
+    art.validate();
+    assert_eq!(art.len(), expected.len());
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(expected));
+
+    // extending a populated tree, first sorted and then not
+    let mut keys = keys;
+    keys.dedup();
+    let mut art: Art<usize> = keys.iter().step_by(2).cloned().zip(0..).collect();
+    let mut model: BTreeMap<Vec<u8>, usize> = keys.iter().step_by(2).cloned().zip(0..).collect();
+    let more: Vec<(Vec<u8>, usize)> = keys
+        .iter()
+        .skip(1)
+        .step_by(3)
+        .cloned()
+        .zip(5000..)
+        .collect();
+    art.extend(more.clone());
+    model.extend(more.clone());
+    art.extend(more.into_iter().rev().map(|(k, v)| (k, v + 1)));
+    model.extend(
+        keys.iter()
+            .skip(1)
+            .step_by(3)
+            .cloned()
+            .zip(5001..)
+            .collect::<Vec<_>>(),
+    );
+    art.validate();
+    assert_eq!(art.len(), model.len());
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model));
+}
@@ -0,0 +1,33 @@
+extern crate cart;
+
+use std::sync::Arc;
+
+use cart::ArtBuilder;
+
+#[test]
+fn test_clear() {
+    let shared = Arc::new(());
+    let mut art = ArtBuilder::new()
+        .intern_prefixes(true)
+        .root_capacity(256)
+        .build();
+
+    for i in 0..1000u32 {
+        art.set(format!("{}", i * 7919 % 1000).into_bytes(), shared.clone());
+    }
+    assert_eq!(Arc::strong_count(&shared), 1001);
+
+    art.clear();
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert!(art.is_empty());
+    assert_eq!(art.iter().count(), 0);
+    assert_eq!(art.root_variant(), "Node4");
+    assert_eq!(art.get(b"123"), None);
+
+    // the tree is reusable and keeps its configuration
+    art.set(b"abc".to_vec(), shared.clone());
+    art.set(b"abd".to_vec(), shared.clone());
+    assert_eq!(art.len(), 2);
+    assert!(art.get(b"abc").is_some());
+    art.validate();
+}
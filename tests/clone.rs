@@ -0,0 +1,79 @@
+extern crate cart;
+
+mod common;
+
+use common::count;
+
+#[test]
+fn test_clone_stays_consistent_while_writing() {
+    let mut art = cart::ArtBuilder::new().intern_prefixes(true).build();
+    for i in 0..2000u32 {
+        art.set(format!("events/{:05}", i).into_bytes(), i.to_string());
+    }
+    let before: Vec<(Vec<u8>, String)> = art.iter().map(|(k, v)| (k, v.clone())).collect();
+
+    let copy = art.clone();
+    copy.validate();
+    assert_eq!(copy.len(), art.len());
+
+    // stream the copy while the tree keeps changing
+    let mut streamed = vec![];
+    for (i, (key, value)) in copy.iter().enumerate() {
+        streamed.push((key, value.clone()));
+        art.set(
+            format!("events/{:05}", 2000 + i).into_bytes(),
+            "new".to_string(),
+        );
+        art.remove(format!("events/{:05}", i).as_bytes());
+    }
+    assert_eq!(streamed, before);
+    assert_eq!(art.len(), 2000);
+    art.validate();
+}
+
+#[test]
+fn test_clone_frees_its_own_nodes() {
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::Art::default();
+        for i in 0..500u32 {
+            art.set(format!("{}", i * 7919).into_bytes(), vec![i; 3]);
+        }
+        let copy = art.clone();
+        art.clear();
+        drop(art);
+        assert_eq!(copy.len(), 500);
+        assert_eq!(copy.get(b"7919"), Some(&vec![1; 3]));
+    });
+    assert_eq!(allocated, freed);
+}
+
+#[test]
+fn test_clone_is_deep() {
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::Art::default();
+        for i in 0..500u32 {
+            art.set(format!("{}", i * 7).into_bytes(), vec![i]);
+        }
+
+        let mut copy = art.clone();
+        copy.validate();
+        assert!(copy.iter().eq(art.iter()));
+
+        // changes to either tree stay in it
+        art.get_mut(b"7").unwrap().push(1);
+        copy.remove(b"14");
+        copy.set(b"new".to_vec(), vec![]);
+        assert_eq!(art.get(b"7"), Some(&vec![1, 1]));
+        assert_eq!(copy.get(b"7"), Some(&vec![1]));
+        assert_eq!(art.get(b"14"), Some(&vec![2]));
+        assert_eq!(art.get(b"new"), None);
+        assert_eq!(copy.count_prefix(b""), 500);
+
+        drop(art);
+        copy.validate();
+        assert_eq!(copy.len(), 500);
+    });
+
+    // every node was freed exactly once
+    assert_eq!(allocated, freed);
+}
@@ -0,0 +1,57 @@
+// Shared helpers for the integration tests. Each test file that
+// declares `mod common;` gets the counting allocator below as its
+// global allocator, so allocation counts can be asserted with:
+//
+//     cargo test --test alloc
+//
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations and frees made by the current thread, so
+// that tests running in parallel don't disturb each other.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static FREES: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = FREES.try_with(|f| f.set(f.get() + 1));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// The number of allocations made by this thread so far.
+pub fn allocations() -> usize {
+    ALLOCATIONS.with(|a| a.get())
+}
+
+/// The number of frees made by this thread so far.
+pub fn frees() -> usize {
+    FREES.with(|f| f.get())
+}
+
+/// Runs `f`, returning its result along with the number of
+/// allocations and frees it made.
+pub fn count<R, F>(f: F) -> (R, usize, usize)
+where
+    F: FnOnce() -> R,
+{
+    let (allocated, freed) = (allocations(), frees());
+    let result = f();
+
+    (result, allocations() - allocated, frees() - freed)
+}
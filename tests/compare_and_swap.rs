@@ -0,0 +1,56 @@
+extern crate cart;
+
+use cart::CompareAndSwapError;
+
+#[test]
+fn test_compare_and_swap() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+
+    // insert if absent, also where the key ends inside a prefix
+    assert_eq!(art.compare_and_swap(b"abc", None, Some(2)), Ok(None));
+    assert_eq!(art.get(b"abc"), Some(&2));
+    assert_eq!(
+        art.compare_and_swap(b"abc", None, Some(3)),
+        Err(CompareAndSwapError {
+            current: Some(&2),
+            proposed: Some(3),
+        })
+    );
+
+    // replace
+    assert_eq!(art.compare_and_swap(b"abc", Some(&2), Some(4)), Ok(Some(2)));
+    assert_eq!(
+        art.compare_and_swap(b"abc", Some(&2), Some(5)),
+        Err(CompareAndSwapError {
+            current: Some(&4),
+            proposed: Some(5),
+        })
+    );
+    assert_eq!(art.get(b"abc"), Some(&4));
+
+    // remove
+    assert_eq!(
+        art.compare_and_swap(b"abcdef", Some(&0), None),
+        Err(CompareAndSwapError {
+            current: Some(&1),
+            proposed: None,
+        })
+    );
+    assert_eq!(art.compare_and_swap(b"abcdef", Some(&1), None), Ok(Some(1)));
+    assert_eq!(art.get(b"abcdef"), None);
+    assert_eq!(
+        art.compare_and_swap(b"abcdef", Some(&1), None),
+        Err(CompareAndSwapError {
+            current: None,
+            proposed: None,
+        })
+    );
+
+    // expecting absence and removing is a no-op
+    assert_eq!(art.compare_and_swap(b"xyz", None, None), Ok(None));
+
+    assert_eq!(art.len(), 1);
+    art.validate();
+    assert_eq!(art.drain().collect::<Vec<_>>(), vec![(b"abc".to_vec(), 4)]);
+}
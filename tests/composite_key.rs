@@ -0,0 +1,86 @@
+extern crate cart;
+extern crate rand;
+
+use cart::key_encoding::CompositeKey;
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+fn encode(tenant: u64, table: &[u8], id: i64) -> CompositeKey {
+    CompositeKey::new()
+        .push_u64(tenant)
+        .push_bytes(table)
+        .push_i64(id)
+}
+
+#[test]
+fn test_composite_keys_sort_by_component() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut tuples: Vec<(u64, Vec<u8>, i64)> = (0..500)
+        .map(|_| {
+            let len = rng.gen_range(0, 4);
+            // zero bytes and 0xff are the interesting ones
+            let table = (0..len)
+                .map(|_| [0, 1, 0xff][rng.gen_range(0, 3)])
+                .collect();
+            (rng.gen_range(0, 3), table, rng.gen_range(-2, 3))
+        })
+        .collect();
+    tuples.sort();
+    tuples.dedup();
+
+    let mut keys: Vec<CompositeKey> = tuples.iter().map(|(t, n, i)| encode(*t, n, *i)).collect();
+    let before = keys.clone();
+    keys.sort();
+    assert_eq!(keys, before);
+
+    for ((tenant, table, id), key) in tuples.iter().zip(&keys) {
+        let mut components = CompositeKey::decode(key.as_bytes());
+        assert_eq!(components.next_u64(), Some(*tenant));
+        assert_eq!(components.next_bytes().as_ref(), Some(table));
+        assert_eq!(components.next_i64(), Some(*id));
+        assert!(components.is_empty());
+    }
+}
+
+#[test]
+fn test_composite_keys_in_a_tree() {
+    let mut art = cart::Art::default();
+    for tenant in 0..3 {
+        for table in &["users", "user", "orders"] {
+            for id in -2..3 {
+                art.set(encode(tenant, table.as_bytes(), id), ());
+            }
+        }
+    }
+
+    // every row of one table, in key order
+    let prefix = CompositeKey::new().push_u64(1).push_str("user");
+    let rows: Vec<i64> = art
+        .iter_prefix(prefix.as_bytes())
+        .map(|(key, _)| {
+            let mut components = CompositeKey::decode(&key);
+            components.next_u64();
+            components.next_str();
+            components.next_i64().unwrap()
+        })
+        .collect();
+    assert_eq!(rows, vec![-2, -1, 0, 1, 2]);
+    assert_eq!(
+        art.count_prefix(CompositeKey::new().push_u64(2).as_bytes()),
+        15
+    );
+}
+
+#[test]
+fn test_decoding_mismatched_components() {
+    let key = CompositeKey::new().push_str("a").push_u64(1);
+    let mut components = CompositeKey::decode(key.as_bytes());
+    assert_eq!(components.next_str(), Some("a".to_string()));
+    assert_eq!(components.next_bytes(), None);
+    assert_eq!(components.next_u64(), Some(1));
+    assert_eq!(components.next_u64(), None);
+
+    let invalid = CompositeKey::new().push_bytes(&[0xff]);
+    let mut components = CompositeKey::decode(invalid.as_bytes());
+    assert_eq!(components.next_str(), None);
+    assert_eq!(components.next_bytes(), Some(vec![0xff]));
+}
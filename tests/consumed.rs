@@ -0,0 +1,33 @@
+extern crate cart;
+
+#[test]
+fn test_get_with_consumed() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+    art.set(b"abcx".to_vec(), 3);
+    art.set(b"abcxyz".to_vec(), 2);
+    art.set(b"q".to_vec(), 4);
+
+    // full matches
+    assert_eq!(art.get_with_consumed(b"abcdef"), (6, Some(&1)));
+    assert_eq!(art.get_with_consumed(b"abcxyz"), (6, Some(&2)));
+    assert_eq!(art.get_with_consumed(b"abcx"), (4, Some(&3)));
+    assert_eq!(art.get_with_consumed(b"q"), (1, Some(&4)));
+
+    // diverging inside a node prefix
+    assert_eq!(art.get_with_consumed(b"abcdeg"), (5, None));
+    assert_eq!(art.get_with_consumed(b"abcxyy"), (5, None));
+    assert_eq!(art.get_with_consumed(b"ab"), (2, None));
+
+    // diverging at a child byte
+    assert_eq!(art.get_with_consumed(b"abcq"), (3, None));
+    assert_eq!(art.get_with_consumed(b"z"), (0, None));
+    assert_eq!(art.get_with_consumed(b"qq"), (1, None));
+
+    // running past a stored key
+    assert_eq!(art.get_with_consumed(b"abcdefg"), (6, None));
+
+    // matching a path without a value
+    assert_eq!(art.get_with_consumed(b"abc"), (3, None));
+    assert_eq!(art.get_with_consumed(b""), (0, None));
+}
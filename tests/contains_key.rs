@@ -0,0 +1,27 @@
+extern crate cart;
+
+#[test]
+fn test_contains_key() {
+    let mut art = cart::Art::default();
+    assert!(!art.contains_key(b""));
+
+    let keys: &[&[u8]] = &[b"abcdef", b"abcxyz", b"abc", b"b", b"bcdefgh"];
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.to_vec(), i);
+    }
+
+    for key in keys {
+        assert!(art.contains_key(key), "{:?}", key);
+    }
+
+    // paths through and inside prefixes that hold no value
+    let absent: &[&[u8]] = &[b"", b"a", b"ab", b"abcd", b"abcdefg", b"bcd", b"bx", b"c"];
+    for key in absent {
+        assert!(!art.contains_key(key), "{:?}", key);
+        assert_eq!(art.get(key), None);
+    }
+
+    art.remove(b"abc");
+    assert!(!art.contains_key(b"abc"));
+    assert!(art.contains_key(b"abcdef"));
+}
@@ -0,0 +1,135 @@
+extern crate cart;
+extern crate rand;
+
+use std::collections::BTreeMap;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+fn check(art: &cart::Art<u32>, model: &BTreeMap<Vec<u8>, u32>) {
+    art.validate();
+    assert_eq!(art.count_prefix(b""), model.len());
+
+    let mut prefixes = vec![vec![]];
+    for len in 1..4 {
+        for i in 0..(4u32.pow(len)) {
+            prefixes.push((0..len).map(|j| (i / 4u32.pow(j) % 4) as u8).collect());
+        }
+    }
+    for prefix in prefixes {
+        let expected = model.keys().filter(|k| k.starts_with(&prefix)).count();
+        assert_eq!(art.count_prefix(&prefix), expected, "{:?}", prefix);
+    }
+}
+
+#[test]
+fn test_count_prefix() {
+    let mut art = cart::Art::default();
+    for key in &["users/1", "users/2", "users/30", "orders/1", "users"] {
+        art.set(key.as_bytes().to_vec(), 0);
+    }
+
+    assert_eq!(art.count_prefix(b""), 5);
+    assert_eq!(art.count_prefix(b"users"), 4);
+    assert_eq!(art.count_prefix(b"users/"), 3);
+    assert_eq!(art.count_prefix(b"use"), 4);
+    assert_eq!(art.count_prefix(b"users/3"), 1);
+    assert_eq!(art.count_prefix(b"orders/1"), 1);
+    assert_eq!(art.count_prefix(b"orders/10"), 0);
+    assert_eq!(art.count_prefix(b"x"), 0);
+
+    art.remove(b"users/2");
+    assert_eq!(art.count_prefix(b"users/"), 2);
+    art.remove_prefix(b"users/");
+    assert_eq!(art.count_prefix(b"users"), 1);
+    assert_eq!(art.count_prefix(b""), 2);
+}
+
+#[test]
+fn test_count_prefix_matches_model() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let random_key = |rng: &mut XorShiftRng| -> Vec<u8> {
+        let len = rng.gen_range(0, 6);
+        (0..len).map(|_| rng.gen_range(0, 4)).collect()
+    };
+
+    for round in 0..300 {
+        let key = random_key(&mut rng);
+        let value = rng.gen_range(0, 100);
+        match rng.gen_range(0, 12) {
+            0..=2 => {
+                art.set(key.clone(), value);
+                model.insert(key, value);
+            }
+            3 | 4 => {
+                art.remove(&key);
+                model.remove(&key);
+            }
+            5 => {
+                art.update(key.clone(), |old| match old {
+                    Some(_) => None,
+                    None => Some(value),
+                });
+                if model.remove(&key).is_none() {
+                    model.insert(key, value);
+                }
+            }
+            6 => {
+                let mut other = art.split_off(&key);
+                let moved = model.split_off(&key);
+                check(&other, &moved);
+                if round % 2 == 0 {
+                    art.append(&mut other);
+                    model.extend(moved);
+                }
+            }
+            7 => {
+                *art.get_or_insert_with(&key, || value) += 1;
+                *model.entry(key).or_insert(value) += 1;
+            }
+            8 => match art.entry(key.clone()) {
+                cart::Entry::Occupied(entry) => {
+                    assert_eq!(entry.remove(), model.remove(&key).unwrap());
+                }
+                cart::Entry::Vacant(entry) => {
+                    entry.insert(value);
+                    model.insert(key, value);
+                }
+            },
+            9 => {
+                let expired = |v: &u32| *v < 50;
+                let removed = art.remove_if(&key, expired);
+                if model.get(&key).is_some_and(expired) {
+                    assert_eq!(removed, model.remove(&key));
+                } else {
+                    assert_eq!(removed, None);
+                }
+            }
+            10 => {
+                let mut cursor = art.cursor_mut();
+                if cursor.seek(&key) {
+                    let next = cursor.key().unwrap().to_vec();
+                    assert_eq!(cursor.remove_current(), model.remove(&next));
+                }
+                cursor.insert(key.clone(), value);
+                model.insert(key, value);
+            }
+            _ => {
+                let prefix = &key[..key.len().min(2)];
+                let before = model.len();
+                model.retain(|k, _| !k.starts_with(prefix));
+                assert_eq!(art.remove_prefix(prefix), before - model.len());
+            }
+        }
+        check(&art, &model);
+    }
+
+    art.retain(|_, value| *value % 3 != 0);
+    model.retain(|_, value| *value % 3 != 0);
+    check(&art, &model);
+
+    let built = cart::Art::bulk_load(model.clone());
+    check(&built, &model);
+}
@@ -0,0 +1,243 @@
+extern crate cart;
+extern crate rand;
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::count;
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+fn populated() -> (cart::Art<usize>, BTreeMap<Vec<u8>, usize>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let keys: &[&[u8]] = &[
+        b"", b"a", b"ab", b"abc", b"abd", b"abe", b"b", b"ba", b"bcd", b"c", b"cab", b"cb",
+    ];
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.to_vec(), i);
+        model.insert(key.to_vec(), i);
+    }
+    for i in 0..60u8 {
+        art.set(vec![b'd', i, i], i as usize);
+        model.insert(vec![b'd', i, i], i as usize);
+    }
+
+    (art, model)
+}
+
+fn points() -> Vec<Vec<u8>> {
+    let mut points: Vec<Vec<u8>> = [
+        &b""[..],
+        b"a",
+        b"aa",
+        b"abc",
+        b"abcd",
+        b"abf",
+        b"b",
+        b"bb",
+        b"bcc",
+        b"bce",
+        b"ca",
+        b"d",
+        b"z",
+    ]
+    .iter()
+    .map(|p| p.to_vec())
+    .collect();
+    for i in 0..62u8 {
+        points.push(vec![b'd', i]);
+        points.push(vec![b'd', i, i]);
+        points.push(vec![b'd', i, i, 0]);
+    }
+    points
+}
+
+#[test]
+fn test_cursor_walks_in_key_order() {
+    let (art, model) = populated();
+
+    let mut cursor = art.cursor();
+    let mut walked = vec![];
+    while let Some(key) = cursor.key() {
+        walked.push((key.to_vec(), *cursor.value().unwrap()));
+        cursor.next();
+    }
+    assert_eq!(walked, model.clone().into_iter().collect::<Vec<_>>());
+    assert!(!cursor.next());
+    assert!(!cursor.prev());
+
+    let (last, _) = model.iter().next_back().unwrap();
+    cursor.seek(last);
+    let mut walked = vec![];
+    while let Some(key) = cursor.key() {
+        walked.push(key.to_vec());
+        cursor.prev();
+    }
+    walked.reverse();
+    assert_eq!(walked, model.keys().cloned().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_cursor_seek() {
+    let (art, model) = populated();
+    let mut cursor = art.cursor();
+
+    // seek back and forth, so that every seek starts from a
+    // different position
+    let points = points();
+    for a in &points {
+        for b in &points {
+            for point in &[a, b] {
+                let expected = model.range::<Vec<u8>, _>(*point..).next();
+                assert_eq!(cursor.seek(point), expected.is_some());
+                assert_eq!(cursor.key(), expected.map(|(k, _)| &k[..]));
+                assert_eq!(cursor.value(), expected.map(|(_, v)| v));
+            }
+
+            let before = model.range::<Vec<u8>, _>(..b).next_back();
+            if cursor.key().is_some() {
+                cursor.prev();
+                assert_eq!(cursor.key(), before.map(|(k, _)| &k[..]));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cursor_merge_join() {
+    let mut left = cart::Art::default();
+    let mut right = cart::Art::default();
+    for i in 0..1000u32 {
+        left.set(format!("user/{:04}", i * 3).into_bytes(), i);
+        right.set(format!("user/{:04}", i * 5).into_bytes(), i);
+    }
+
+    let mut joined = vec![];
+    let mut cursor = right.cursor();
+    for (key, value) in &left {
+        if cursor.seek(&key) && cursor.key() == Some(&key[..]) {
+            joined.push((*value, *cursor.value().unwrap()));
+        }
+    }
+    assert_eq!(joined.len(), 200);
+    assert!(joined.iter().all(|(l, r)| l * 3 == r * 5));
+
+    // once its buffers have grown, seeking allocates nothing
+    let keys: Vec<Vec<u8>> = (0..1000u32)
+        .map(|i| format!("user/{:04}", i * 7 % 5000).into_bytes())
+        .collect();
+    cursor.seek(b"user/4995");
+    let (_, allocated, _) = count(|| {
+        for key in &keys {
+            cursor.seek(key);
+        }
+    });
+    assert_eq!(allocated, 0);
+
+    let empty: cart::Art<u32> = cart::Art::default();
+    let mut cursor = empty.cursor();
+    assert_eq!(cursor.key(), None);
+    assert!(!cursor.seek(b""));
+    assert!(!cursor.next());
+    assert!(!cursor.prev());
+}
+
+#[test]
+fn test_cursor_mut_rewrites() {
+    let (mut art, model) = populated();
+
+    // a compaction-style pass: drop odd values and move the
+    // keys below "d" under "e", where the pass reaches them
+    // again
+    {
+        let mut cursor = art.cursor_mut();
+        while let Some(key) = cursor.key().map(|k| k.to_vec()) {
+            let value = *cursor.value().unwrap();
+            if value % 2 == 1 {
+                assert_eq!(cursor.remove_current(), Some(value));
+                continue;
+            }
+            if key < b"d".to_vec() {
+                let mut moved = b"e".to_vec();
+                moved.extend_from_slice(&key);
+                cursor.remove_current();
+                assert_eq!(cursor.insert(moved, value), None);
+                continue;
+            }
+            *cursor.value_mut().unwrap() += 100;
+            cursor.next();
+        }
+        assert!(!cursor.next());
+    }
+
+    let mut expected = BTreeMap::new();
+    for (key, value) in model.iter().filter(|(_, v)| *v % 2 == 0) {
+        if key < &b"d".to_vec() {
+            let mut moved = b"e".to_vec();
+            moved.extend_from_slice(key);
+            expected.insert(moved, value + 100);
+        } else {
+            expected.insert(key.clone(), value + 100);
+        }
+    }
+    art.validate();
+    assert_eq!(art.len(), expected.len());
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+}
+
+#[test]
+fn test_cursor_mut_matches_model() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let random_key = |rng: &mut XorShiftRng| -> Vec<u8> {
+        let len = rng.gen_range(0, 5);
+        (0..len).map(|_| rng.gen_range(0, 4)).collect()
+    };
+
+    for round in 0..50 {
+        let mut cursor = art.cursor_mut();
+        for _ in 0..40 {
+            let key = random_key(&mut rng);
+            match rng.gen_range(0, 4) {
+                0 | 1 => {
+                    let value = rng.gen_range(0, 1000);
+                    let current = cursor.key().map(|k| k.to_vec());
+                    assert_eq!(cursor.insert(key.clone(), value), model.insert(key, value));
+                    assert_eq!(cursor.key().map(|k| k.to_vec()), current);
+                }
+                2 => {
+                    cursor.seek(&key);
+                    let removed = cursor.key().map(|k| k.to_vec());
+                    let value = cursor.remove_current();
+                    if let Some(removed) = removed {
+                        assert_eq!(value, model.remove(&removed));
+                        let next = model.range(removed..).next().map(|(k, _)| k.clone());
+                        assert_eq!(cursor.key().map(|k| k.to_vec()), next);
+                    } else {
+                        assert_eq!(value, None);
+                    }
+                }
+                _ => {
+                    cursor.seek(&key);
+                    if round % 2 == 0 {
+                        cursor.prev();
+                    } else {
+                        cursor.next();
+                    }
+                }
+            }
+            if let Some(key) = cursor.key() {
+                assert_eq!(cursor.value(), model.get(key));
+            }
+        }
+        drop(cursor);
+
+        art.validate();
+        assert_eq!(art.len(), model.len());
+        assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.clone()));
+    }
+}
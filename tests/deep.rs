@@ -0,0 +1,75 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+// keys that are each one byte longer than the previous one
+// produce a single chain of nodes as deep as the longest key.
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_deep_chain() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for len in 1..3000 {
+        art.set(pattern(len), len);
+        model.insert(pattern(len), len);
+    }
+
+    for len in 1..3000 {
+        assert_eq!(art.get(&pattern(len)), Some(&len));
+    }
+    assert_eq!(art.get(&pattern(3000)), None);
+    assert_eq!(art.stats().node4, 3000);
+
+    let drained: Vec<(Vec<u8>, usize)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, usize)> = model.into_iter().collect();
+    assert_eq!(drained, expected);
+}
+
+#[test]
+fn test_long_keys() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..64u8 {
+        let mut key = pattern(10_000);
+        key[9_000 + i as usize * 10] = 255;
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+
+    for (k, v) in &model {
+        assert_eq!(art.get(k), Some(v));
+    }
+
+    let drained: Vec<(Vec<u8>, u8)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, u8)> = model.into_iter().collect();
+    assert_eq!(drained, expected);
+}
+
+#[test]
+fn test_deep_chain_on_small_stack() {
+    // descents are loops rather than recursion, so a tree far
+    // deeper than the stack could hold frames for is fine
+    let worker = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| {
+            let mut art = cart::Art::default();
+            for len in 1..2000 {
+                art.set(pattern(len), len);
+            }
+            for len in 1..2000 {
+                assert_eq!(art.get(&pattern(len)), Some(&len));
+            }
+            assert_eq!(art.count_prefix(&pattern(1500)), 500);
+            for len in (1..2000).step_by(2) {
+                assert_eq!(art.remove(&pattern(len)), Some(len));
+            }
+            assert_eq!(art.len(), 999);
+        })
+        .unwrap();
+    worker.join().unwrap();
+}
@@ -0,0 +1,99 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+fn populated() -> (cart::Art<u8>, BTreeMap<Vec<u8>, u8>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for k in 0..20u8 {
+        let key = vec![k; k as usize];
+        art.set(key.clone(), k);
+        model.insert(key, k);
+    }
+
+    (art, model)
+}
+
+#[test]
+fn test_full_drain() {
+    let (mut art, model) = populated();
+
+    let drained: Vec<(Vec<u8>, u8)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, u8)> = model.into_iter().collect();
+    assert_eq!(drained, expected);
+
+    for k in 0..20u8 {
+        assert_eq!(art.get(&vec![k; k as usize]), None);
+    }
+
+    // the tree stays usable after being drained
+    art.set(b"abc".to_vec(), 1);
+    assert_eq!(art.get(b"abc"), Some(&1));
+}
+
+#[test]
+fn test_partial_drain() {
+    let (mut art, model) = populated();
+
+    let drained: Vec<(Vec<u8>, u8)> = art.drain().take(3).collect();
+    let expected: Vec<(Vec<u8>, u8)> = model.into_iter().take(3).collect();
+    assert_eq!(drained, expected);
+
+    for k in 0..20u8 {
+        assert_eq!(art.get(&vec![k; k as usize]), None);
+    }
+    assert_eq!(art.drain().count(), 0);
+}
+
+#[test]
+fn test_drain_drops_remaining_values() {
+    let shared = Arc::new(());
+    let mut art = cart::Art::default();
+
+    for k in 0..20u8 {
+        art.set(vec![k; k as usize], shared.clone());
+    }
+    for k in 0..20u8 {
+        art.set(vec![1, 2, k], shared.clone());
+    }
+    assert_eq!(Arc::strong_count(&shared), 41);
+
+    {
+        let mut drain = art.drain();
+        let first = drain.next();
+        assert!(first.is_some());
+        assert_eq!(Arc::strong_count(&shared), 41);
+    }
+    assert_eq!(Arc::strong_count(&shared), 1);
+
+    art.set(b"abc".to_vec(), shared.clone());
+    assert_eq!(art.drain().count(), 1);
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
+
+#[test]
+fn test_drain_moves_values() {
+    // neither Clone nor Copy
+    #[derive(Debug, PartialEq)]
+    struct Moved(u32);
+
+    let mut art = cart::ArtBuilder::new().intern_prefixes(true).build();
+    for i in 0..100u32 {
+        art.set(format!("key{}", i).into_bytes(), Moved(i));
+    }
+
+    let moved: BTreeMap<Vec<u8>, Moved> = art.drain().collect();
+    assert_eq!(moved.len(), 100);
+    assert_eq!(moved.get(&b"key42"[..]), Some(&Moved(42)));
+    assert!(art.is_empty());
+
+    // the emptied tree keeps interning into a chunk of its own
+    art.set(b"key1".to_vec(), Moved(1));
+    art.set(b"key2".to_vec(), Moved(2));
+    assert_eq!(art.len(), 2);
+    assert_eq!(art.get(b"key42"), None);
+    assert_eq!(moved.get(&b"key1"[..]), Some(&Moved(1)));
+    art.validate();
+}
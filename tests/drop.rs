@@ -0,0 +1,100 @@
+extern crate cart;
+
+use std::sync::Arc;
+
+mod common;
+
+use common::count;
+
+#[test]
+fn test_drop_frees_everything() {
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::Art::default();
+        for i in 0..2000u32 {
+            let key = format!("{}", i * 7919 % 5000).into_bytes();
+            art.set(key, format!("value {}", i));
+        }
+        // a Node256 and Node48s below it
+        for a in 0..=255u8 {
+            for b in 0..20u8 {
+                art.set(vec![b'z', a, b, 0], a.to_string());
+            }
+        }
+        // a deep chain of nodes
+        for len in 0..500 {
+            art.set(vec![b'd'; len], String::new());
+        }
+        art.remove(b"123");
+    });
+
+    assert_eq!(allocated, freed);
+}
+
+#[test]
+fn test_drop_runs_value_destructors() {
+    let shared = Arc::new(());
+
+    {
+        let mut art = cart::Art::default();
+        for k in 0..100u8 {
+            art.set(vec![k; k as usize], shared.clone());
+            art.set(vec![1, 2, k], shared.clone());
+        }
+        assert_eq!(Arc::strong_count(&shared), 201);
+    }
+
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
+
+#[test]
+fn test_map_does_not_double_free() {
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::Art::default();
+        for i in 0..500u32 {
+            art.set(format!("{}", i).into_bytes(), i.to_string());
+        }
+        let mapped = art.map(|_, v| v.len());
+        assert_eq!(mapped.get(b"123"), Some(&3));
+    });
+
+    assert_eq!(allocated, freed);
+}
+
+#[test]
+fn test_drop_frees_derived_trees() {
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::Art::default();
+        for i in 0..1000u32 {
+            art.set(format!("{}", i * 7919 % 5000).into_bytes(), i.to_string());
+        }
+        let copy: cart::Art<String> = art.iter().map(|(k, v)| (k, v.clone())).collect();
+        let upper = art.split_off(b"3");
+        assert_eq!(copy.len(), art.len() + upper.len());
+
+        let mut typed = cart::TypedArt::new();
+        let mut strings = cart::StrArt::new();
+        for i in 0..1000u64 {
+            typed.set(i, i.to_string());
+            strings.set(&i.to_string(), i);
+        }
+    });
+
+    assert_eq!(allocated, freed);
+}
+
+#[test]
+fn test_drop_deep_tree_on_small_stack() {
+    // the nodes are freed from a stack of pointers, so dropping
+    // does not recurse as deep as the tree is
+    let worker = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| {
+            let mut art = cart::Art::default();
+            for len in 0..2000 {
+                art.set(vec![b'd'; len], Arc::new(()));
+            }
+            drop(art);
+        })
+        .unwrap();
+    worker.join().unwrap();
+}
@@ -0,0 +1,66 @@
+extern crate cart;
+
+#[test]
+fn test_empty_key() {
+    let mut art = cart::Art::default();
+    assert_eq!(art.get(b""), None);
+    assert_eq!(art.remove(b""), None);
+
+    assert_eq!(art.set(vec![], 1), None);
+    assert_eq!(art.set(vec![], 2), Some(1));
+    assert_eq!(art.get(b""), Some(&2));
+    assert_eq!(art.len(), 1);
+    art.validate();
+
+    // the root keeps it while keys come and go below it
+    for key in &[&b"a"[..], b"ab", b"b"] {
+        art.set(key.to_vec(), 3);
+    }
+    assert_eq!(art.iter().next(), Some((vec![], &2)));
+    assert_eq!(art.iter_prefix(b"").count(), 4);
+    assert_eq!(art.range(&b""[..]..&b"a"[..]).count(), 1);
+    assert_eq!(art.count_prefix(b""), 4);
+    for key in &[&b"a"[..], b"ab", b"b"] {
+        art.remove(key);
+        art.validate();
+    }
+    assert_eq!(art.get(b""), Some(&2));
+
+    assert_eq!(art.remove(b""), Some(2));
+    assert_eq!(art.get(b""), None);
+    assert!(art.is_empty());
+    art.validate();
+}
+
+#[test]
+fn test_empty_trailing_segments() {
+    // composite keys of a table name and a row key joined by a
+    // zero byte, where the row key may be empty
+    let key = |table: &str, row: &str| {
+        let mut key = table.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(row.as_bytes());
+        key
+    };
+
+    let mut art = cart::Art::default();
+    art.set(key("users", ""), 0);
+    art.set(key("users", "alice"), 1);
+    art.set(key("users", "a"), 2);
+    art.set(key("user", ""), 3);
+    art.validate();
+
+    assert_eq!(art.get(&key("users", "")), Some(&0));
+    assert_eq!(art.get(&key("user", "")), Some(&3));
+    let rows: Vec<u32> = art
+        .iter_prefix(&key("users", ""))
+        .map(|(_, v)| *v)
+        .collect();
+    assert_eq!(rows, vec![0, 2, 1]);
+
+    assert_eq!(art.remove(&key("users", "")), Some(0));
+    art.validate();
+    assert_eq!(art.get(&key("users", "a")), Some(&2));
+    assert_eq!(art.get(&key("users", "")), None);
+    assert_eq!(art.len(), 3);
+}
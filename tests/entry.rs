@@ -0,0 +1,120 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+use cart::Entry;
+
+#[test]
+fn test_entry_counters() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let words = "a an and ant a an at and a";
+    for word in words.split(' ') {
+        *art.entry(word.as_bytes().to_vec()).or_insert(0) += 1;
+        *model.entry(word.as_bytes().to_vec()).or_insert(0) += 1;
+    }
+
+    art.validate();
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.into_iter()));
+    assert_eq!(art.get(b"a"), Some(&3));
+}
+
+#[test]
+fn test_entry_and_modify() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+
+    art.entry(b"abc".to_vec())
+        .and_modify(|v| *v += 10)
+        .or_insert(0);
+    art.entry(b"abd".to_vec())
+        .and_modify(|v| *v += 10)
+        .or_insert(5);
+    // ends inside the prefix shared by "abc" and "abd"
+    art.entry(b"a".to_vec())
+        .and_modify(|v| *v += 10)
+        .or_insert_with(|| 7);
+
+    assert_eq!(art.get(b"abc"), Some(&11));
+    assert_eq!(art.get(b"abd"), Some(&5));
+    assert_eq!(art.get(b"a"), Some(&7));
+    art.validate();
+}
+
+#[test]
+fn test_entry_variants() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+
+    // "ab" is a node without a value
+    match art.entry(b"ab".to_vec()) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), b"ab");
+            assert_eq!(*entry.insert(3), 3);
+        }
+        Entry::Occupied(_) => panic!("ab should be vacant"),
+    }
+    assert_eq!(art.get(b"ab"), Some(&3));
+
+    match art.entry(b"abc".to_vec()) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), b"abc");
+            assert_eq!(*entry.get(), 1);
+            assert_eq!(entry.insert(10), 1);
+            assert_eq!(entry.remove(), 10);
+        }
+        Entry::Vacant(_) => panic!("abc should be occupied"),
+    }
+    assert_eq!(art.get(b"abc"), None);
+
+    match art.entry(b"abd".to_vec()) {
+        Entry::Occupied(entry) => *entry.into_mut() += 1,
+        Entry::Vacant(_) => panic!("abd should be occupied"),
+    }
+    assert_eq!(art.get(b"abd"), Some(&3));
+    art.validate();
+
+    let entries: Vec<(Vec<u8>, i32)> = art.iter().map(|(k, v)| (k, *v)).collect();
+    assert_eq!(entries, vec![(b"ab".to_vec(), 3), (b"abd".to_vec(), 3)]);
+}
+
+#[test]
+fn test_entry_remove_prunes() {
+    let mut art = cart::Art::default();
+    for b in 0..20u8 {
+        art.set(vec![b], b);
+    }
+    assert_eq!(art.root_variant(), "Node48");
+
+    for b in 0..20u8 {
+        if let Entry::Occupied(entry) = art.entry(vec![b]) {
+            assert_eq!(entry.remove(), b);
+        }
+    }
+    assert_eq!(art.root_variant(), "Node4");
+    assert_eq!(art.iter().count(), 0);
+}
+
+#[test]
+fn test_try_insert() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+
+    *art.try_insert(b"abc".to_vec(), 2).unwrap() += 10;
+    assert_eq!(art.get(b"abc"), Some(&12));
+
+    let err = art.try_insert(b"abcdef".to_vec(), 3).unwrap_err();
+    assert_eq!(err.key, b"abcdef".to_vec());
+    assert_eq!(err.value, 3);
+    assert_eq!(
+        err.to_string(),
+        "key [97, 98, 99, 100, 101, 102] already holds a value"
+    );
+    assert_eq!(art.get(b"abcdef"), Some(&1));
+
+    assert!(art.try_insert(b"abc".to_vec(), 4).is_err());
+    assert_eq!(art.len(), 2);
+    art.validate();
+}
@@ -0,0 +1,75 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_extract_if() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for i in 0..300usize {
+        art.set(i.to_string().into_bytes(), i);
+        model.insert(i.to_string().into_bytes(), i);
+    }
+
+    let extracted: Vec<(Vec<u8>, usize)> = art.extract_if(|_, v| *v % 3 == 0).collect();
+    let (expected, kept): (Vec<_>, Vec<_>) = model.into_iter().partition(|(_, v)| *v % 3 == 0);
+    assert_eq!(extracted, expected);
+
+    art.validate();
+    assert_eq!(art.len(), kept.len());
+    assert!(art.drain().eq(kept));
+}
+
+#[test]
+fn test_extract_if_is_lazy() {
+    let mut art = cart::Art::default();
+    for (i, key) in [&b"b"[..], b"ab", b"a", b"abc", b"c"].iter().enumerate() {
+        art.set(key.to_vec(), i);
+    }
+
+    let mut seen = vec![];
+    {
+        let mut extract = art.extract_if(|k, v| {
+            seen.push(k.to_vec());
+            *v += 1000;
+            true
+        });
+        assert_eq!(extract.next(), Some((b"a".to_vec(), 1002)));
+        assert_eq!(extract.next(), Some((b"ab".to_vec(), 1001)));
+    }
+
+    // only the first two entries were looked at and removed
+    assert_eq!(seen, vec![b"a".to_vec(), b"ab".to_vec()]);
+    assert_eq!(art.len(), 3);
+    assert_eq!(art.get(b"abc"), Some(&3));
+    assert_eq!(art.get(b"b"), Some(&0));
+    assert_eq!(art.get(b"c"), Some(&4));
+    art.validate();
+}
+
+#[test]
+fn test_extract_if_keeps_modifications() {
+    let mut art = cart::Art::default();
+    for (i, key) in [&b"abc"[..], b"ab", b"abcd", b"b", b""].iter().enumerate() {
+        art.set(key.to_vec(), i);
+    }
+
+    // the predicate may update the entries it keeps
+    let extracted: Vec<Vec<u8>> = art
+        .extract_if(|k, v| {
+            *v += 10;
+            k.len() % 2 == 0
+        })
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(
+        extracted,
+        vec![b"".to_vec(), b"ab".to_vec(), b"abcd".to_vec()]
+    );
+
+    art.validate();
+    assert_eq!(
+        art.drain().collect::<Vec<_>>(),
+        vec![(b"abc".to_vec(), 10), (b"b".to_vec(), 13)]
+    );
+}
@@ -0,0 +1,105 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_take_smallest_and_largest() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    // shorter keys first, so that none of them ends inside the
+    // prefix of a node created for a longer one.
+    for len in 0..4 {
+        for i in 0..250u32 {
+            let x = i.wrapping_mul(2_654_435_761);
+            let key = x.to_be_bytes()[..len].to_vec();
+            art.set(key.clone(), i);
+            model.insert(key, i);
+        }
+    }
+
+    for k in &[0, 1, 3, 10, 500] {
+        let smallest: Vec<(Vec<u8>, &u32)> =
+            model.iter().take(*k).map(|(k, v)| (k.clone(), v)).collect();
+        assert_eq!(art.take_smallest(*k), smallest);
+
+        let largest: Vec<(Vec<u8>, &u32)> = model
+            .iter()
+            .rev()
+            .take(*k)
+            .map(|(k, v)| (k.clone(), v))
+            .collect();
+        assert_eq!(art.take_largest(*k), largest);
+    }
+
+    // asking for more than there is returns everything
+    assert_eq!(art.take_smallest(model.len() + 10).len(), model.len());
+    assert_eq!(art.take_largest(model.len() + 10).len(), model.len());
+}
+
+#[test]
+fn test_take_smallest_three() {
+    let mut art = cart::Art::default();
+    for key in &[
+        &b"delta"[..],
+        b"al",
+        b"alpha",
+        b"charlie",
+        b"bravo",
+        b"echo",
+    ] {
+        art.set(key.to_vec(), key.len());
+    }
+
+    assert_eq!(
+        art.take_smallest(3),
+        vec![
+            (b"al".to_vec(), &2),
+            (b"alpha".to_vec(), &5),
+            (b"bravo".to_vec(), &5)
+        ]
+    );
+    assert_eq!(
+        art.take_largest(2),
+        vec![(b"echo".to_vec(), &4), (b"delta".to_vec(), &5)]
+    );
+    assert!(cart::Art::<u8>::default().take_largest(3).is_empty());
+}
+
+#[test]
+fn test_first_and_last_key_value() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    assert_eq!(art.first_key_value(), None);
+    assert_eq!(art.last_key_value(), None);
+
+    // the empty key lives at the root and is both extremes
+    art.set(vec![], 0);
+    assert_eq!(art.first_key_value(), Some((vec![], &0)));
+    assert_eq!(art.last_key_value(), Some((vec![], &0)));
+    art.remove(b"");
+
+    // enough distinct bytes per level for every node variant
+    for i in 0..2000u32 {
+        let x = i.wrapping_mul(2_654_435_761);
+        let key = x.to_be_bytes()[..1 + i as usize % 4].to_vec();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+
+        if i % 97 == 0 {
+            let first = model.iter().next().map(|(k, v)| (k.clone(), v));
+            let last = model.iter().next_back().map(|(k, v)| (k.clone(), v));
+            assert_eq!(art.first_key_value(), first);
+            assert_eq!(art.last_key_value(), last);
+        }
+    }
+
+    while let Some((key, _)) = art.first_key_value() {
+        assert_eq!(art.remove(&key), model.remove(&key));
+        let first = model.iter().next().map(|(k, v)| (k.clone(), v));
+        let last = model.iter().next_back().map(|(k, v)| (k.clone(), v));
+        assert_eq!(art.first_key_value(), first);
+        assert_eq!(art.last_key_value(), last);
+    }
+    assert!(model.is_empty());
+}
@@ -0,0 +1,36 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_to_flat() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for len in 0..5 {
+        for i in 0..300u32 {
+            let x = i.wrapping_mul(2_654_435_761);
+            let key = x.to_be_bytes()[..len].to_vec();
+            art.set(key.clone(), i);
+            model.insert(key, i);
+        }
+    }
+
+    let (keys, entries) = art.to_flat();
+    assert_eq!(keys.len(), model.keys().map(|k| k.len()).sum::<usize>());
+
+    let rebuilt: Vec<(Vec<u8>, u32)> = entries
+        .into_iter()
+        .map(|(range, v)| (keys[range].to_vec(), *v))
+        .collect();
+    let expected: Vec<(Vec<u8>, u32)> = model.into_iter().collect();
+    assert_eq!(rebuilt, expected);
+}
+
+#[test]
+fn test_to_flat_empty() {
+    let art: cart::Art<u8> = cart::Art::default();
+    let (keys, entries) = art.to_flat();
+    assert!(keys.is_empty());
+    assert!(entries.is_empty());
+}
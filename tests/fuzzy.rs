@@ -0,0 +1,69 @@
+extern crate cart;
+
+// the textbook edit distance, to check the tree against
+fn distance(a: &[u8], b: &[u8]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut next = vec![i + 1];
+        for (j, y) in b.iter().enumerate() {
+            let cost = row[j] + (x != y) as usize;
+            next.push(cost.min(row[j + 1] + 1).min(next[j] + 1));
+        }
+        row = next;
+    }
+    row[b.len()]
+}
+
+#[test]
+fn test_fuzzy_search_matches_brute_force() {
+    let words: &[&str] = &[
+        "", "a", "an", "and", "ant", "anti", "antler", "band", "bank", "banner", "can", "cane",
+        "candle", "cant", "canto", "hello", "help", "helm", "yellow",
+    ];
+    let mut art = cart::Art::default();
+    for (i, word) in words.iter().enumerate() {
+        art.set(word.as_bytes().to_vec(), i);
+    }
+
+    for query in &["", "an", "cant", "bnak", "helo", "candel", "xyz"] {
+        for max_edits in 0..4 {
+            let found: Vec<(Vec<u8>, usize, usize)> = art
+                .fuzzy_search(query.as_bytes(), max_edits)
+                .into_iter()
+                .map(|(k, v, d)| (k, *v, d))
+                .collect();
+
+            let mut expected: Vec<(Vec<u8>, usize, usize)> = words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    (
+                        w.as_bytes().to_vec(),
+                        i,
+                        distance(w.as_bytes(), query.as_bytes()),
+                    )
+                })
+                .filter(|(_, _, d)| *d <= max_edits)
+                .collect();
+            expected.sort();
+
+            assert_eq!(found, expected, "{:?} within {}", query, max_edits);
+        }
+    }
+}
+
+#[test]
+fn test_fuzzy_search_typos() {
+    let mut art = cart::Art::default();
+    for word in &["receive", "recipe", "recite", "deceive"] {
+        art.set(word.as_bytes().to_vec(), ());
+    }
+
+    let found: Vec<(Vec<u8>, usize)> = art
+        .fuzzy_search(b"receve", 1)
+        .into_iter()
+        .map(|(k, _, d)| (k, d))
+        .collect();
+    assert_eq!(found, vec![(b"receive".to_vec(), 1)]);
+    assert!(art.fuzzy_search(b"zzz", 1).is_empty());
+}
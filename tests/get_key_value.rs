@@ -0,0 +1,30 @@
+extern crate cart;
+
+#[test]
+fn test_get_key_value() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+    art.set(b"abcxyz".to_vec(), 2);
+    art.set(b"abc".to_vec(), 3);
+
+    assert_eq!(art.get_key_value(b"abc"), Some((&b"abc"[..], &3)));
+    assert_eq!(art.get_key_value(b"abcxyz"), Some((&b"abcxyz"[..], &2)));
+    assert_eq!(art.get_key_value(b"ab"), None);
+    assert_eq!(art.get_key_value(b"abcd"), None);
+    assert_eq!(art.get_key_value(b""), None);
+}
+
+#[test]
+fn test_get_key_value_outlives_key() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+
+    // the value is borrowed from the tree, not from the key
+    let value = {
+        let key = b"abc".to_vec();
+        let (k, v) = art.get_key_value(&key).unwrap();
+        assert_eq!(k, &key[..]);
+        v
+    };
+    assert_eq!(value, &1);
+}
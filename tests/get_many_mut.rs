@@ -0,0 +1,36 @@
+extern crate cart;
+
+#[test]
+fn test_get_many_mut() {
+    let mut balances = cart::Art::default();
+    balances.set(b"alice".to_vec(), 100);
+    balances.set(b"bob".to_vec(), 20);
+    balances.set(b"bobby".to_vec(), 5);
+
+    {
+        let [from, to] = balances.get_many_mut([b"alice", b"bob"]).unwrap();
+        *from -= 30;
+        *to += 30;
+    }
+    assert_eq!(balances.get(b"alice"), Some(&70));
+    assert_eq!(balances.get(b"bob"), Some(&50));
+
+    // a key and one it is a prefix of, in both orders
+    {
+        let [a, b] = balances.get_many_mut([b"bobby", b"bob"]).unwrap();
+        std::mem::swap(a, b);
+    }
+    assert_eq!(balances.get(b"bob"), Some(&5));
+    assert_eq!(balances.get(b"bobby"), Some(&50));
+    {
+        let [a, b] = balances.get_many_mut([b"bob", b"bobby"]).unwrap();
+        std::mem::swap(a, b);
+    }
+    assert_eq!(balances.get(b"bob"), Some(&50));
+    assert_eq!(balances.get(b"bobby"), Some(&5));
+
+    assert!(balances.get_many_mut([b"alice", b"carol"]).is_none());
+    assert!(balances.get_many_mut([b"bob", b"bob"]).is_none());
+    assert!(balances.get_many_mut::<0>([]).is_some());
+    assert_eq!(balances.get_many_mut([b"alice"]), Some([&mut 70]));
+}
@@ -0,0 +1,40 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_get_mut_counters() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let words = "the quick brown fox jumps over the lazy dog the end";
+    for word in words.split(' ') {
+        let key = word.as_bytes();
+        match art.get_mut(key) {
+            Some(count) => *count += 1,
+            None => {
+                art.set(key.to_vec(), 1);
+            }
+        }
+        *model.entry(key.to_vec()).or_insert(0) += 1;
+    }
+
+    assert_eq!(art.get(b"the"), Some(&3));
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.into_iter()));
+}
+
+#[test]
+fn test_get_mut_missing() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+
+    assert_eq!(art.get_mut(b"ab"), None);
+    assert_eq!(art.get_mut(b"abcd"), None);
+    assert_eq!(art.get_mut(b"x"), None);
+    assert_eq!(art.get_mut(b""), None);
+
+    *art.get_mut(b"abd").unwrap() = 20;
+    assert_eq!(art.get(b"abd"), Some(&20));
+    assert_eq!(art.get(b"abc"), Some(&1));
+}
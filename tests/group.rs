@@ -0,0 +1,66 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+type Group<'a> = (Vec<u8>, Vec<(Vec<u8>, &'a u32)>);
+
+#[test]
+fn test_group_by_prefix() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..500u32 {
+        let x = i.wrapping_mul(2_654_435_761);
+        let key = vec![
+            (x >> 24) as u8 % 5,
+            (x >> 16) as u8 % 7,
+            (x >> 8) as u8,
+            x as u8,
+        ];
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+
+    // group the sorted model entries by hand
+    let mut expected: Vec<Group> = vec![];
+    for (k, v) in &model {
+        let group = k[..2].to_vec();
+        match expected.last_mut() {
+            Some((last, entries)) if *last == group => entries.push((k.clone(), v)),
+            _ => expected.push((group, vec![(k.clone(), v)])),
+        }
+    }
+
+    let groups: Vec<_> = art.group_by_prefix(2).collect();
+    assert_eq!(groups, expected);
+
+    // everything is a single group without a prefix
+    let groups: Vec<_> = art.group_by_prefix(0).collect();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].0, Vec::<u8>::new());
+    assert_eq!(groups[0].1.len(), model.len());
+}
+
+#[test]
+fn test_group_by_prefix_short_keys() {
+    let mut art = cart::Art::default();
+    art.set(b"a".to_vec(), 1);
+    art.set(b"abc".to_vec(), 2);
+    art.set(b"abd".to_vec(), 3);
+    art.set(b"ac".to_vec(), 4);
+    art.set(b"b".to_vec(), 5);
+
+    let groups: Vec<_> = art.group_by_prefix(2).collect();
+    assert_eq!(
+        groups,
+        vec![
+            (b"a".to_vec(), vec![(b"a".to_vec(), &1)]),
+            (
+                b"ab".to_vec(),
+                vec![(b"abc".to_vec(), &2), (b"abd".to_vec(), &3)]
+            ),
+            (b"ac".to_vec(), vec![(b"ac".to_vec(), &4)]),
+            (b"b".to_vec(), vec![(b"b".to_vec(), &5)]),
+        ]
+    );
+}
@@ -0,0 +1,85 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+use cart::{Art, ArtBuilder};
+
+mod common;
+
+use common::allocations;
+
+// short keys that share little beyond their first byte, so
+// most nodes end up with a short prefix of their own.
+fn keys() -> Vec<Vec<u8>> {
+    (0..2000u32)
+        .map(|i| {
+            let x = i.wrapping_mul(2_654_435_761);
+            vec![
+                (i % 7) as u8,
+                (x >> 24) as u8,
+                (x >> 16) as u8,
+                (x >> 8) as u8,
+                x as u8,
+            ]
+        })
+        .collect()
+}
+
+fn populate(art: &mut Art<u32>) -> BTreeMap<Vec<u8>, u32> {
+    let mut model = BTreeMap::new();
+    for (i, key) in keys().into_iter().enumerate() {
+        art.set(key.clone(), i as u32);
+        model.insert(key, i as u32);
+    }
+    model
+}
+
+#[test]
+fn test_interned_matches_model() {
+    let mut art = ArtBuilder::new().intern_prefixes(true).build();
+    let model = populate(&mut art);
+
+    for (k, v) in &model {
+        assert_eq!(art.get(k), Some(v));
+    }
+
+    art.compact();
+    for (k, v) in &model {
+        assert_eq!(art.get(k), Some(v));
+    }
+
+    let drained: Vec<(Vec<u8>, u32)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, u32)> = model.into_iter().collect();
+    assert_eq!(drained, expected);
+}
+
+#[test]
+fn test_interned_allocations() {
+    let start = allocations();
+    let mut owned = Art::default();
+    populate(&mut owned);
+    let owned_allocations = allocations() - start;
+
+    let start = allocations();
+    let mut interned = ArtBuilder::new().intern_prefixes(true).build();
+    populate(&mut interned);
+    let interned_allocations = allocations() - start;
+
+    assert_eq!(owned.stats(), interned.stats());
+    assert!(
+        interned_allocations < owned_allocations,
+        "interned: {} owned: {}",
+        interned_allocations,
+        owned_allocations
+    );
+
+    // compaction drops the bytes of prefixes that were split
+    let before = interned.memory_bytes();
+    interned.compact();
+    assert!(interned.memory_bytes() <= before);
+
+    // compacting a tree without interning leaves it alone
+    let before = owned.memory_bytes();
+    owned.compact();
+    assert_eq!(owned.memory_bytes(), before);
+}
@@ -0,0 +1,57 @@
+extern crate cart;
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use common::count;
+
+#[test]
+fn test_into_iter() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for i in 0..300u32 {
+        let key = format!("item/{}", i).into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+
+    let mut keys = vec![];
+    for (key, value) in &art {
+        assert_eq!(model.get(&key), Some(value));
+        keys.push(key);
+    }
+    assert!(keys.iter().eq(model.keys()));
+
+    for (_, value) in &mut art {
+        *value += 1;
+    }
+
+    let owned: Vec<(Vec<u8>, u32)> = art.into_iter().collect();
+    let expected: Vec<(Vec<u8>, u32)> = model.into_iter().map(|(k, v)| (k, v + 1)).collect();
+    assert_eq!(owned, expected);
+
+    // a round trip through the consuming iterator
+    let art = cart::Art::bulk_load(expected.clone());
+    assert!(art.into_iter().eq(expected));
+}
+
+#[test]
+fn test_into_iter_frees_everything() {
+    let shared = Arc::new(());
+
+    let (_, allocated, freed) = count(|| {
+        let mut art = cart::ArtBuilder::new().intern_prefixes(true).build();
+        for i in 0..100u32 {
+            art.set(format!("key{}", i).into_bytes(), shared.clone());
+        }
+
+        let mut iter = art.into_iter();
+        let (_, first) = iter.next().unwrap();
+        assert_eq!(Arc::strong_count(&first), 101);
+        // the rest is dropped along with the iterator
+    });
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert_eq!(allocated, freed);
+}
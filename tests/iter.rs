@@ -0,0 +1,102 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_iter_order() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    // every node variant, keys that are prefixes of each other
+    // and keys ending inside node prefixes
+    for i in 0..=255u8 {
+        for key in &[vec![i], vec![i, 0, 0, 0], vec![i, 0], vec![i, 255, i]] {
+            art.set(key.clone(), i);
+            model.insert(key.clone(), i);
+        }
+    }
+    art.set(vec![], 0);
+    model.insert(vec![], 0);
+
+    let mut iter = art.iter();
+    assert!(iter.by_ref().map(|(k, v)| (k, *v)).eq(model));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+}
+
+fn populated() -> (cart::Art<usize>, BTreeMap<Vec<u8>, usize>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    // squares, whose lengths and shared prefixes vary a lot
+    for i in 0..500usize {
+        let key = (i * i).to_string().into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+    art.set(vec![], 1000);
+    model.insert(vec![], 1000);
+    (art, model)
+}
+
+#[test]
+fn test_iter_rev() {
+    let (art, model) = populated();
+
+    assert!(art
+        .iter()
+        .rev()
+        .map(|(k, v)| (k, *v))
+        .eq(model.clone().into_iter().rev()));
+
+    for prefix in &[&b""[..], b"1", b"12", b"123", b"999", b"x"] {
+        let expected: Vec<Vec<u8>> = model
+            .keys()
+            .rev()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+        let reversed: Vec<Vec<u8>> = art.iter_prefix(prefix).rev().map(|(k, _)| k).collect();
+        assert_eq!(reversed, expected, "{:?}", prefix);
+    }
+}
+
+#[test]
+fn test_iter_from_both_ends() {
+    let (art, model) = populated();
+
+    // every interleaving pattern meets in the middle without
+    // yielding anything twice
+    for pattern in 1..8u32 {
+        let mut iter = art.iter();
+        let mut front = vec![];
+        let mut back = vec![];
+        for step in 0.. {
+            let next = if (pattern >> (step % 3)) & 1 == 1 {
+                iter.next().map(|e| front.push(e.0))
+            } else {
+                iter.next_back().map(|e| back.push(e.0))
+            };
+            if next.is_none() {
+                break;
+            }
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        back.reverse();
+        front.extend(back);
+        assert!(front.iter().eq(model.keys()), "pattern {}", pattern);
+    }
+
+    let mut range = art.range(&b"2"[..]..&b"5"[..]);
+    let mut expected = model.range(b"2".to_vec()..b"5".to_vec());
+    assert_eq!(
+        range.next().map(|e| e.0).as_ref(),
+        expected.next().map(|e| e.0)
+    );
+    assert_eq!(
+        range.next_back().map(|e| e.0).as_ref(),
+        expected.next_back().map(|e| e.0)
+    );
+    assert_eq!(range.count(), expected.count());
+}
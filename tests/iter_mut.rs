@@ -0,0 +1,75 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_iter_mut_doubles_values() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..300u32 {
+        let key = format!("{}", i * 7919 % 1000).into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+    art.set(vec![], 1000);
+    model.insert(vec![], 1000);
+
+    let keys: Vec<Vec<u8>> = art
+        .iter_mut()
+        .map(|(key, value)| {
+            *value *= 2;
+            key
+        })
+        .collect();
+    assert_eq!(keys, model.keys().cloned().collect::<Vec<_>>());
+
+    for value in model.values_mut() {
+        *value *= 2;
+    }
+    assert_eq!(art.drain().collect::<BTreeMap<_, _>>(), model);
+}
+
+#[test]
+fn test_iter_mut_references_are_unique() {
+    let mut art = cart::Art::default();
+    for key in &[&b"a"[..], b"ab", b"abc", b"abd", b"b"] {
+        art.set(key.to_vec(), 0);
+    }
+
+    // all references are alive at once
+    let mut values: Vec<&mut i32> = art.iter_mut().map(|(_, value)| value).collect();
+    for (i, value) in values.iter_mut().enumerate() {
+        **value = i as i32;
+    }
+
+    let values: Vec<i32> = art.drain().map(|(_, v)| v).collect();
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_iter_mut_empty() {
+    let mut art: cart::Art<u8> = cart::Art::default();
+    assert_eq!(art.iter_mut().count(), 0);
+}
+
+#[test]
+fn test_iter_mut_bumps_counters() {
+    let mut art = cart::Art::default();
+    for i in 0..=255u8 {
+        art.set(vec![i], 0u32);
+        art.set(vec![i, i], 0);
+        art.set(vec![i, 0, 0, i], 0);
+    }
+
+    for round in 1..4 {
+        let mut iter = art.iter_mut();
+        for (_, counter) in iter.by_ref() {
+            *counter += 1;
+        }
+        assert!(iter.next().is_none());
+
+        assert!(art.iter().all(|(_, counter)| *counter == round));
+    }
+    assert_eq!(art.get(&[7, 0, 0, 7]), Some(&3));
+}
@@ -0,0 +1,65 @@
+extern crate cart;
+
+#[test]
+fn test_typed_keys() {
+    let mut art = cart::Art::default();
+    for id in &[300u64, 2, 70_000, 1 << 40, 0] {
+        art.set(*id, *id);
+    }
+    assert_eq!(art.get(&70_000u64), Some(&70_000));
+    assert_eq!(art.get(&3u64), None);
+
+    // big-endian keys iterate in numeric order
+    let ids: Vec<u64> = art.values().cloned().collect();
+    assert_eq!(ids, vec![0, 2, 300, 70_000, 1 << 40]);
+
+    let mut names = cart::Art::default();
+    names.set("bob", 1);
+    names.set(String::from("alice"), 2);
+    names.set(&b"carol"[..], 3);
+    names.set(b"dave".to_vec(), 4);
+    assert_eq!(names.get("alice"), Some(&2));
+    assert_eq!(names.get(&String::from("bob")), Some(&1));
+    assert_eq!(names.get(b"carol"), Some(&3));
+    assert_eq!(names.get(&b"dave"[..]), Some(&4));
+    assert_eq!(names.get(""), None);
+}
+
+#[test]
+fn test_key_encoding_preserves_order() {
+    use cart::Key;
+
+    let mut numbers = [0u32, 1, 255, 256, 65_535, 65_536, u32::MAX];
+    numbers.reverse();
+    let mut encoded: Vec<Vec<u8>> = numbers.iter().map(|n| n.to_bytes().into_owned()).collect();
+    encoded.sort();
+    numbers.sort();
+    let decoded: Vec<Vec<u8>> = numbers.iter().map(|n| n.to_bytes().into_owned()).collect();
+    assert_eq!(encoded, decoded);
+
+    assert_eq!(&*"ab".to_bytes(), b"ab");
+    assert_eq!(&*0x0102u16.to_bytes(), &[1, 2]);
+}
+
+#[test]
+fn test_get_outlives_key() {
+    let mut art = cart::Art::default();
+    for i in 0..100u32 {
+        art.set(format!("user/{}", i), i);
+    }
+
+    // the values are borrowed from the tree, so the keys can be
+    // built on the fly
+    let found: Vec<&u32> = (0..200u32)
+        .filter_map(|i| art.get(&format!("user/{}", i)))
+        .collect();
+    assert_eq!(found.len(), 100);
+    assert_eq!(*found[99], 99);
+
+    let value = {
+        let key = 7u64;
+        art.set(key, 700);
+        art.get(&key)
+    };
+    assert_eq!(value, Some(&700));
+}
@@ -0,0 +1,81 @@
+extern crate cart;
+extern crate rand;
+
+use cart::key_encoding::*;
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+#[test]
+fn test_signed_encodings() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut values: Vec<i64> = (0..1000).map(|_| rng.gen()).collect();
+    values.extend(&[i64::MIN, -1, 0, 1, i64::MAX]);
+
+    for &a in &values {
+        assert_eq!(decode_i64(encode_i64(a)), a);
+        for &b in &values[..50] {
+            assert_eq!(encode_i64(a).cmp(&encode_i64(b)), a.cmp(&b));
+        }
+    }
+
+    for a in i8::MIN..=i8::MAX {
+        assert_eq!(decode_i8(encode_i8(a)), a);
+        assert_eq!(encode_i8(a) < encode_i8(a.saturating_add(1)), a < i8::MAX);
+    }
+    assert!(encode_i16(-300) < encode_i16(300));
+    assert!(encode_i32(i32::MIN) < encode_i32(-1));
+    assert_eq!(decode_i128(encode_i128(i128::MIN + 5)), i128::MIN + 5);
+}
+
+#[test]
+fn test_float_encodings() {
+    let values = [
+        -f64::NAN,
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.5,
+        -f64::MIN_POSITIVE,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        1.5,
+        f64::MAX,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+    for (i, a) in values.iter().enumerate() {
+        assert_eq!(decode_f64(encode_f64(*a)).to_bits(), a.to_bits());
+        for b in &values[i + 1..] {
+            assert!(encode_f64(*a) < encode_f64(*b), "{} {}", a, b);
+        }
+    }
+
+    assert!(encode_f32(-2.0) < encode_f32(-1.0));
+    assert!(encode_f32(1.0) < encode_f32(2.0));
+    assert_eq!(decode_f32(encode_f32(-0.25)), -0.25);
+}
+
+#[test]
+fn test_numeric_range_scan() {
+    let mut art = cart::Art::default();
+    for i in -50i64..50 {
+        art.set(i * 1000, i);
+    }
+
+    let (start, end) = (encode_i64(-3500), encode_i64(2000));
+    let found: Vec<i64> = art.range(&start[..]..&end[..]).map(|(_, v)| *v).collect();
+    assert_eq!(found, (-3..2).collect::<Vec<_>>());
+
+    let mut temperatures = cart::Art::default();
+    for t in &[3.5f64, -10.25, 0.0, -0.5, 21.0] {
+        temperatures.set(*t, ());
+    }
+    let sorted: Vec<f64> = temperatures
+        .keys()
+        .map(|k| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&k);
+            decode_f64(bytes)
+        })
+        .collect();
+    assert_eq!(sorted, vec![-10.25, -0.5, 0.0, 3.5, 21.0]);
+}
@@ -0,0 +1,56 @@
+extern crate cart;
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::count;
+
+// keys under a long shared prefix, which `keys` has to rebuild for
+// every entry, plus the empty key stored at the root
+fn long_prefixed() -> (cart::Art<u32>, BTreeMap<Vec<u8>, u32>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for i in 0..300u32 {
+        let key = format!("a long shared key prefix {}", i).into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+    art.set(vec![], 1000);
+    model.insert(vec![], 1000);
+    (art, model)
+}
+
+#[test]
+fn test_keys_and_values() {
+    let (mut art, mut model) = long_prefixed();
+
+    assert!(art.keys().eq(model.keys().cloned()));
+    assert!(art.values().eq(model.values()));
+
+    for value in art.values_mut() {
+        *value *= 2;
+    }
+    for value in model.values_mut() {
+        *value *= 2;
+    }
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model));
+
+    let empty: cart::Art<u32> = cart::Art::default();
+    assert_eq!(empty.keys().count(), 0);
+    assert_eq!(empty.values().count(), 0);
+}
+
+#[test]
+fn test_values_does_not_build_keys() {
+    let (art, _) = long_prefixed();
+
+    let (sum, _, _) = count(|| art.values().sum::<u32>());
+    assert_eq!(sum, (0..300).sum::<u32>() + 1000);
+
+    // only the stack and the children of each visited node are
+    // allocated, no keys
+    let (_, with_keys, _) = count(|| art.iter().count());
+    let (_, without_keys, _) = count(|| art.values().count());
+    assert!(with_keys >= without_keys + 301);
+}
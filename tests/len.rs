@@ -0,0 +1,67 @@
+extern crate cart;
+
+use cart::Entry;
+
+#[test]
+fn test_len_follows_every_operation() {
+    let mut art = cart::Art::default();
+    assert!(art.is_empty());
+
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+    art.set(b"ab".to_vec(), 3);
+    assert_eq!(art.len(), 3);
+
+    // overwrites
+    art.set(b"abc".to_vec(), 4);
+    art.update(b"abd".to_vec(), |v| v.map(|v| v + 1));
+    assert_eq!(art.len(), 3);
+
+    art.update(b"x".to_vec(), |_| Some(5));
+    art.update(b"y".to_vec(), |_| None);
+    assert_eq!(art.len(), 4);
+    art.update(b"x".to_vec(), |_| None);
+    assert_eq!(art.len(), 3);
+
+    assert_eq!(art.remove(b"ab"), Some(3));
+    assert_eq!(art.remove(b"ab"), None);
+    assert_eq!(art.remove_if(b"abc", |_| false), None);
+    assert_eq!(art.len(), 2);
+
+    *art.entry(b"e".to_vec()).or_insert(0) += 1;
+    *art.entry(b"e".to_vec()).or_insert(0) += 1;
+    assert_eq!(art.len(), 3);
+    if let Entry::Occupied(entry) = art.entry(b"e".to_vec()) {
+        assert_eq!(entry.remove(), 2);
+    }
+    assert_eq!(art.len(), 2);
+
+    assert_eq!(
+        art.remove_many(&[b"abc", b"abd", b"abc"]),
+        vec![Some(4), Some(3), None]
+    );
+    assert!(art.is_empty());
+
+    art.set(b"a".to_vec(), 1);
+    art.set(b"b".to_vec(), 2);
+    let art = art.map(|_, v| v * 2);
+    assert_eq!(art.len(), 2);
+}
+
+#[test]
+fn test_len_after_drain_and_bulk_load() {
+    let mut art = cart::Art::bulk_load(vec![
+        (b"a".to_vec(), 1),
+        (b"b".to_vec(), 2),
+        (b"b".to_vec(), 3),
+        (b"bc".to_vec(), 4),
+    ]);
+    assert_eq!(art.len(), 3);
+
+    {
+        let mut drain = art.drain();
+        drain.next();
+    }
+    assert_eq!(art.len(), 0);
+    assert!(art.is_empty());
+}
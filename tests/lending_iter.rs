@@ -0,0 +1,49 @@
+extern crate cart;
+
+mod common;
+
+use common::count;
+
+#[test]
+fn test_lending_iter_matches_iter() {
+    let mut art = cart::Art::default();
+    for i in 0..5000u32 {
+        art.set(format!("k{}", i * 7919 % 10000).into_bytes(), i);
+    }
+    art.set(vec![], 5000);
+    art.set(b"k".to_vec(), 5001);
+
+    let mut lent = vec![];
+    let mut entries = art.lending_iter();
+    while let Some((key, value)) = entries.next() {
+        lent.push((key.to_vec(), *value));
+    }
+    assert!(entries.next().is_none());
+
+    let expected: Vec<(Vec<u8>, u32)> = art.iter().map(|(k, v)| (k, *v)).collect();
+    assert_eq!(lent, expected);
+
+    let empty: cart::Art<u32> = cart::Art::default();
+    assert!(empty.lending_iter().next().is_none());
+}
+
+#[test]
+fn test_lending_iter_allocates_per_depth_not_per_key() {
+    let mut art = cart::Art::default();
+    for i in 0..20_000u32 {
+        art.set(format!("a fairly long key {:08}", i).into_bytes(), i);
+    }
+
+    let (sum, allocated, freed) = count(|| {
+        let mut sum = 0u64;
+        let mut entries = art.lending_iter();
+        while let Some((key, value)) = entries.next() {
+            sum += key.len() as u64 + *value as u64;
+        }
+        sum
+    });
+    assert_eq!(sum, 20_000 * 26 + (0..20_000u64).sum::<u64>());
+    // only the key buffer and the path grow, a few times each
+    assert!(allocated < 20, "{} allocations", allocated);
+    assert_eq!(allocated, freed);
+}
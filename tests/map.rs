@@ -0,0 +1,55 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_map() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..1000u32 {
+        let x = i.wrapping_mul(2_654_435_761);
+        let key = vec![(x >> 24) as u8 % 9, (x >> 16) as u8, x as u8];
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+    let stats = art.stats();
+
+    let mut seen = vec![];
+    let mut mapped = art.map(|k, v| {
+        seen.push(k.to_vec());
+        v + 1
+    });
+
+    // keys are visited in order, and the layout is unchanged
+    assert_eq!(seen, model.keys().cloned().collect::<Vec<_>>());
+    assert_eq!(mapped.stats(), stats);
+    mapped.validate();
+
+    let expected: Vec<(Vec<u8>, u32)> = model.into_iter().map(|(k, v)| (k, v + 1)).collect();
+    for (k, v) in &expected {
+        assert_eq!(mapped.get(k), Some(v));
+    }
+    assert_eq!(mapped.drain().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_map_changes_type() {
+    let mut art = cart::Art::default();
+    art.set(b"".to_vec(), 0u8);
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+
+    let mut mapped = art.map(|k, v| format!("{}:{}", String::from_utf8_lossy(k), v));
+    mapped.set(b"x".to_vec(), "new".to_string());
+
+    assert_eq!(
+        mapped.drain().collect::<Vec<_>>(),
+        vec![
+            (b"".to_vec(), ":0".to_string()),
+            (b"abc".to_vec(), "abc:1".to_string()),
+            (b"abd".to_vec(), "abd:2".to_string()),
+            (b"x".to_vec(), "new".to_string()),
+        ]
+    );
+}
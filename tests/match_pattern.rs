@@ -0,0 +1,73 @@
+extern crate cart;
+
+// a straightforward recursive matcher to check the tree against
+fn glob(pattern: &[u8], key: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => key.is_empty(),
+        Some((b'*', rest)) => (0..=key.len()).any(|i| glob(rest, &key[i..])),
+        Some((b'?', rest)) => !key.is_empty() && glob(rest, &key[1..]),
+        Some((p, rest)) => key.first() == Some(p) && glob(rest, &key[1..]),
+    }
+}
+
+#[test]
+fn test_match_pattern_matches_brute_force() {
+    let keys: Vec<Vec<u8>> = [
+        "",
+        "a",
+        "ab",
+        "abc",
+        "abcabc",
+        "b",
+        "ba",
+        "bab",
+        "logs/2019/01/app.log",
+        "logs/2019/02/app.log",
+        "logs/2019/02/db.log",
+        "logs/2020/01/app.txt",
+        "x*y",
+        "x?y",
+    ]
+    .iter()
+    .map(|k| k.as_bytes().to_vec())
+    .collect();
+
+    let mut art = cart::Art::default();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.clone(), i);
+    }
+
+    let patterns = [
+        "",
+        "*",
+        "?",
+        "a*",
+        "*c",
+        "a?c",
+        "*b*",
+        "a*c",
+        "**",
+        "?*?",
+        "logs/2019/*/app.log",
+        "logs/*.log",
+        "logs/20??/0?/*",
+        "x*y",
+        "x?y",
+        "abc*abc",
+        "*z*",
+    ];
+    for pattern in &patterns {
+        let found: Vec<Vec<u8>> = art
+            .match_pattern(pattern.as_bytes())
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        let mut expected: Vec<Vec<u8>> = keys
+            .iter()
+            .filter(|k| glob(pattern.as_bytes(), k))
+            .cloned()
+            .collect();
+        expected.sort();
+        assert_eq!(found, expected, "{}", pattern);
+    }
+}
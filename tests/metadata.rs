@@ -0,0 +1,95 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+use cart::{Art, ArtBuilder};
+
+// the largest value stored at or below every node
+fn annotate_max(art: &mut Art<u32, Option<u32>>) {
+    art.update_metadata(|_, value, children| {
+        children
+            .iter()
+            .filter_map(|max| **max)
+            .chain(value.cloned())
+            .max()
+    });
+}
+
+fn model_max(model: &BTreeMap<Vec<u8>, u32>, prefix: &[u8]) -> Option<u32> {
+    model
+        .iter()
+        .filter(|(k, _)| k.starts_with(prefix))
+        .map(|(_, v)| *v)
+        .max()
+}
+
+#[test]
+fn test_max_value_annotation() {
+    let mut art = ArtBuilder::new().build_with_metadata();
+    let mut model = BTreeMap::new();
+
+    for i in 0..500u32 {
+        let key = format!("{}", i * 7919 % 1000).into_bytes();
+        let value = i * 31 % 97;
+        art.set(key.clone(), value);
+        model.insert(key, value);
+    }
+    annotate_max(&mut art);
+
+    let prefixes: Vec<&[u8]> = vec![b"", b"1", b"12", b"123", b"9", b"99", b"5000", b"x"];
+    for prefix in prefixes {
+        assert_eq!(
+            art.metadata(prefix).cloned().and_then(|max| max),
+            model_max(&model, prefix),
+            "prefix {:?}",
+            prefix
+        );
+    }
+}
+
+#[test]
+fn test_metadata_carried_over_grow() {
+    let mut art: Art<u32, Option<u32>> = ArtBuilder::new().build_with_metadata();
+    for b in 0..4u8 {
+        art.set(vec![b], u32::from(b));
+    }
+    annotate_max(&mut art);
+    assert_eq!(art.root_variant(), "Node4");
+    assert_eq!(art.metadata(b""), Some(&Some(3)));
+
+    // the root grows into a Node16 and keeps its metadata
+    // until it is recomputed
+    art.set(vec![100], 100);
+    assert_eq!(art.root_variant(), "Node16");
+    assert_eq!(art.metadata(b""), Some(&Some(3)));
+    assert_eq!(art.metadata(&[100]), Some(&None));
+
+    annotate_max(&mut art);
+    assert_eq!(art.metadata(b""), Some(&Some(100)));
+    assert_eq!(art.metadata(&[3]), Some(&Some(3)));
+}
+
+#[test]
+fn test_metadata_sees_full_keys() {
+    let mut art: Art<(), Vec<u8>> = ArtBuilder::new().build_with_metadata();
+    art.set(b"abc".to_vec(), ());
+    art.set(b"abd".to_vec(), ());
+
+    art.update_metadata(|key, _, _| key.to_vec());
+
+    assert_eq!(art.metadata(b"a"), Some(&b"ab".to_vec()));
+    assert_eq!(art.metadata(b"abc"), Some(&b"abc".to_vec()));
+    assert_eq!(art.metadata(b"abe"), None);
+}
+
+#[test]
+fn test_map_keeps_metadata() {
+    let mut art: Art<u32, Option<u32>> = ArtBuilder::new().build_with_metadata();
+    art.set(b"a".to_vec(), 1);
+    art.set(b"b".to_vec(), 2);
+    annotate_max(&mut art);
+
+    let art = art.map(|_, v| v * 10);
+    assert_eq!(art.metadata(b""), Some(&Some(2)));
+    assert_eq!(art.get(b"b"), Some(&20));
+}
@@ -0,0 +1,193 @@
+// Small workloads that walk every path through the unsafe
+// code: node growth and shrinking, prefix splits and merges,
+// mutable iteration, cursors and the ways nodes change hands
+// between trees. They are sized to finish under Miri, which
+// checks the pointer accesses along the way:
+//
+//     cargo +nightly miri test --test miri
+//
+// String values make every leaked or doubly freed value show up
+// as well. `test_mutable_access` asks `get_many_mut` for a key
+// before one it prefixes, which Miri flagged while lookups took
+// the nodes they passed mutably. Miri reports no undefined
+// behavior or leaks for any of these tests.
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+// every number below `n`, so the tests know which keys `b"1"` or
+// the prefix `b"4"` hit
+fn numbered(n: u32) -> (cart::Art<String>, BTreeMap<Vec<u8>, String>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for i in 0..n {
+        let key = format!("{}", n - 1 - i).into_bytes();
+        art.set(key.clone(), i.to_string());
+        model.insert(key, i.to_string());
+    }
+    (art, model)
+}
+
+fn check(art: &cart::Art<String>, model: &BTreeMap<Vec<u8>, String>) {
+    art.validate();
+    assert_eq!(art.len(), model.len());
+    assert!(art.iter().eq(model.iter().map(|(k, v)| (k.clone(), v))));
+}
+
+#[test]
+fn test_grow_and_shrink() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    // every variant up to a Node256, then back down again
+    for byte in 0..=255u8 {
+        art.set(vec![b'x', byte], byte.to_string());
+        model.insert(vec![b'x', byte], byte.to_string());
+    }
+    check(&art, &model);
+    for byte in (0..=255u8).rev() {
+        assert_eq!(art.remove(&[b'x', byte]), model.remove(&vec![b'x', byte]));
+    }
+    check(&art, &model);
+}
+
+#[test]
+fn test_prefix_splits_and_merges() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let keys: &[&[u8]] = &[b"abcdef", b"abcxyz", b"abc", b"ab", b"", b"abcdefgh"];
+    for key in keys {
+        art.set(key.to_vec(), String::from_utf8(key.to_vec()).unwrap());
+        model.insert(key.to_vec(), String::from_utf8(key.to_vec()).unwrap());
+    }
+    check(&art, &model);
+    for key in keys.iter().rev() {
+        assert_eq!(art.remove(key), model.remove(*key));
+        check(&art, &model);
+    }
+}
+
+#[test]
+fn test_mutable_access() {
+    let (mut art, mut model) = numbered(100);
+
+    for (_, value) in art.iter_mut() {
+        value.push('!');
+    }
+    for value in model.values_mut() {
+        value.push('!');
+    }
+    check(&art, &model);
+
+    if let Some([a, b]) = art.get_many_mut([b"1", b"2"]) {
+        std::mem::swap(a, b);
+    }
+    let a = model.remove(&b"1"[..]).unwrap();
+    let b = model.insert(b"2".to_vec(), a).unwrap();
+    model.insert(b"1".to_vec(), b);
+    check(&art, &model);
+
+    // the value of each key lies on the path to the next one
+    art.set(vec![], "root".to_string());
+    model.insert(vec![], "root".to_string());
+    if let Some([a, b, c]) = art.get_many_mut([b"", b"1", b"15"]) {
+        std::mem::swap(a, b);
+        std::mem::swap(b, c);
+    }
+    let empty = model.remove(&b""[..]).unwrap();
+    let one = model.remove(&b"1"[..]).unwrap();
+    let fifteen = model.remove(&b"15"[..]).unwrap();
+    model.insert(b"".to_vec(), one);
+    model.insert(b"1".to_vec(), fifteen);
+    model.insert(b"15".to_vec(), empty);
+    check(&art, &model);
+
+    // updates that add, change and remove keys on each
+    // other's paths
+    for key in [&b"4"[..], b"4x", b"4", b"40"] {
+        art.update(key.to_vec(), |old| match old {
+            Some(v) if v.len() > 2 => None,
+            Some(v) => Some(v + "?"),
+            None => Some("new".to_string()),
+        });
+        match model.remove(key) {
+            Some(v) if v.len() > 2 => {}
+            Some(v) => {
+                model.insert(key.to_vec(), v + "?");
+            }
+            None => {
+                model.insert(key.to_vec(), "new".to_string());
+            }
+        }
+    }
+    check(&art, &model);
+
+    art.retain(|_, v| v.len() % 2 == 0);
+    model.retain(|_, v| v.len() % 2 == 0);
+    check(&art, &model);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let (mut art, model) = numbered(60);
+
+    {
+        let mut cursor = art.cursor_mut();
+        while let Some(key) = cursor.key().map(|k| k.to_vec()) {
+            if key.len() == 1 {
+                cursor.remove_current();
+                let mut moved = b"z".to_vec();
+                moved.extend_from_slice(&key);
+                cursor.insert(moved, "moved".to_string());
+            } else {
+                cursor.next();
+            }
+        }
+    }
+
+    let mut expected = BTreeMap::new();
+    for (key, value) in model {
+        if key.len() == 1 {
+            let mut moved = b"z".to_vec();
+            moved.extend_from_slice(&key);
+            expected.insert(moved, "moved".to_string());
+        } else {
+            expected.insert(key, value);
+        }
+    }
+    check(&art, &expected);
+}
+
+#[test]
+fn test_nodes_changing_hands() {
+    let (mut art, mut model) = numbered(100);
+
+    let mut other = art.split_off(b"5");
+    let mut other_model = model.split_off(&b"5"[..]);
+    check(&art, &model);
+    check(&other, &other_model);
+
+    art.append(&mut other);
+    model.append(&mut other_model);
+    check(&art, &model);
+    check(&other, &other_model);
+
+    let copy = art.clone();
+    assert_eq!(art.remove_prefix(b"4"), 11);
+    model.retain(|k, _| !k.starts_with(b"4"));
+    check(&art, &model);
+
+    // iterators that are dropped half way free the rest
+    let mut into_iter = copy.clone().into_iter();
+    assert!(into_iter.next().is_some());
+    drop(into_iter);
+    let mut drain = art.drain();
+    assert!(drain.next().is_some());
+    drop(drain);
+    assert!(art.is_empty());
+
+    let lengths = copy.map(|_, v| v.len());
+    assert_eq!(lengths.len(), 100);
+    lengths.validate();
+}
@@ -0,0 +1,54 @@
+extern crate cart;
+extern crate rand;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+#[test]
+fn test_multi_get() {
+    let mut art = cart::Art::default();
+    for (i, key) in [&b"abcdef"[..], b"abcxyz", b"abc", b"b", b""]
+        .iter()
+        .enumerate()
+    {
+        art.set(key.to_vec(), i);
+    }
+
+    let probes: Vec<&[u8]> = vec![b"b", b"abcxyz", b"abcd", b"", b"abc", b"b", b"ab", b"c"];
+    assert_eq!(
+        art.multi_get(probes.iter().cloned()),
+        vec![
+            Some(&3),
+            Some(&1),
+            None,
+            Some(&4),
+            Some(&2),
+            Some(&3),
+            None,
+            None
+        ]
+    );
+    assert!(art.multi_get(vec![]).is_empty());
+}
+
+#[test]
+fn test_multi_get_matches_get() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut random_key = || -> Vec<u8> {
+        let len = rng.gen_range(0, 6);
+        (0..len).map(|_| rng.gen_range(0, 4)).collect()
+    };
+
+    let mut art = cart::Art::default();
+    for i in 0..300 {
+        art.set(random_key(), i);
+    }
+    for i in 0..300u32 {
+        art.set(format!("key{}", i).into_bytes(), i);
+    }
+
+    let mut probes: Vec<Vec<u8>> = (0..1000).map(|_| random_key()).collect();
+    probes.extend((0..400u32).map(|i| format!("key{}", i).into_bytes()));
+
+    let expected: Vec<Option<&u32>> = probes.iter().map(|k| art.get(k)).collect();
+    assert_eq!(art.multi_get(probes.iter().map(|k| &k[..])), expected);
+}
@@ -0,0 +1,76 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Unbounded};
+
+#[test]
+fn test_neighbours_match_model() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    let keys: &[&[u8]] = &[b"", b"a", b"ab", b"abc", b"abd", b"b", b"ba", b"bcd", b"c"];
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.to_vec(), i);
+        model.insert(key.to_vec(), i);
+    }
+    for i in 0..40u8 {
+        art.set(vec![b'd', i * 3], 100 + i as usize);
+        model.insert(vec![b'd', i * 3], 100 + i as usize);
+    }
+
+    let mut probes: Vec<Vec<u8>> = keys.iter().map(|k| k.to_vec()).collect();
+    for probe in &[
+        &b"aa"[..],
+        b"abcd",
+        b"abe",
+        b"bb",
+        b"bc",
+        b"bcde",
+        b"d",
+        b"z",
+    ] {
+        probes.push(probe.to_vec());
+    }
+    for i in 0..130u8 {
+        probes.push(vec![b'd', i]);
+    }
+
+    let owned = |found: Option<(&Vec<u8>, &usize)>| found.map(|(k, v)| (k.clone(), *v));
+    let copied = |found: Option<(Vec<u8>, &usize)>| found.map(|(k, v)| (k, *v));
+    for probe in &probes {
+        let p = probe.clone();
+        assert_eq!(
+            copied(art.get_ge(probe)),
+            owned(model.range(p.clone()..).next())
+        );
+        assert_eq!(
+            copied(art.get_gt(probe)),
+            owned(model.range((Excluded(p.clone()), Unbounded)).next())
+        );
+        assert_eq!(
+            copied(art.get_le(probe)),
+            owned(model.range(..=p.clone()).next_back())
+        );
+        assert_eq!(
+            copied(art.get_lt(probe)),
+            owned(model.range((Unbounded, Excluded(p))).next_back())
+        );
+    }
+}
+
+#[test]
+fn test_latest_sample_at_or_before() {
+    let mut samples = cart::Art::default();
+    for t in (0..100u64).map(|t| t * 10) {
+        samples.set(t.to_be_bytes().to_vec(), t);
+    }
+
+    let at = |t: u64| samples.get_le(&t.to_be_bytes()).map(|(_, v)| *v);
+    assert_eq!(at(0), Some(0));
+    assert_eq!(at(55), Some(50));
+    assert_eq!(at(60), Some(60));
+    assert_eq!(at(5000), Some(990));
+
+    let empty: cart::Art<u64> = cart::Art::default();
+    assert_eq!(empty.get_le(b"x"), None);
+    assert_eq!(empty.get_ge(b""), None);
+}
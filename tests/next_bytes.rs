@@ -0,0 +1,37 @@
+extern crate cart;
+
+#[test]
+fn test_children_of_prefix() {
+    let mut art = cart::Art::default();
+    for word in &["car", "cart", "carts", "cat", "dog", "door", "zebra"] {
+        art.set(word.as_bytes().to_vec(), ());
+    }
+
+    assert_eq!(art.children_of_prefix(b""), b"cdz".to_vec());
+    assert_eq!(art.children_of_prefix(b"c"), b"a".to_vec());
+    assert_eq!(art.children_of_prefix(b"ca"), b"rt".to_vec());
+    assert_eq!(art.children_of_prefix(b"car"), b"t".to_vec());
+    assert_eq!(art.children_of_prefix(b"cart"), b"s".to_vec());
+    assert_eq!(art.children_of_prefix(b"do"), b"go".to_vec());
+
+    // the prefix ends inside the prefix of the node for "zebra"
+    assert_eq!(art.children_of_prefix(b"z"), b"e".to_vec());
+    assert_eq!(art.children_of_prefix(b"zeb"), b"r".to_vec());
+
+    // stored keys without anything after them
+    assert!(art.children_of_prefix(b"carts").is_empty());
+    assert!(art.children_of_prefix(b"zebra").is_empty());
+    assert!(art.children_of_prefix(b"cab").is_empty());
+    assert!(art.children_of_prefix(b"x").is_empty());
+}
+
+#[test]
+fn test_children_of_prefix_wide_node() {
+    let mut art = cart::Art::default();
+    for b in (0..=255u8).rev().step_by(3) {
+        art.set(vec![b'k', b, 0], ());
+    }
+
+    let expected: Vec<u8> = (0..=255u8).rev().step_by(3).rev().collect();
+    assert_eq!(art.children_of_prefix(b"k"), expected);
+}
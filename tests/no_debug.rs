@@ -0,0 +1,23 @@
+extern crate cart;
+
+// deliberately implements neither Debug nor Clone
+struct Opaque(u32);
+
+#[test]
+fn test_values_without_debug() {
+    let mut art = cart::Art::default();
+    for i in 0..100u32 {
+        art.set(format!("{}", i).into_bytes(), Opaque(i));
+    }
+    assert_eq!(art.get(b"42").map(|o| o.0), Some(42));
+    assert_eq!(art.iter().map(|(_, o)| o.0).sum::<u32>(), 4950);
+    assert_eq!(art.remove(b"7").map(|o| o.0), Some(7));
+    art.validate();
+
+    let mut handlers: cart::Art<Box<dyn Fn(u32) -> u32>> = cart::Art::default();
+    handlers.set(b"double".to_vec(), Box::new(|x| x * 2));
+    handlers.set(b"square".to_vec(), Box::new(|x| x * x));
+    assert_eq!(handlers.get(b"square").map(|f| f(5)), Some(25));
+    let results: Vec<u32> = handlers.values().map(|f| f(3)).collect();
+    assert_eq!(results, vec![6, 9]);
+}
@@ -0,0 +1,56 @@
+extern crate cart;
+
+use std::borrow::Cow;
+
+use cart::{ascii_lowercase, ArtBuilder, NormalizedArt};
+
+#[test]
+fn test_case_insensitive_hosts() {
+    let mut hosts = NormalizedArt::new(ascii_lowercase);
+    assert_eq!(hosts.set(b"Example.COM", 1), None);
+    assert_eq!(hosts.set(b"example.com", 2), Some(1));
+    hosts.set(b"API.Example.com", 3);
+    hosts.set(b"b\xc3\x9cCHER.de", 4);
+    assert_eq!(hosts.len(), 3);
+
+    assert_eq!(hosts.get(b"EXAMPLE.com"), Some(&2));
+    assert_eq!(hosts.get(b"api.example.COM"), Some(&3));
+    // only ASCII is folded
+    assert_eq!(hosts.get(b"b\xc3\x9ccher.de"), Some(&4));
+    *hosts.get_mut(b"Api.Example.Com").unwrap() += 10;
+
+    let keys: Vec<Vec<u8>> = hosts.iter().map(|(k, _)| k).collect();
+    assert_eq!(
+        keys,
+        vec![
+            b"api.example.com".to_vec(),
+            b"b\xc3\x9ccher.de".to_vec(),
+            b"example.com".to_vec(),
+        ]
+    );
+
+    let scanned: Vec<u32> = hosts.iter_prefix(b"API.").map(|(_, v)| *v).collect();
+    assert_eq!(scanned, vec![13]);
+    assert_eq!(hosts.range(b"B", b"F").count(), 2);
+
+    assert_eq!(hosts.remove(b"EXAMPLE.COM"), Some(2));
+    assert_eq!(hosts.get(b"example.com"), None);
+    assert_eq!(hosts.into_art().len(), 2);
+}
+
+#[test]
+fn test_custom_normalizer() {
+    // ignores trailing dots, as in fully qualified names
+    fn trim_dots(key: &[u8]) -> Cow<'_, [u8]> {
+        let end = key.iter().rposition(|b| *b != b'.').map_or(0, |i| i + 1);
+        Cow::Borrowed(&key[..end])
+    }
+
+    let mut names = ArtBuilder::new()
+        .intern_prefixes(true)
+        .build_normalized(trim_dots);
+    names.set(b"example.com.", 1);
+    assert_eq!(names.get(b"example.com"), Some(&1));
+    assert_eq!(names.get(b"example.com.."), Some(&1));
+    assert_eq!(names.as_art().get(b"example.com"), Some(&1));
+}
@@ -0,0 +1,62 @@
+extern crate cart;
+
+fn check_overwrite(keys: &[&[u8]], key: &[u8]) {
+    let mut art = cart::Art::default();
+    for (i, k) in keys.iter().enumerate() {
+        art.set(k.to_vec(), i);
+    }
+
+    let previous = keys.iter().rposition(|k| *k == key);
+    assert_eq!(art.set(key.to_vec(), 100), previous);
+    assert_eq!(art.set(key.to_vec(), 101), Some(100));
+    assert_eq!(art.get(key), Some(&101));
+    art.validate();
+
+    let mut expected: Vec<Vec<u8>> = keys.iter().map(|k| k.to_vec()).collect();
+    expected.push(key.to_vec());
+    expected.sort();
+    expected.dedup();
+
+    let drained: Vec<Vec<u8>> = art.drain().map(|(k, _)| k).collect();
+    assert_eq!(drained, expected);
+}
+
+#[test]
+fn test_overwrite_across_split_prefix() {
+    // "abcdef" gets split at "abc" by "abcxyz"
+    check_overwrite(&[b"abcdef", b"abcxyz"], b"abcdef");
+    check_overwrite(&[b"abcdef", b"abcxyz"], b"abcxyz");
+    check_overwrite(&[b"abcdef", b"abcxyz", b"abc"], b"abc");
+
+    // splits at the very first and very last byte of a prefix
+    check_overwrite(&[b"abcdef", b"xbcdef"], b"abcdef");
+    check_overwrite(&[b"abcdef", b"abcdeg"], b"abcdef");
+    check_overwrite(&[b"abcdef", b"abcdeg"], b"abcdeg");
+
+    // repeated splits of the same path
+    check_overwrite(
+        &[b"abcdefgh", b"abcdefxx", b"abcxxxxx", b"axxxxxxx"],
+        b"abcdefgh",
+    );
+    check_overwrite(
+        &[b"abcdefgh", b"abcdefxx", b"abcxxxxx", b"axxxxxxx"],
+        b"abcxxxxx",
+    );
+
+    // the empty key lives at the root
+    check_overwrite(&[b"abc"], b"");
+}
+
+#[test]
+fn test_overwrite_after_split_keeps_siblings() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+    art.set(b"abcxyz".to_vec(), 2);
+    assert_eq!(art.set(b"abcdef".to_vec(), 3), Some(1));
+    assert_eq!(art.set(b"abcxyz".to_vec(), 4), Some(2));
+
+    assert_eq!(art.get(b"abcdef"), Some(&3));
+    assert_eq!(art.get(b"abcxyz"), Some(&4));
+    assert_eq!(art.get(b"abc"), None);
+    assert_eq!(art.drain().count(), 2);
+}
@@ -0,0 +1,93 @@
+extern crate cart;
+
+fn populated() -> cart::Art<u32> {
+    let mut art = cart::Art::default();
+    for i in 0..100u32 {
+        art.set(format!("users/{:03}", i).into_bytes(), i);
+        art.set(format!("groups/{:03}", i).into_bytes(), i);
+    }
+    art.set(b"users".to_vec(), 1000);
+    art.set(b"users/".to_vec(), 1001);
+    art.set(b"usersz".to_vec(), 1002);
+    art
+}
+
+#[test]
+fn test_paging_covers_prefix() {
+    let art = populated();
+
+    for limit in &[1, 7, 50, 102, 200] {
+        let mut paged = vec![];
+        let mut token: Option<Vec<u8>> = None;
+        loop {
+            let page = art.scan_prefix_paged(b"users/", *limit, token.as_ref().map(|t| &t[..]));
+            assert!(page.entries.len() <= *limit);
+            paged.extend(page.entries.into_iter().map(|(k, v)| (k, *v)));
+            token = page.resume_after;
+            if token.is_none() {
+                break;
+            }
+        }
+
+        let expected: Vec<(Vec<u8>, u32)> =
+            art.iter_prefix(b"users/").map(|(k, v)| (k, *v)).collect();
+        assert_eq!(paged, expected, "limit {}", limit);
+    }
+}
+
+#[test]
+fn test_paging_between_changes() {
+    let mut art = populated();
+
+    let page = art.scan_prefix_paged(b"users/", 10, None);
+    assert_eq!(page.entries[0].0, b"users/".to_vec());
+    let token = page.resume_after.unwrap();
+    assert_eq!(token, b"users/008".to_vec());
+
+    // the token stays valid when its own key goes away
+    art.remove(&token);
+    art.set(b"users/0085".to_vec(), 2000);
+    art.set(b"users/000x".to_vec(), 2001);
+
+    let page = art.scan_prefix_paged(b"users/", 3, Some(&token));
+    let keys: Vec<Vec<u8>> = page.entries.into_iter().map(|(k, _)| k).collect();
+    assert_eq!(
+        keys,
+        vec![
+            b"users/0085".to_vec(),
+            b"users/009".to_vec(),
+            b"users/010".to_vec()
+        ]
+    );
+
+    // tokens outside of the prefix start at its first entry or
+    // find nothing
+    let page = art.scan_prefix_paged(b"users/", 1, Some(b"groups/050"));
+    assert_eq!(page.entries[0].0, b"users/".to_vec());
+    let page = art.scan_prefix_paged(b"users/", 1, Some(b"v"));
+    assert_eq!(
+        page,
+        cart::Page {
+            entries: vec![],
+            resume_after: None
+        }
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_paging_without_limit() {
+    populated().scan_prefix_paged(b"users/", 0, None);
+}
+
+#[test]
+fn test_try_paging() {
+    let art = populated();
+    assert_eq!(
+        art.try_scan_prefix_paged(b"users/", 0, None),
+        Err(cart::CartError::ZeroLimit)
+    );
+
+    let page = art.try_scan_prefix_paged(b"users/", 10, None).unwrap();
+    assert_eq!(page, art.scan_prefix_paged(b"users/", 10, None));
+}
@@ -0,0 +1,140 @@
+extern crate cart;
+
+mod common;
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+use common::count;
+
+fn populated() -> cart::Art<String> {
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(format!("{}", i * 7 % 1000).into_bytes(), i.to_string());
+    }
+    art
+}
+
+// runs `f`, which has to panic, without printing the panic
+fn expect_panic<F: FnOnce()>(f: F) {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+    assert!(result.is_err());
+}
+
+// runs `test` and returns whether everything it allocated was
+// freed again
+fn freed_everything<F: FnOnce()>(test: F) -> bool {
+    // the first panic on a thread sets up state that outlives it
+    expect_panic(|| panic!("warm up"));
+
+    let (_, allocated, freed) = count(test);
+    allocated == freed
+}
+
+thread_local! {
+    static CLONES: Cell<u32> = const { Cell::new(0) };
+}
+
+// a value whose clone panics once `CLONES` ran out
+struct Fragile(u32);
+
+impl Clone for Fragile {
+    fn clone(&self) -> Fragile {
+        if CLONES.with(|c| c.replace(c.get().saturating_sub(1))) == 0 {
+            panic!("out of clones");
+        }
+        Fragile(self.0)
+    }
+}
+
+#[test]
+fn test_panic_in_retain() {
+    assert!(freed_everything(|| {
+        let mut art = populated();
+        let before: Vec<(Vec<u8>, String)> = art.iter().map(|(k, v)| (k, v.clone())).collect();
+
+        let mut seen = 0;
+        expect_panic(|| {
+            art.retain(|_, v| {
+                seen += 1;
+                if seen == 500 {
+                    panic!("retain");
+                }
+                v.len() != 2
+            })
+        });
+
+        // the entries visited before the panic were filtered,
+        // the others are left alone
+        let expected: Vec<(Vec<u8>, String)> = before
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (_, v))| *i >= 499 || v.len() != 2)
+            .map(|(_, entry)| entry)
+            .collect();
+        art.validate();
+        assert_eq!(art.len(), expected.len());
+        assert!(art.iter().map(|(k, v)| (k, v.clone())).eq(expected));
+    }));
+}
+
+#[test]
+fn test_panic_in_fetch_and_update() {
+    assert!(freed_everything(|| {
+        let mut art = populated();
+        expect_panic(|| {
+            art.fetch_and_update(b"500", |_| panic!("update"));
+        });
+
+        // the value handed to the closure is gone with it
+        art.validate();
+        assert_eq!(art.len(), 999);
+        assert_eq!(art.get(b"500"), None);
+        assert_eq!(art.count_prefix(b"50"), 10);
+    }));
+}
+
+#[test]
+fn test_panic_in_map() {
+    assert!(freed_everything(|| {
+        let art = populated();
+        let mut mapped = 0;
+        expect_panic(|| {
+            art.map(|_, v| {
+                mapped += 1;
+                if mapped == 500 {
+                    panic!("map");
+                }
+                v.len()
+            });
+        });
+    }));
+}
+
+#[test]
+fn test_panic_in_clone() {
+    assert!(freed_everything(|| {
+        let mut art = cart::Art::default();
+        for i in 0..1000u32 {
+            art.set(format!("{}", i).into_bytes(), Fragile(i));
+        }
+
+        CLONES.with(|c| c.set(500));
+        expect_panic(|| {
+            drop(art.clone());
+        });
+
+        // the original is untouched and can still be cloned
+        art.validate();
+        CLONES.with(|c| c.set(1000));
+        let copy = art.clone();
+        copy.validate();
+        assert!(copy
+            .iter()
+            .map(|(_, v)| v.0)
+            .eq(art.iter().map(|(_, v)| v.0)));
+    }));
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "rayon")]
+
+extern crate cart;
+extern crate rayon;
+
+use rayon::prelude::*;
+
+#[test]
+fn test_par_iter_matches_iter() {
+    let mut art = cart::Art::default();
+    for i in 0..10_000u64 {
+        art.set(format!("{}/{}", i % 97, i).into_bytes(), i);
+    }
+    art.set(vec![], 10_000);
+
+    let collected: Vec<(Vec<u8>, u64)> = art.par_iter().map(|(k, v)| (k, *v)).collect();
+    let expected: Vec<(Vec<u8>, u64)> = art.iter().map(|(k, v)| (k, *v)).collect();
+    assert_eq!(collected, expected);
+
+    let sum: u64 = art.par_iter().map(|(_, v)| *v).sum();
+    assert_eq!(sum, (0..=10_000).sum::<u64>());
+
+    let empty: cart::Art<u64> = cart::Art::default();
+    assert_eq!(empty.par_iter().count(), 0);
+}
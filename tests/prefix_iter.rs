@@ -0,0 +1,83 @@
+extern crate cart;
+
+mod common;
+
+use common::count;
+
+fn populate() -> cart::Art<u32> {
+    let mut art = cart::Art::default();
+    for (i, key) in [
+        &b"user:alice"[..],
+        b"user:bob",
+        b"user:",
+        b"user",
+        b"users",
+        b"group:admins",
+        b"",
+    ]
+    .iter()
+    .enumerate()
+    {
+        art.set(key.to_vec(), i as u32);
+    }
+    art
+}
+
+#[test]
+fn test_iter_prefix() {
+    let art = populate();
+
+    let keys: Vec<Vec<u8>> = art.iter_prefix(b"user").map(|(k, _)| k).collect();
+    assert_eq!(
+        keys,
+        vec![
+            b"user".to_vec(),
+            b"user:".to_vec(),
+            b"user:alice".to_vec(),
+            b"user:bob".to_vec(),
+            b"users".to_vec(),
+        ]
+    );
+
+    // the prefix ends inside a node prefix
+    assert_eq!(art.iter_prefix(b"user:a").count(), 1);
+    assert_eq!(art.iter_prefix(b"gr").count(), 1);
+    assert_eq!(art.iter_prefix(b"").count(), 7);
+    assert_eq!(art.iter_prefix(b"user:c").count(), 0);
+    assert_eq!(art.iter_prefix(b"user:alice!").count(), 0);
+    assert_eq!(art.iter_prefix(b"x").count(), 0);
+}
+
+#[test]
+fn test_iter_prefix_stripped() {
+    let art = populate();
+
+    for prefix in &[&b"user:"[..], b"user", b"u", b"user:alice", b"", b"x"] {
+        let expected: Vec<(Vec<u8>, &u32)> = art
+            .iter_prefix(prefix)
+            .map(|(k, v)| (k[prefix.len()..].to_vec(), v))
+            .collect();
+        let stripped: Vec<(Vec<u8>, &u32)> = art.iter_prefix_stripped(prefix).collect();
+        assert_eq!(stripped, expected, "prefix {:?}", prefix);
+    }
+
+    let stripped: Vec<Vec<u8>> = art.iter_prefix_stripped(b"user:").map(|(k, _)| k).collect();
+    assert_eq!(stripped, vec![vec![], b"alice".to_vec(), b"bob".to_vec()]);
+}
+
+#[test]
+fn test_scan_prefix_visits_only_the_subtree() {
+    let mut art = populate();
+    for i in 0..10_000u32 {
+        art.set(format!("event:{}", i).into_bytes(), i);
+    }
+
+    for prefix in [&b""[..], b"user", b"user:a", b"event:99", b"x"] {
+        assert!(art.scan_prefix(prefix).eq(art.iter_prefix(prefix)));
+    }
+
+    // the work depends on the namespace, not on the whole tree
+    let (keys, allocated, _) = count(|| art.scan_prefix(b"user:").count());
+    assert_eq!(keys, 3);
+    assert!(allocated < 20, "{} allocations", allocated);
+}
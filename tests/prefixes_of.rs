@@ -0,0 +1,67 @@
+extern crate cart;
+
+#[test]
+fn test_prefixes_of_query() {
+    let mut art = cart::Art::default();
+    let words: &[&[u8]] = &[
+        b"",
+        b"a",
+        b"an",
+        b"ant",
+        b"antelope",
+        b"anti",
+        b"b",
+        b"ban",
+        b"band",
+    ];
+    for (i, word) in words.iter().enumerate() {
+        art.set(word.to_vec(), i);
+    }
+
+    let found = |query: &[u8]| -> Vec<(Vec<u8>, usize)> {
+        art.prefixes_of(query)
+            .map(|(k, v)| (k.to_vec(), *v))
+            .collect()
+    };
+    let expected = |query: &[u8]| -> Vec<(Vec<u8>, usize)> {
+        words
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| query.starts_with(w))
+            .map(|(i, w)| (w.to_vec(), i))
+            .collect()
+    };
+
+    for query in &[
+        &b""[..],
+        b"a",
+        b"ante",
+        b"antelopes",
+        b"anti",
+        b"antic",
+        b"bandana",
+        b"c",
+        b"b",
+    ] {
+        assert_eq!(found(query), expected(query), "{:?}", query);
+    }
+
+    // the keys borrow from the query
+    let query = b"antelope".to_vec();
+    let longest = art.prefixes_of(&query).last().unwrap();
+    assert_eq!(longest, (&query[..], &4));
+}
+
+#[test]
+fn test_acl_resolution() {
+    let mut acl = cart::Art::default();
+    acl.set(b"/".to_vec(), "read");
+    acl.set(b"/home/".to_vec(), "none");
+    acl.set(b"/home/alice/".to_vec(), "write");
+
+    let effective = |path: &[u8]| acl.prefixes_of(path).last().map(|(_, rule)| *rule);
+    assert_eq!(effective(b"/etc/passwd"), Some("read"));
+    assert_eq!(effective(b"/home/bob/notes"), Some("none"));
+    assert_eq!(effective(b"/home/alice/notes"), Some("write"));
+    assert_eq!(effective(b"relative"), None);
+}
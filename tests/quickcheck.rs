@@ -15,8 +15,9 @@ const KEY_SPACE: u8 = 20;
 enum Op {
     Set(u8, u8),
     Get(u8),
+    Remove(u8),
 }
-use Op::{Get, Set};
+use Op::{Get, Remove, Set};
 
 // Arbitrary lets you create randomized instances
 // of types that you're interested in testing
@@ -28,10 +29,10 @@ impl Arbitrary for Op {
         // pick a random key to perform an operation on
         let k: u8 = g.gen_range(0, KEY_SPACE);
 
-        if g.gen_weighted_bool(2) {
-            Set(k, g.gen())
-        } else {
-            Get(k)
+        match g.gen_range(0, 3) {
+            0 => Set(k, g.gen()),
+            1 => Get(k),
+            _ => Remove(k),
         }
     }
 }
@@ -43,18 +44,101 @@ fn prop_impl_matches_model(ops: Vec<Op>) -> bool {
     for op in ops {
         match op {
             Set(k, v) => {
-                implementation.set(vec![k; k as usize], v);
-                model.insert(k, v);
+                if implementation.set(vec![k; k as usize], v) != model.insert(k, v) {
+                    return false;
+                }
             }
             Get(k) => {
-                if implementation.get(&*vec![k; k as usize]) != model.get(&k) {
+                if implementation.get(&vec![k; k as usize]) != model.get(&k) {
+                    return false;
+                }
+            }
+            Remove(k) => {
+                let removed = implementation.remove(&vec![k; k as usize]);
+                if removed != model.remove(&k) {
                     return false;
                 }
             }
         }
     }
 
-    true
+    implementation.validate();
+
+    implementation.len() == model.len()
+}
+
+// Keys over a tiny alphabet, so that generated keys often are
+// prefixes of each other and end at or inside node prefixes.
+#[derive(Clone, Debug)]
+struct Key(Vec<u8>);
+
+impl Arbitrary for Key {
+    fn arbitrary<G: Gen>(g: &mut G) -> Key {
+        let len = g.gen_range(0, 6);
+        Key((0..len).map(|_| g.gen_range(0, 3)).collect())
+    }
+}
+
+// sets the key to the value, or removes it for `None`
+fn prop_arbitrary_keys_match_model(ops: Vec<(Key, Option<u8>)>) -> bool {
+    let mut implementation = cart::Art::default();
+    let mut model = std::collections::BTreeMap::new();
+
+    for (Key(k), v) in ops {
+        match v {
+            Some(v) => {
+                if implementation.set(k.clone(), v) != model.insert(k, v) {
+                    return false;
+                }
+            }
+            None => {
+                if implementation.remove(&k) != model.remove(&k) {
+                    return false;
+                }
+            }
+        }
+    }
+
+    implementation.validate();
+
+    implementation.len() == model.len()
+        && model.iter().all(|(k, v)| implementation.get(k) == Some(v))
+        && implementation.drain().eq(model)
+}
+
+// iterating over everything and over a range agree with the
+// model after the same sets and removes.
+fn prop_iter_matches_model(ops: Vec<(Key, Option<u8>)>, start: Key, end: Key) -> bool {
+    let mut implementation = cart::Art::default();
+    let mut model = std::collections::BTreeMap::new();
+
+    for (Key(k), v) in ops {
+        match v {
+            Some(v) => {
+                implementation.set(k.clone(), v);
+                model.insert(k, v);
+            }
+            None => {
+                implementation.remove(&k);
+                model.remove(&k);
+            }
+        }
+    }
+
+    if !implementation
+        .iter()
+        .eq(model.iter().map(|(k, v)| (k.clone(), v)))
+    {
+        return false;
+    }
+
+    let (Key(start), Key(end)) = (start, end);
+    if start > end {
+        return implementation.range(&start[..]..&end[..]).next().is_none();
+    }
+    implementation
+        .range(&start[..]..&end[..])
+        .eq(model.range(start..end).map(|(k, v)| (k.clone(), v)))
 }
 
 // This macro is shorthand for creating a test
@@ -68,6 +152,14 @@ quickcheck! {
     fn implementation_matches_model(ops: Vec<Op>) -> bool {
         prop_impl_matches_model(ops)
     }
+
+    fn arbitrary_keys_match_model(ops: Vec<(Key, Option<u8>)>) -> bool {
+        prop_arbitrary_keys_match_model(ops)
+    }
+
+    fn iter_matches_model(ops: Vec<(Key, Option<u8>)>, start: Key, end: Key) -> bool {
+        prop_iter_matches_model(ops, start, end)
+    }
 }
 
 #[test]
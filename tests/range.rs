@@ -0,0 +1,190 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+
+// bounds can fall on a key, between keys that are prefixes of
+// each other, inside a node prefix or between the children of
+// a Node48
+fn populated() -> (cart::Art<usize>, BTreeMap<Vec<u8>, usize>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let keys: &[&[u8]] = &[b"", b"a", b"ab", b"abcd", b"abd", b"b", b"bb", b"ca"];
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.to_vec(), i);
+        model.insert(key.to_vec(), i);
+    }
+    for i in (0..120u8).step_by(2) {
+        art.set(vec![b'd', i, 40], i as usize);
+        model.insert(vec![b'd', i, 40], i as usize);
+    }
+
+    (art, model)
+}
+
+fn check(start: Bound<&[u8]>, end: Bound<&[u8]>) {
+    let (art, model) = populated();
+
+    let mut visited = vec![];
+    art.range_for_each(start, end, |k, v| visited.push((k.to_vec(), *v)));
+
+    let ranged: Vec<(Vec<u8>, usize)> = art
+        .range::<[u8], _>((start, end))
+        .map(|(k, v)| (k, *v))
+        .collect();
+    assert_eq!(ranged, visited);
+
+    let mut reversed: Vec<(Vec<u8>, usize)> = art
+        .range::<[u8], _>((start, end))
+        .rev()
+        .map(|(k, v)| (k, *v))
+        .collect();
+    reversed.reverse();
+    assert_eq!(reversed, visited);
+
+    let start = start.map(|s| s.to_vec());
+    let end = end.map(|e| e.to_vec());
+    let expected: Vec<(Vec<u8>, usize)> = model
+        .range::<Vec<u8>, _>((start.as_ref(), end.as_ref()))
+        .map(|(k, v)| (k.clone(), *v))
+        .collect();
+
+    assert_eq!(visited, expected);
+}
+
+#[test]
+fn test_range_for_each() {
+    let points: Vec<&[u8]> = vec![
+        b"",
+        b"a",
+        b"aa",
+        b"ab",
+        b"abc",
+        b"abcd",
+        b"abd",
+        b"b",
+        b"bb",
+        b"c",
+        b"ca",
+        b"d",
+        &[b'd', 10],
+        &[b'd', 10, 10],
+        &[b'd', 30, 40],
+        b"z",
+    ];
+
+    check(Unbounded, Unbounded);
+    for a in &points {
+        check(Included(a), Unbounded);
+        check(Excluded(a), Unbounded);
+        check(Unbounded, Included(a));
+        check(Unbounded, Excluded(a));
+        for b in &points {
+            if a > b {
+                continue;
+            }
+            check(Included(a), Included(b));
+            check(Included(a), Excluded(b));
+            check(Excluded(a), Included(b));
+            if a != b {
+                check(Excluded(a), Excluded(b));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_range() {
+    let (art, model) = populated();
+
+    let points: Vec<&[u8]> = vec![
+        b"",
+        b"a",
+        b"ab",
+        b"abcd",
+        b"b",
+        b"ca",
+        b"d",
+        &[b'd', 10],
+        &[b'd', 30, 40],
+        b"z",
+    ];
+    for a in &points {
+        for b in &points {
+            let ranged: Vec<(Vec<u8>, usize)> = art.range(*a..*b).map(|(k, v)| (k, *v)).collect();
+
+            let mut visited = vec![];
+            art.range_for_each(Included(a), Excluded(b), |k, v| {
+                visited.push((k.to_vec(), *v))
+            });
+            assert_eq!(ranged, visited, "{:?}..{:?}", a, b);
+        }
+    }
+
+    let all: Vec<(Vec<u8>, usize)> = art.iter().map(|(k, v)| (k, *v)).collect();
+    assert_eq!(all, model.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_range_bounds() {
+    let (art, model) = populated();
+    let collect = |range: cart::Range<usize>| -> Vec<Vec<u8>> { range.map(|(k, _)| k).collect() };
+    let expected = |range: std::collections::btree_map::Range<Vec<u8>, usize>| -> Vec<Vec<u8>> {
+        range.map(|(k, _)| k.clone()).collect()
+    };
+
+    let (a, b) = (b"ab".to_vec(), b"c".to_vec());
+    assert_eq!(
+        collect(art.range(&a[..]..&b[..])),
+        expected(model.range(a.clone()..b.clone()))
+    );
+    assert_eq!(
+        collect(art.range(&a[..]..=&b[..])),
+        expected(model.range(a.clone()..=b.clone()))
+    );
+    assert_eq!(
+        collect(art.range(a.clone()..)),
+        expected(model.range(a.clone()..))
+    );
+    assert_eq!(
+        collect(art.range(..b.clone())),
+        expected(model.range(..b.clone()))
+    );
+    assert_eq!(
+        collect(art.range::<[u8], _>(..)),
+        expected(model.range::<Vec<u8>, _>(..))
+    );
+
+    // nothing lies in an inverted range
+    assert_eq!(art.range(&b[..]..&a[..]).count(), 0);
+}
+
+#[test]
+fn test_range_mut() {
+    let (mut art, mut model) = populated();
+
+    let (a, b): (&[u8], &[u8]) = (b"ab", b"d\x0a");
+    for (key, value) in art.range_mut(a..b) {
+        assert!(&key[..] >= a && &key[..] < b);
+        *value += 1000;
+    }
+    for (_, value) in model.range_mut(a.to_vec()..b.to_vec()) {
+        *value += 1000;
+    }
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model.clone()));
+
+    // re-price everything in one category
+    let mut prices = cart::Art::default();
+    for sku in &[
+        "books/1", "books/2", "games/1", "games/2", "games/3", "toys/1",
+    ] {
+        prices.set(sku.as_bytes().to_vec(), 100);
+    }
+    for (_, price) in prices.range_mut(&b"games/"[..]..&b"games0"[..]) {
+        *price = *price * 9 / 10;
+    }
+    let repriced: Vec<u32> = prices.iter().map(|(_, v)| *v).collect();
+    assert_eq!(repriced, vec![100, 100, 90, 90, 90, 100]);
+}
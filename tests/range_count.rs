@@ -0,0 +1,70 @@
+extern crate cart;
+extern crate rand;
+
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+#[test]
+fn test_range_count() {
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(format!("log/{:04}", i).into_bytes(), i);
+    }
+    art.set(b"log".to_vec(), 0);
+    art.set(b"metrics/cpu".to_vec(), 0);
+
+    assert_eq!(art.range_count::<[u8], _>(..), 1002);
+    assert_eq!(art.range_count(&b"log/0100"[..]..&b"log/0200"[..]), 100);
+    assert_eq!(art.range_count(&b"log/0100"[..]..=&b"log/0200"[..]), 101);
+    assert_eq!(art.range_count(&b"log/"[..]..&b"log0"[..]), 1000);
+    assert_eq!(art.range_count(&b"log"[..]..&b"log/"[..]), 1);
+    assert_eq!(art.range_count(..&b"log/0010"[..]), 11);
+    assert_eq!(art.range_count(&b"log/0995"[..]..), 6);
+    assert_eq!(art.range_count(&b"m"[..]..&b"n"[..]), 1);
+    assert_eq!(art.range_count(&b"n"[..]..&b"m"[..]), 0);
+}
+
+#[test]
+fn test_range_count_matches_model() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    let random_key = |rng: &mut XorShiftRng| -> Vec<u8> {
+        let len = rng.gen_range(0, 5);
+        (0..len).map(|_| rng.gen_range(0, 4)).collect()
+    };
+
+    for _ in 0..300 {
+        let key = random_key(&mut rng);
+        if rng.gen() {
+            art.set(key.clone(), ());
+            model.insert(key, ());
+        } else {
+            art.remove(&key);
+            model.remove(&key);
+        }
+    }
+
+    for _ in 0..2000 {
+        let (a, b) = (random_key(&mut rng), random_key(&mut rng));
+        let bound = |key: &Vec<u8>, kind| match kind {
+            0 => Included(key.clone()),
+            1 => Excluded(key.clone()),
+            _ => Unbounded,
+        };
+        let (start, end) = (
+            bound(&a, rng.gen_range(0, 3)),
+            bound(&b, rng.gen_range(0, 3)),
+        );
+
+        let expected = match (&start, &end) {
+            (Included(a), Included(b)) | (Included(a), Excluded(b)) if a > b => 0,
+            (Excluded(a), Included(b)) | (Excluded(a), Excluded(b)) if a >= b => 0,
+            _ => model.range((start.clone(), end.clone())).count(),
+        };
+        assert_eq!(art.range_count((start.clone(), end.clone())), expected);
+    }
+}
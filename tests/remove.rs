@@ -0,0 +1,155 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+use cart::{Art, ArtBuilder, Stats};
+
+#[test]
+fn test_remove_returns_value() {
+    let mut art = Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+    art.set(b"ab".to_vec(), 3);
+
+    assert_eq!(art.remove(b"abc"), Some(1));
+    assert_eq!(art.remove(b"abc"), None);
+    assert_eq!(art.remove(b"a"), None);
+    assert_eq!(art.remove(b"abcd"), None);
+    assert_eq!(art.get(b"abd"), Some(&2));
+    assert_eq!(art.get(b"ab"), Some(&3));
+    art.validate();
+}
+
+#[test]
+fn test_remove_everything_leaves_empty_root() {
+    let mut art = Art::default();
+    let keys: Vec<Vec<u8>> = (0..1000u32)
+        .map(|i| format!("{}", i * 7919 % 1000).into_bytes())
+        .collect();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.clone(), i);
+    }
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(art.remove(key), Some(i));
+        art.validate();
+    }
+
+    assert_eq!(
+        art.stats(),
+        Stats {
+            node4: 1,
+            node16: 0,
+            node48: 0,
+            node256: 0,
+        }
+    );
+    assert_eq!(art.drain().count(), 0);
+
+    // the emptied tree is still usable
+    art.set(b"again".to_vec(), 1);
+    assert_eq!(art.get(b"again"), Some(&1));
+}
+
+#[test]
+fn test_nodes_shrink() {
+    let mut art = Art::default();
+    for b in 0..=255u8 {
+        art.set(vec![b], u32::from(b));
+    }
+    assert_eq!(art.root_variant(), "Node256");
+
+    let mut variants = vec![];
+    for b in 0..=255u8 {
+        art.remove(&[b]);
+        if variants.last() != Some(&art.root_variant()) {
+            variants.push(art.root_variant());
+        }
+    }
+    assert_eq!(variants, vec!["Node256", "Node48", "Node16", "Node4"]);
+
+    // without Node48s, a Node256 shrinks straight into a Node16
+    let mut art = ArtBuilder::new().node48(false).build();
+    for b in 0..20u8 {
+        art.set(vec![b], ());
+    }
+    assert_eq!(art.root_variant(), "Node256");
+    for b in 0..5u8 {
+        art.remove(&[b]);
+    }
+    assert_eq!(art.root_variant(), "Node16");
+}
+
+#[test]
+fn test_single_child_is_merged() {
+    let mut art = Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+    art.set(b"abcxyz".to_vec(), 2);
+    art.set(b"abcxzz".to_vec(), 3);
+    let before = art.stats();
+
+    // "abc" is left with the single child for "abcx", which
+    // takes its place
+    assert_eq!(art.remove(b"abcdef"), Some(1));
+    art.validate();
+    assert_eq!(art.stats().node4, before.node4 - 2);
+    assert_eq!(art.children_of_prefix(b"a"), b"b".to_vec());
+    assert_eq!(art.get(b"abcxyz"), Some(&2));
+    assert_eq!(art.get(b"abcxzz"), Some(&3));
+
+    // new keys still split the merged prefix correctly
+    art.set(b"abd".to_vec(), 4);
+    art.set(b"abcx".to_vec(), 5);
+    art.validate();
+    let entries: BTreeMap<Vec<u8>, u32> = art.drain().collect();
+    assert_eq!(
+        entries.into_iter().collect::<Vec<_>>(),
+        vec![
+            (b"abcx".to_vec(), 5),
+            (b"abcxyz".to_vec(), 2),
+            (b"abcxzz".to_vec(), 3),
+            (b"abd".to_vec(), 4),
+        ]
+    );
+}
+
+#[test]
+fn test_update_removal_prunes() {
+    let mut art = Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+
+    art.update(b"abc".to_vec(), |_| None);
+    art.update(b"abd".to_vec(), |_| None);
+
+    assert_eq!(art.stats().node4, 1);
+    assert_eq!(art.drain().count(), 0);
+}
+
+#[test]
+fn test_shrink_thresholds() {
+    // a node shrinks once its children drop below the capacity
+    // of the next smaller variant
+    let cases = [
+        (49, 48, "Node256", "Node48"),
+        (17, 16, "Node48", "Node16"),
+        (5, 4, "Node16", "Node4"),
+    ];
+
+    for &(size, capacity, larger, smaller) in &cases {
+        let mut art = Art::default();
+        for b in 0..size {
+            art.set(vec![b], ());
+        }
+        assert_eq!(art.root_variant(), larger);
+
+        for b in capacity..size {
+            art.remove(&[b]);
+        }
+        assert_eq!(art.root_variant(), larger);
+
+        art.remove(&[0]);
+        assert_eq!(art.root_variant(), smaller);
+        art.validate();
+    }
+}
@@ -0,0 +1,47 @@
+extern crate cart;
+
+#[test]
+fn test_remove_if() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+    art.set(b"ab".to_vec(), 3);
+
+    // retained, the predicate sees the stored value
+    assert_eq!(
+        art.remove_if(b"abc", |v| {
+            assert_eq!(*v, 1);
+            false
+        }),
+        None
+    );
+    assert_eq!(art.get(b"abc"), Some(&1));
+
+    // removed
+    assert_eq!(art.remove_if(b"abc", |v| *v == 1), Some(1));
+    assert_eq!(art.get(b"abc"), None);
+    assert_eq!(art.get(b"abd"), Some(&2));
+
+    // interior values can be removed too
+    assert_eq!(art.remove_if(b"ab", |_| true), Some(3));
+    assert_eq!(art.get(b"ab"), None);
+    assert_eq!(art.get(b"abd"), Some(&2));
+    art.validate();
+
+    assert_eq!(art.drain().collect::<Vec<_>>(), vec![(b"abd".to_vec(), 2)]);
+}
+
+#[test]
+fn test_remove_if_absent() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+
+    let never = |_: &u32| -> bool { panic!("predicate called") };
+    assert_eq!(art.remove_if(b"ab", never), None);
+    assert_eq!(art.remove_if(b"abcd", never), None);
+    assert_eq!(art.remove_if(b"x", never), None);
+    assert_eq!(art.remove_if(b"abc", |_| true), Some(1));
+    assert_eq!(art.remove_if(b"abc", never), None);
+
+    assert_eq!(art.drain().count(), 0);
+}
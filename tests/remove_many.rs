@@ -0,0 +1,43 @@
+extern crate cart;
+
+#[test]
+fn test_remove_many() {
+    let keys: Vec<Vec<u8>> = (0..200u32)
+        .map(|i| format!("{}", i * 7919 % 500).into_bytes())
+        .collect();
+
+    let mut art = cart::Art::default();
+    let mut single = cart::Art::default();
+    let mut model = std::collections::BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.clone(), i);
+        single.set(key.clone(), i);
+        model.insert(key.clone(), i);
+    }
+
+    let batch: Vec<&[u8]> = vec![b"7919", b"19", b"", b"1", b"19", b"419", b"x", b"41"];
+    let removed = art.remove_many(&batch);
+
+    let expected: Vec<Option<usize>> = batch
+        .iter()
+        .map(|key| single.remove_if(key, |_| true))
+        .collect();
+    assert_eq!(removed, expected);
+
+    let from_model: Vec<Option<usize>> = batch.iter().map(|key| model.remove(*key)).collect();
+    assert_eq!(removed, from_model);
+    assert!(removed.iter().any(|v| v.is_some()));
+
+    art.validate();
+
+    // a batch that empties whole subtrees, in reverse order
+    let rest: Vec<Vec<u8>> = model.keys().rev().step_by(2).cloned().collect();
+    let batch: Vec<&[u8]> = rest.iter().map(|k| &k[..]).collect();
+    let removed = art.remove_many(&batch);
+    let from_model: Vec<Option<usize>> = batch.iter().map(|key| model.remove(*key)).collect();
+    assert_eq!(removed, from_model);
+    assert!(removed.iter().all(|v| v.is_some()));
+
+    art.validate();
+    assert!(art.drain().eq(model.into_iter()));
+}
@@ -0,0 +1,69 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+fn check_remove_prefix(keys: &[&[u8]], prefix: &[u8]) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.to_vec(), i);
+        model.insert(key.to_vec(), i);
+    }
+
+    let before = model.len();
+    model.retain(|k, _| !k.starts_with(prefix));
+
+    assert_eq!(
+        art.remove_prefix(prefix),
+        before - model.len(),
+        "{:?}",
+        prefix
+    );
+    art.validate();
+    assert_eq!(art.len(), model.len());
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model), "{:?}", prefix);
+}
+
+#[test]
+fn test_remove_prefix() {
+    let keys: &[&[u8]] = &[
+        b"", b"a", b"ab", b"abc", b"abcdef", b"abcxyz", b"abd", b"b", b"bcd", b"bcde", b"c",
+    ];
+    let prefixes: &[&[u8]] = &[
+        b"", b"a", b"ab", b"abc", b"abcd", b"abcdef", b"abcdefg", b"abcx", b"abe", b"b", b"bc",
+        b"bcd", b"c", b"d",
+    ];
+    for prefix in prefixes {
+        check_remove_prefix(keys, prefix);
+    }
+
+    // a prefix ending inside a node prefix
+    check_remove_prefix(&[b"tenant1/a", b"tenant1/b", b"tenant2/a"], b"tenant1");
+    check_remove_prefix(&[b"tenant1/a", b"tenant1/b"], b"tenant1");
+    check_remove_prefix(&[b"tenant1/a", b"tenant1/b"], b"tenant2");
+}
+
+#[test]
+fn test_remove_prefix_drops_values() {
+    let shared = Arc::new(());
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(
+            format!("tenant{}/{}", i % 3, i).into_bytes(),
+            shared.clone(),
+        );
+    }
+
+    let removed = art.remove_prefix(b"tenant1/");
+    assert_eq!(removed, 333);
+    assert_eq!(Arc::strong_count(&shared), 1 + 1000 - 333);
+    assert_eq!(art.len(), 1000 - 333);
+    assert_eq!(art.iter_prefix(b"tenant1").count(), 0);
+    art.validate();
+
+    assert_eq!(art.remove_prefix(b"tenant"), 667);
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert!(art.is_empty());
+    assert_eq!(art.root_variant(), "Node4");
+}
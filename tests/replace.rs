@@ -0,0 +1,33 @@
+extern crate cart;
+
+#[test]
+fn test_replace_missing_key() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+
+    assert_eq!(art.replace(b"abd", 2), None);
+    assert_eq!(art.replace(b"ab", 2), None);
+    assert_eq!(art.replace(b"abcd", 2), None);
+
+    assert_eq!(art.get(b"abd"), None);
+    assert_eq!(art.get(b"ab"), None);
+    assert_eq!(art.get(b"abcd"), None);
+    assert_eq!(art.get(b"abc"), Some(&1));
+    assert_eq!(art.drain().count(), 1);
+}
+
+#[test]
+fn test_replace_present_key() {
+    let mut art = cart::Art::default();
+    art.set(b"abc".to_vec(), 1);
+    art.set(b"abd".to_vec(), 2);
+    art.set(vec![], 3);
+
+    assert_eq!(art.replace(b"abc", 10), Some(1));
+    assert_eq!(art.replace(b"abc", 11), Some(10));
+    assert_eq!(art.replace(b"", 12), Some(3));
+
+    assert_eq!(art.get(b"abc"), Some(&11));
+    assert_eq!(art.get(b"abd"), Some(&2));
+    assert_eq!(art.get(b""), Some(&12));
+}
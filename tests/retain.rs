@@ -0,0 +1,89 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[test]
+fn test_retain() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for i in 0..300usize {
+        let key = format!("user/{}", i).into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+
+    let mut visited = vec![];
+    art.retain(|k, v| {
+        visited.push(k.to_vec());
+        *v += 1;
+        *v % 3 != 0
+    });
+    assert!(visited.iter().eq(model.keys()));
+
+    model = model
+        .into_iter()
+        .map(|(k, v)| (k, v + 1))
+        .filter(|(_, v)| v % 3 != 0)
+        .collect();
+
+    art.validate();
+    assert_eq!(art.len(), model.len());
+    assert!(art.drain().eq(model));
+}
+
+#[test]
+fn test_retain_collapses_paths() {
+    let mut art = cart::Art::default();
+    art.set(b"abcdef".to_vec(), 1);
+    art.set(b"abcxyz".to_vec(), 2);
+    art.set(b"abc".to_vec(), 3);
+    art.set(b"b".to_vec(), 4);
+
+    // leaves a single key behind the root, which has to be
+    // merged into one path again
+    art.retain(|k, _| k == b"abcxyz");
+    art.validate();
+    assert_eq!(art.len(), 1);
+    assert_eq!(art.get(b"abcxyz"), Some(&2));
+    assert_eq!(art.get(b"abc"), None);
+
+    art.set(b"abcdef".to_vec(), 5);
+    assert_eq!(art.get(b"abcdef"), Some(&5));
+    art.validate();
+}
+
+#[test]
+fn test_retain_nothing() {
+    let shared = Arc::new(());
+    let mut art = cart::Art::default();
+    for i in 0..300u32 {
+        art.set(format!("{}", i).into_bytes(), shared.clone());
+    }
+
+    art.retain(|_, _| false);
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert!(art.is_empty());
+    assert_eq!(art.iter().count(), 0);
+    art.validate();
+
+    art.set(b"1".to_vec(), shared.clone());
+    assert!(art.contains_key(b"1"));
+}
+
+#[test]
+fn test_retain_everything() {
+    // a Node256 root with children of every smaller variant
+    let mut art = cart::Art::default();
+    for i in 0..=255u8 {
+        for j in 0..i % 64 {
+            art.set(vec![i, j], j);
+        }
+    }
+    let entries: Vec<(Vec<u8>, u8)> = art.iter().map(|(k, v)| (k, *v)).collect();
+    let stats = art.stats();
+
+    art.retain(|_, _| true);
+    assert_eq!(art.stats(), stats);
+    assert!(art.drain().eq(entries));
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "rand")]
+
+extern crate cart;
+extern crate rand;
+
+use std::collections::HashMap;
+
+use cart::{Art, ArtBuilder};
+use rand::{SeedableRng, XorShiftRng};
+
+#[test]
+fn test_sample_is_uniform_across_uneven_subtrees() {
+    let mut art: Art<u32, usize> = ArtBuilder::new().build_with_metadata();
+    // one key on its own next to a thousand below another byte
+    art.set(b"a".to_vec(), 0);
+    for i in 1..1000u32 {
+        art.set(format!("b{}", i).into_bytes(), i);
+    }
+    art.set(b"b".to_vec(), 1000);
+    art.update_counts();
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let samples = art.sample(&mut rng, 100_000);
+    assert_eq!(samples.len(), 100_000);
+
+    let mut hits: HashMap<Vec<u8>, usize> = HashMap::new();
+    for (key, value) in samples {
+        assert_eq!(art.get(&key), Some(value));
+        *hits.entry(key).or_insert(0) += 1;
+    }
+
+    // every entry is expected 100 times
+    assert_eq!(hits.len(), 1001);
+    assert!(hits.values().all(|n| *n > 40 && *n < 180));
+    assert!(hits[&b"a".to_vec()] < 180);
+}
+
+#[test]
+fn test_sample_with_stale_counts() {
+    let mut art: Art<u32, usize> = ArtBuilder::new().build_with_metadata();
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    assert!(art.sample(&mut rng, 10).is_empty());
+
+    for i in 0..100u32 {
+        art.set(format!("{}", i).into_bytes(), i);
+    }
+    // without counts every child weighs the same, entries are
+    // still found
+    for (key, value) in art.sample(&mut rng, 1000) {
+        assert_eq!(art.get(&key), Some(value));
+    }
+}
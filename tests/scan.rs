@@ -0,0 +1,55 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_scan_cumulative_sum() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..200u64 {
+        let key = format!("{}", i * 31 % 200).into_bytes();
+        art.set(key.clone(), i);
+        model.insert(key, i);
+    }
+
+    let mut sum = 0;
+    let expected: Vec<(Vec<u8>, u64)> = model
+        .into_iter()
+        .map(|(key, value)| {
+            sum += value;
+            (key, sum)
+        })
+        .collect();
+
+    assert_eq!(art.scan(0, |acc, _, value| acc + value), expected);
+}
+
+#[test]
+fn test_scan_sees_keys() {
+    let mut art = cart::Art::default();
+    art.set(b"ab".to_vec(), ());
+    art.set(b"a".to_vec(), ());
+    art.set(b"abcd".to_vec(), ());
+
+    let lengths = art.scan(vec![], |acc: &Vec<usize>, key, _| {
+        let mut acc = acc.clone();
+        acc.push(key.len());
+        acc
+    });
+
+    assert_eq!(
+        lengths,
+        vec![
+            (b"a".to_vec(), vec![1]),
+            (b"ab".to_vec(), vec![1, 2]),
+            (b"abcd".to_vec(), vec![1, 2, 4]),
+        ]
+    );
+}
+
+#[test]
+fn test_scan_empty() {
+    let art: cart::Art<u8> = cart::Art::default();
+    assert!(art.scan(0, |acc, _, _| *acc).is_empty());
+}
@@ -0,0 +1,78 @@
+extern crate cart;
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+fn assert_send<S: Send>() {}
+fn assert_sync<S: Sync>() {}
+
+#[test]
+fn test_send_sync_follow_the_values() {
+    assert_send::<cart::Art<String>>();
+    assert_sync::<cart::Art<String>>();
+    assert_send::<cart::IntoIter<String>>();
+    assert_send::<cart::Iter<'static, String>>();
+    assert_send::<Arc<Mutex<cart::Art<Vec<u8>>>>>();
+    assert_sync::<Arc<RwLock<cart::Art<Vec<u8>>>>>();
+}
+
+#[test]
+fn test_move_across_threads() {
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(format!("{}", i).into_bytes(), i.to_string());
+    }
+
+    let art = thread::spawn(move || {
+        art.remove(b"500");
+        art
+    })
+    .join()
+    .unwrap();
+    art.validate();
+    assert_eq!(art.len(), 999);
+
+    let values = thread::spawn(move || art.into_iter().map(|(_, v)| v).collect::<Vec<_>>())
+        .join()
+        .unwrap();
+    assert_eq!(values.len(), 999);
+}
+
+#[test]
+fn test_shared_behind_locks() {
+    let art = Arc::new(Mutex::new(cart::Art::default()));
+
+    let writers: Vec<_> = (0..4u32)
+        .map(|t| {
+            let art = art.clone();
+            thread::spawn(move || {
+                for i in 0..250u32 {
+                    let key = format!("{}/{}", t, i).into_bytes();
+                    art.lock().unwrap().set(key, i);
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let art = art.lock().unwrap();
+    art.validate();
+    assert_eq!(art.len(), 1000);
+    assert_eq!(art.count_prefix(b"3/"), 250);
+
+    // readers only need a shared borrow
+    let art = RwLock::new(art.clone());
+    thread::scope(|s| {
+        for t in 0..4u32 {
+            let art = &art;
+            s.spawn(move || {
+                let art = art.read().unwrap();
+                let prefix = format!("{}/", t).into_bytes();
+                assert_eq!(art.count_prefix(&prefix), 250);
+                assert_eq!(art.get(&format!("{}/7", t)), Some(&7));
+            });
+        }
+    });
+}
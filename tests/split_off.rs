@@ -0,0 +1,94 @@
+extern crate cart;
+extern crate rand;
+
+use std::collections::BTreeMap;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+fn check_split_off(keys: &[Vec<u8>], at: &[u8]) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        art.set(key.clone(), i);
+        model.insert(key.clone(), i);
+    }
+
+    let other = art.split_off(at);
+    let other_model = model.split_off(at);
+
+    art.validate();
+    other.validate();
+    assert_eq!(art.len(), model.len(), "{:?}", at);
+    assert_eq!(other.len(), other_model.len(), "{:?}", at);
+    assert!(art.iter().map(|(k, v)| (k, *v)).eq(model), "{:?}", at);
+    assert!(
+        other.iter().map(|(k, v)| (k, *v)).eq(other_model),
+        "{:?}",
+        at
+    );
+}
+
+#[test]
+fn test_split_off() {
+    let keys: Vec<Vec<u8>> = [
+        &b"abcdef"[..],
+        b"abcxyz",
+        b"abc",
+        b"ab",
+        b"",
+        b"b",
+        b"bcd",
+        b"c",
+    ]
+    .iter()
+    .map(|k| k.to_vec())
+    .collect();
+
+    let points: &[&[u8]] = &[
+        b"", b"a", b"ab", b"abc", b"abcd", b"abcdef", b"abcdeg", b"abcx", b"abd", b"b", b"bb",
+        b"bcd", b"bce", b"c", b"d",
+    ];
+    for at in points {
+        check_split_off(&keys, at);
+    }
+    check_split_off(&[], b"abc");
+}
+
+#[test]
+fn test_split_off_random() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+    for _ in 0..100 {
+        let n = rng.gen_range(0, 200);
+        let keys: Vec<Vec<u8>> = (0..n)
+            .map(|_| {
+                let len = rng.gen_range(0, 5);
+                (0..len).map(|_| rng.gen_range(0, 4)).collect()
+            })
+            .collect();
+        let len = rng.gen_range(0, 5);
+        let at: Vec<u8> = (0..len).map(|_| rng.gen_range(0, 4)).collect();
+
+        check_split_off(&keys, &at);
+    }
+}
+
+#[test]
+fn test_split_off_halves_stay_usable() {
+    let mut art = cart::Art::default();
+    for i in 0..1000u32 {
+        art.set(format!("{:04}", i).into_bytes(), i);
+    }
+
+    let mut upper = art.split_off(b"0500");
+    assert_eq!(art.len(), 500);
+    assert_eq!(upper.len(), 500);
+    assert_eq!(art.last_key_value(), Some((b"0499".to_vec(), &499)));
+    assert_eq!(upper.first_key_value(), Some((b"0500".to_vec(), &500)));
+
+    art.append(&mut upper);
+    assert_eq!(art.len(), 1000);
+    assert!(upper.is_empty());
+    art.validate();
+    assert!(art.iter().map(|(_, v)| *v).eq(0..1000));
+}
@@ -0,0 +1,58 @@
+extern crate cart;
+
+use cart::StrArt;
+
+#[test]
+fn test_str_art() {
+    let mut hosts = StrArt::new();
+    assert!(hosts.is_empty());
+    for (i, host) in [
+        "b.example.com",
+        "a.example.com",
+        "ünïcode.example",
+        "a.example.org",
+        "",
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(hosts.set(host, i), None);
+    }
+    assert_eq!(hosts.set("a.example.com", 10), Some(1));
+    assert_eq!(hosts.len(), 5);
+
+    assert_eq!(hosts.get("a.example.com"), Some(&10));
+    assert_eq!(hosts.get("c.example.com"), None);
+    *hosts.get_mut("").unwrap() += 100;
+    assert_eq!(hosts.get(""), Some(&104));
+
+    let keys: Vec<String> = hosts.iter().map(|(k, _)| k).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "",
+            "a.example.com",
+            "a.example.org",
+            "b.example.com",
+            "ünïcode.example"
+        ]
+    );
+
+    let found: Vec<(String, usize)> = hosts
+        .scan_prefix("a.example.")
+        .map(|(k, v)| (k, *v))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("a.example.com".to_string(), 10),
+            ("a.example.org".to_string(), 3)
+        ]
+    );
+    assert_eq!(hosts.scan_prefix("ü").count(), 1);
+    assert_eq!(hosts.scan_prefix("x").count(), 0);
+
+    assert_eq!(hosts.remove("b.example.com"), Some(0));
+    assert_eq!(hosts.remove("b.example.com"), None);
+    assert_eq!(hosts.into_art().len(), 4);
+}
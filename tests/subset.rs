@@ -0,0 +1,51 @@
+extern crate cart;
+
+use cart::Art;
+
+fn art(keys: &[&[u8]]) -> Art<usize> {
+    let mut art = Art::default();
+    for key in keys {
+        art.set(key.to_vec(), key.len());
+    }
+    art
+}
+
+#[test]
+fn test_disjoint() {
+    let a = art(&[b"abc", b"abd"]);
+    let b = art(&[b"xyz", b"b"]);
+
+    assert!(!a.is_subset(&b));
+    assert!(!b.is_subset(&a));
+}
+
+#[test]
+fn test_overlapping() {
+    let a = art(&[b"abc", b"abd", b"q"]);
+    let b = art(&[b"abc", b"abd", b"x"]);
+
+    assert!(!a.is_subset(&b));
+    assert!(!b.is_subset(&a));
+}
+
+#[test]
+fn test_strict_subset() {
+    let a = art(&[b"abc", b"x"]);
+    let b = art(&[b"", b"a", b"abc", b"abd", b"b", b"x", b"xyz"]);
+
+    assert!(a.is_subset(&b));
+    assert!(!b.is_subset(&a));
+    assert!(a.is_subset(&a));
+    assert!(Art::default().is_subset(&a));
+    assert!(!a.is_subset(&Art::default()));
+}
+
+#[test]
+fn test_differing_values() {
+    let a = art(&[b"abc", b"x"]);
+    let mut b = art(&[b"abc", b"x", b"y"]);
+    assert!(a.is_subset(&b));
+
+    b.set(b"x".to_vec(), 100);
+    assert!(!a.is_subset(&b));
+}
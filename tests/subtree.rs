@@ -0,0 +1,69 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+fn populated() -> (cart::Art<u32>, BTreeMap<Vec<u8>, u32>) {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for tenant in &["acme", "acme-eu", "globex"] {
+        for i in 0..50u32 {
+            let key = format!("{}/{}", tenant, i).into_bytes();
+            art.set(key.clone(), i);
+            model.insert(key, i);
+        }
+    }
+    art.set(b"acme/".to_vec(), 100);
+    model.insert(b"acme/".to_vec(), 100);
+    (art, model)
+}
+
+#[test]
+fn test_subtree_is_relative_to_prefix() {
+    let (art, model) = populated();
+
+    for prefix in &[
+        &b""[..],
+        b"a",
+        b"acme",
+        b"acme/",
+        b"acme/4",
+        b"acme/49",
+        b"globex/",
+        b"initech/",
+    ] {
+        let view = art.subtree(prefix);
+        assert_eq!(view.prefix(), *prefix);
+
+        let expected: Vec<(Vec<u8>, u32)> = model
+            .iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k[prefix.len()..].to_vec(), *v))
+            .collect();
+        let viewed: Vec<(Vec<u8>, u32)> = view.iter().map(|(k, v)| (k, *v)).collect();
+        assert_eq!(viewed, expected, "{:?}", prefix);
+        assert_eq!(view.len(), expected.len());
+        assert_eq!(view.is_empty(), expected.is_empty());
+
+        for (key, value) in &expected {
+            assert_eq!(view.get(key), Some(value));
+        }
+    }
+
+    let acme = art.subtree(b"acme/");
+    assert_eq!(acme.get(b""), Some(&100));
+    assert_eq!(acme.get(b"7"), Some(&7));
+    assert_eq!(acme.get(b"50"), None);
+    // keys of other namespaces can't be reached from the view
+    assert_eq!(acme.get(b"../globex/7"), None);
+    assert_eq!(art.subtree(b"acme").get(b"-eu/3"), Some(&3));
+}
+
+#[test]
+fn test_subtree_of_empty_tree() {
+    let art: cart::Art<u32> = cart::Art::default();
+    let view = art.subtree(b"");
+    assert!(view.is_empty());
+    assert_eq!(view.len(), 0);
+    assert_eq!(view.iter().count(), 0);
+    assert_eq!(view.get(b""), None);
+}
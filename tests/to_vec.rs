@@ -0,0 +1,34 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn test_to_vec_matches_model() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    for i in 0..500u32 {
+        let key = format!("{}", i * 7919 % 800).into_bytes();
+        art.set(key.clone(), i.to_string());
+        model.insert(key, i.to_string());
+    }
+    art.set(vec![], "empty".to_string());
+    model.insert(vec![], "empty".to_string());
+
+    let entries = art.to_vec();
+    assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(entries, model.into_iter().collect::<Vec<_>>());
+    assert_eq!(entries.capacity(), entries.len());
+
+    // the tree is left alone
+    assert_eq!(art.get(b""), Some(&"empty".to_string()));
+    assert_eq!(art.drain().count(), entries.len());
+}
+
+#[test]
+fn test_to_vec_empty() {
+    let art: cart::Art<u8> = cart::Art::default();
+    let entries = art.to_vec();
+    assert!(entries.is_empty());
+    assert_eq!(entries.capacity(), 0);
+}
@@ -0,0 +1,69 @@
+extern crate cart;
+extern crate rand;
+
+use std::collections::BTreeMap;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+#[test]
+fn test_top_k_in_prefix() {
+    let mut art = cart::Art::default();
+    for (word, hits) in &[
+        ("car", 40u32),
+        ("card", 7),
+        ("care", 40),
+        ("cargo", 12),
+        ("cart", 90),
+        ("cat", 300),
+        ("dog", 500),
+    ] {
+        art.set(word.as_bytes().to_vec(), *hits);
+    }
+
+    let top = art.top_k_in_prefix(b"car", 3, |_, hits| *hits);
+    assert_eq!(
+        top,
+        vec![
+            (b"cart".to_vec(), &90),
+            (b"car".to_vec(), &40),
+            (b"care".to_vec(), &40),
+        ]
+    );
+
+    // shorter keys first
+    let top = art.top_k_in_prefix(b"ca", 2, |key, _| std::cmp::Reverse(key.len()));
+    assert_eq!(top, vec![(b"car".to_vec(), &40), (b"cat".to_vec(), &300)]);
+
+    assert_eq!(art.top_k_in_prefix(b"car", 0, |_, hits| *hits), vec![]);
+    assert_eq!(art.top_k_in_prefix(b"cz", 3, |_, hits| *hits), vec![]);
+    assert_eq!(art.top_k_in_prefix(b"", 100, |_, hits| *hits).len(), 7);
+}
+
+#[test]
+fn test_top_k_in_prefix_matches_model() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+    for _ in 0..2000 {
+        let len = rng.gen_range(0, 6);
+        let key: Vec<u8> = (0..len).map(|_| rng.gen_range(0, 4)).collect();
+        let value = rng.gen_range(0, 50u32);
+        art.set(key.clone(), value);
+        model.insert(key, value);
+    }
+
+    for prefix in &[&b""[..], &[0], &[1, 2], &[3, 3, 3], &[2, 2, 2, 2, 2, 2]] {
+        for &k in &[1, 5, 50, 5000] {
+            let mut expected: Vec<(Vec<u8>, &u32)> = model
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value))
+                .collect();
+            // stable, so equal values stay in key order
+            expected.sort_by(|a, b| b.1.cmp(a.1));
+            expected.truncate(k);
+
+            assert_eq!(art.top_k_in_prefix(prefix, k, |_, value| *value), expected);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+extern crate cart;
+
+use cart::key_encoding::CompositeKey;
+use cart::TypedArt;
+
+#[test]
+fn test_typed_art() {
+    let mut art = TypedArt::new();
+    for id in &[300u64, 2, 70_000, 1 << 40, 0] {
+        assert_eq!(art.set(*id, id.to_string()), None);
+    }
+    assert_eq!(art.set(2, "two".to_string()), Some("2".to_string()));
+    assert_eq!(art.len(), 5);
+
+    assert_eq!(art.get(&300).map(|s| &s[..]), Some("300"));
+    assert_eq!(art.get(&301), None);
+    art.get_mut(&0).unwrap().push('!');
+    assert_eq!(art.remove(&70_000).as_ref().map(|s| &s[..]), Some("70000"));
+
+    let all: Vec<(u64, &str)> = art.iter().map(|(k, v)| (k, &v[..])).collect();
+    assert_eq!(
+        all,
+        vec![
+            (0, "0!"),
+            (2, "two"),
+            (300, "300"),
+            (1 << 40, "1099511627776")
+        ]
+    );
+
+    let keys: Vec<u64> = art.range(2..=300).map(|(k, _)| k).collect();
+    assert_eq!(keys, vec![2, 300]);
+    let keys: Vec<u64> = art.range(..300).rev().map(|(k, _)| k).collect();
+    assert_eq!(keys, vec![2, 0]);
+    assert_eq!(art.range(301..).count(), 1);
+}
+
+#[test]
+fn test_typed_art_signed_and_float_ranges() {
+    let mut temperatures = TypedArt::new();
+    for (i, t) in [3.5f64, -10.25, 0.0, -0.5, 21.0].iter().enumerate() {
+        temperatures.set(*t, i);
+    }
+    let below_zero: Vec<f64> = temperatures.range(..0.0).map(|(k, _)| k).collect();
+    assert_eq!(below_zero, vec![-10.25, -0.5]);
+
+    let mut offsets = TypedArt::new();
+    for i in -5i32..5 {
+        offsets.set(i, ());
+    }
+    let keys: Vec<i32> = offsets.range(-2..2).map(|(k, _)| k).collect();
+    assert_eq!(keys, vec![-2, -1, 0, 1]);
+}
+
+#[test]
+fn test_typed_art_prefixes() {
+    let mut words = TypedArt::new();
+    for word in &["car", "cart", "cat", "dog"] {
+        words.set(word.to_string(), word.len());
+    }
+    let found: Vec<String> = words
+        .iter_prefix(&"car".to_string())
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(found, vec!["car", "cart"]);
+
+    let mut rows = TypedArt::new();
+    for tenant in 0..3 {
+        for id in 0..4 {
+            rows.set(CompositeKey::new().push_u64(tenant).push_i64(id), ());
+        }
+    }
+    let ids: Vec<i64> = rows
+        .iter_prefix(&CompositeKey::new().push_u64(1))
+        .map(|(key, _)| {
+            let mut components = CompositeKey::decode(key.as_bytes());
+            components.next_u64();
+            components.next_i64().unwrap()
+        })
+        .collect();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+    assert_eq!(
+        rows.as_art()
+            .count_prefix(CompositeKey::new().push_u64(2).as_bytes()),
+        4
+    );
+}
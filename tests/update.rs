@@ -0,0 +1,109 @@
+extern crate cart;
+
+use std::collections::BTreeMap;
+
+// the same transition as `Art::update`, expressed on a BTreeMap
+fn model_update<F>(model: &mut BTreeMap<Vec<u8>, u32>, k: Vec<u8>, f: F)
+where
+    F: FnOnce(Option<u32>) -> Option<u32>,
+{
+    let old = model.remove(&k);
+    if let Some(new) = f(old) {
+        model.insert(k, new);
+    }
+}
+
+#[test]
+fn test_update_transitions() {
+    let mut art = cart::Art::default();
+
+    // insert
+    art.update(b"abc".to_vec(), |old| {
+        assert_eq!(old, None);
+        Some(1)
+    });
+    assert_eq!(art.get(b"abc"), Some(&1));
+
+    // modify
+    art.update(b"abc".to_vec(), |old| old.map(|v| v + 1));
+    assert_eq!(art.get(b"abc"), Some(&2));
+
+    // delete
+    art.update(b"abc".to_vec(), |old| {
+        assert_eq!(old, Some(2));
+        None
+    });
+    assert_eq!(art.get(b"abc"), None);
+
+    // absent and staying absent
+    art.update(b"abd".to_vec(), |old| old);
+    assert_eq!(art.get(b"abd"), None);
+    assert_eq!(art.drain().count(), 0);
+}
+
+#[test]
+fn test_update_matches_model() {
+    let mut art = cart::Art::default();
+    let mut model = BTreeMap::new();
+
+    // a counter that wraps around to removal at 3
+    let step = |old: Option<u32>| match old {
+        None => Some(0),
+        Some(2) => None,
+        Some(v) => Some(v + 1),
+    };
+
+    for i in 0..2000u32 {
+        let x = i.wrapping_mul(2_654_435_761) >> 24;
+        let key = vec![(x % 4) as u8, (x % 13) as u8, (x % 7) as u8];
+        art.update(key.clone(), step);
+        model_update(&mut model, key, step);
+    }
+
+    for a in 0..4u8 {
+        for b in 0..13u8 {
+            for c in 0..7u8 {
+                let key = [a, b, c];
+                assert_eq!(art.get(&key), model.get(&key[..]));
+            }
+        }
+    }
+
+    let drained: Vec<(Vec<u8>, u32)> = art.drain().collect();
+    let expected: Vec<(Vec<u8>, u32)> = model.into_iter().collect();
+    assert_eq!(drained, expected);
+}
+
+#[test]
+fn test_fetch_and_update() {
+    let mut art = cart::Art::default();
+
+    // a merge operator appending to the stored list
+    let merge = |item: u32| {
+        move |old: Option<Vec<u32>>| {
+            let mut list = old.unwrap_or_default();
+            list.push(item);
+            Some(list)
+        }
+    };
+
+    assert_eq!(
+        art.fetch_and_update(b"abcdef", merge(1)),
+        Some(&mut vec![1])
+    );
+    assert_eq!(art.fetch_and_update(b"abc", merge(2)), Some(&mut vec![2]));
+    assert_eq!(
+        art.fetch_and_update(b"abcdef", merge(3)),
+        Some(&mut vec![1, 3])
+    );
+    assert_eq!(art.len(), 2);
+
+    assert_eq!(art.fetch_and_update(b"abc", |_| None), None);
+    assert_eq!(art.get(b"abc"), None);
+    assert_eq!(art.fetch_and_update(b"xyz", |_| None), None);
+    assert_eq!(art.len(), 1);
+
+    art.fetch_and_update(b"abcdef", |old| old).unwrap().push(5);
+    assert_eq!(art.get(b"abcdef"), Some(&vec![1, 3, 5]));
+    art.validate();
+}
@@ -0,0 +1,38 @@
+extern crate cart;
+
+#[test]
+fn test_root_growth() {
+    let mut art = cart::Art::default();
+    assert_eq!(art.root_variant(), "Node4");
+
+    for byte in 0..=255u8 {
+        art.set(vec![byte], byte);
+
+        let children = byte as usize + 1;
+        let expected = match children {
+            0..=4 => "Node4",
+            5..=16 => "Node16",
+            17..=48 => "Node48",
+            _ => "Node256",
+        };
+        assert_eq!(art.root_variant(), expected, "{} children", children);
+    }
+
+    for byte in 0..=255u8 {
+        assert_eq!(art.get(&[byte]), Some(&byte));
+    }
+    art.validate();
+}
+
+#[test]
+fn test_variant_name() {
+    let mut art = cart::Art::default();
+    for byte in 0..20u8 {
+        art.set(vec![1, byte], byte);
+    }
+    art.set(vec![2], 0);
+
+    // the root has two children, the one at byte 1 has twenty
+    assert_eq!(art.root_variant(), "Node4");
+    assert_eq!(art.stats().node48, 1);
+}
@@ -0,0 +1,63 @@
+extern crate cart;
+
+use std::ops::ControlFlow;
+
+fn populated() -> cart::Art<u32> {
+    let mut art = cart::Art::default();
+    for i in 0..500u32 {
+        art.set(format!("orders/{}/{}", i % 7, i).into_bytes(), i);
+    }
+    art.set(b"orders".to_vec(), 0);
+    art
+}
+
+#[test]
+fn test_walk_visits_prefix_in_order() {
+    let art = populated();
+
+    for prefix in &[
+        &b""[..],
+        b"orders",
+        b"orders/",
+        b"orders/3",
+        b"orders/3/1",
+        b"x",
+    ] {
+        let mut walked = vec![];
+        let broke: Option<()> = art.walk(prefix, |key, value| {
+            walked.push((key.to_vec(), *value));
+            ControlFlow::Continue(())
+        });
+        assert_eq!(broke, None);
+
+        let expected: Vec<(Vec<u8>, u32)> = art.iter_prefix(prefix).map(|(k, v)| (k, *v)).collect();
+        assert_eq!(walked, expected, "{:?}", prefix);
+    }
+}
+
+#[test]
+fn test_walk_stops_early() {
+    let art = populated();
+
+    let mut visited = 0;
+    let found = art.walk(b"orders/4/", |key, value| {
+        visited += 1;
+        if *value > 100 {
+            ControlFlow::Break(key.to_vec())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+
+    let expected = art
+        .iter_prefix(b"orders/4/")
+        .position(|(_, v)| *v > 100)
+        .unwrap();
+    assert_eq!(visited, expected + 1);
+    let found = found.unwrap();
+    assert!(found.starts_with(b"orders/4/"));
+    assert!(art.get(&found).unwrap() > &100);
+
+    let empty: cart::Art<u32> = cart::Art::default();
+    assert_eq!(empty.walk(b"", |_, _| ControlFlow::Break(())), None);
+}